@@ -0,0 +1,285 @@
+// Montos de dinero en punto fijo: se guardan en centavos enteros para que las
+// sumas y los porcentajes no arrastren errores de redondeo de f64.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Moneda {
+    Ars,
+    Usd,
+    Eur,
+}
+
+// el nombre de cada moneda es su codigo ISO 4217
+impl ConNombre for Moneda {
+    const VARIANTES: &'static [(Moneda, &'static str)] = &[
+        (Moneda::Ars, "ARS"),
+        (Moneda::Usd, "USD"),
+        (Moneda::Eur, "EUR"),
+    ];
+}
+
+impl Moneda {
+    pub fn codigo(&self) -> &str {
+        self.nombre()
+    }
+}
+
+impl fmt::Display for Moneda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.codigo())
+    }
+}
+
+impl FromStr for Moneda {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Moneda::desde_nombre(texto)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redondeo {
+    MitadHaciaArriba, // el medio centavo se aleja del cero
+    Bancario,         // el medio centavo va al par mas cercano
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorDinero {
+    MonedasDistintas,
+    Desbordamiento,
+}
+
+// porcentaje con dos decimales: 1050 centesimas representan 10,50 %
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Porcentaje {
+    centesimas: i64,
+}
+
+impl Porcentaje {
+    // pensado para literales: entra en panico si el porcentaje no cabe en
+    // centesimas; con valores que vienen de afuera usar checked_new
+    pub fn new(porcentaje: i64) -> Porcentaje {
+        Porcentaje::checked_new(porcentaje).expect("porcentaje fuera de rango")
+    }
+
+    pub fn checked_new(porcentaje: i64) -> Result<Porcentaje, ErrorDinero> {
+        let centesimas = porcentaje.checked_mul(100).ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Porcentaje { centesimas })
+    }
+
+    pub fn con_decimales(centesimas: i64) -> Porcentaje {
+        Porcentaje { centesimas }
+    }
+
+    pub fn centesimas(&self) -> i64 {
+        self.centesimas
+    }
+}
+
+impl fmt::Display for Porcentaje {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let signo = if self.centesimas < 0 { "-" } else { "" };
+        let absoluto = self.centesimas.unsigned_abs();
+        write!(f, "{}{}.{:02}%", signo, absoluto / 100, absoluto % 100)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dinero {
+    centavos: i64,
+    moneda: Moneda,
+}
+
+impl Dinero {
+    pub fn new(centavos: i64, moneda: Moneda) -> Dinero {
+        Dinero { centavos, moneda }
+    }
+
+    // pensado para literales: entra en panico si el monto no cabe en centavos;
+    // con valores que vienen de afuera usar checked_desde_unidades
+    pub fn desde_unidades(unidades: i64, moneda: Moneda) -> Dinero {
+        Dinero::checked_desde_unidades(unidades, moneda).expect("monto fuera de rango")
+    }
+
+    pub fn checked_desde_unidades(unidades: i64, moneda: Moneda) -> Result<Dinero, ErrorDinero> {
+        let centavos = unidades.checked_mul(100).ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, moneda))
+    }
+
+    pub fn cero(moneda: Moneda) -> Dinero {
+        Dinero::new(0, moneda)
+    }
+
+    pub fn centavos(&self) -> i64 {
+        self.centavos
+    }
+
+    pub fn moneda(&self) -> Moneda {
+        self.moneda
+    }
+
+    pub fn es_negativo(&self) -> bool {
+        self.centavos < 0
+    }
+
+    pub fn es_cero(&self) -> bool {
+        self.centavos == 0
+    }
+
+    pub fn negar(&self) -> Result<Dinero, ErrorDinero> {
+        let centavos = self.centavos.checked_neg().ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, self.moneda))
+    }
+
+    fn misma_moneda(&self, otro: &Dinero) -> Result<(), ErrorDinero> {
+        if self.moneda == otro.moneda {
+            Ok(())
+        } else {
+            Err(ErrorDinero::MonedasDistintas)
+        }
+    }
+
+    pub fn sumar(&self, otro: &Dinero) -> Result<Dinero, ErrorDinero> {
+        self.misma_moneda(otro)?;
+        let centavos = self.centavos.checked_add(otro.centavos).ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, self.moneda))
+    }
+
+    pub fn restar(&self, otro: &Dinero) -> Result<Dinero, ErrorDinero> {
+        self.misma_moneda(otro)?;
+        let centavos = self.centavos.checked_sub(otro.centavos).ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, self.moneda))
+    }
+
+    pub fn multiplicar(&self, cantidad: i64) -> Result<Dinero, ErrorDinero> {
+        let centavos = self.centavos.checked_mul(cantidad).ok_or(ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, self.moneda))
+    }
+
+    // el monto que representa el porcentaje, redondeado al centavo
+    pub fn porcentaje(&self, porcentaje: Porcentaje, redondeo: Redondeo) -> Result<Dinero, ErrorDinero> {
+        let producto = self.centavos as i128 * porcentaje.centesimas as i128;
+        let centavos = dividir_redondeando(producto, 10_000, redondeo);
+        let centavos = i64::try_from(centavos).map_err(|_| ErrorDinero::Desbordamiento)?;
+        Ok(Dinero::new(centavos, self.moneda))
+    }
+
+    pub fn aplicar_recargo(&self, porcentaje: Porcentaje, redondeo: Redondeo) -> Result<Dinero, ErrorDinero> {
+        self.sumar(&self.porcentaje(porcentaje, redondeo)?)
+    }
+
+    pub fn aplicar_descuento(&self, porcentaje: Porcentaje, redondeo: Redondeo) -> Result<Dinero, ErrorDinero> {
+        self.restar(&self.porcentaje(porcentaje, redondeo)?)
+    }
+}
+
+impl fmt::Display for Dinero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let signo = if self.centavos < 0 { "-" } else { "" };
+        let absoluto = self.centavos.unsigned_abs();
+        write!(f, "{} {}{}.{:02}", self.moneda.codigo(), signo, absoluto / 100, absoluto % 100)
+    }
+}
+
+fn dividir_redondeando(numerador: i128, denominador: i128, redondeo: Redondeo) -> i128 {
+    let cociente = numerador / denominador;
+    let resto = (numerador % denominador).abs();
+    let signo = if numerador < 0 { -1 } else { 1 };
+    let doble_resto = resto * 2;
+    let redondear_lejos_del_cero = match redondeo {
+        Redondeo::MitadHaciaArriba => doble_resto >= denominador,
+        Redondeo::Bancario => doble_resto > denominador || (doble_resto == denominador && cociente % 2 != 0),
+    };
+    if redondear_lejos_del_cero {
+        cociente + signo
+    } else {
+        cociente
+    }
+}
+
+
+// montos en pesos para las pruebas de los demas modulos
+#[cfg(test)]
+pub fn pesos(unidades: i64) -> Dinero {
+    Dinero::desde_unidades(unidades, Moneda::Ars)
+}
+
+#[test]
+fn test_moneda_texto_ida_y_vuelta() {
+    comprobar_variantes::<Moneda>();
+    assert_eq!("usd".parse::<Moneda>(), Ok(Moneda::Usd));
+    assert_eq!("BRL".parse::<Moneda>(), Err(NombreDesconocido("BRL".to_string())));
+}
+
+#[test]
+fn test_sumar_y_restar() {
+    let a = Dinero::new(1050, Moneda::Ars);
+    let b = Dinero::desde_unidades(2, Moneda::Ars);
+    assert_eq!(a.sumar(&b), Ok(Dinero::new(1250, Moneda::Ars)));
+    assert_eq!(b.restar(&a), Ok(Dinero::new(-850, Moneda::Ars)));
+    assert_eq!(a.multiplicar(3), Ok(Dinero::new(3150, Moneda::Ars)));
+    assert_eq!(a.sumar(&Dinero::new(100, Moneda::Usd)), Err(ErrorDinero::MonedasDistintas));
+    assert_eq!(Dinero::new(i64::MAX, Moneda::Ars).sumar(&a), Err(ErrorDinero::Desbordamiento));
+    assert_eq!(Dinero::new(i64::MAX, Moneda::Ars).multiplicar(2), Err(ErrorDinero::Desbordamiento));
+}
+
+#[test]
+fn test_construir_sin_desbordar() {
+    assert_eq!(Dinero::checked_desde_unidades(12, Moneda::Usd), Ok(Dinero::new(1200, Moneda::Usd)));
+    assert_eq!(Dinero::checked_desde_unidades(i64::MAX / 10, Moneda::Ars), Err(ErrorDinero::Desbordamiento));
+    assert_eq!(Porcentaje::checked_new(-15), Ok(Porcentaje::con_decimales(-1500)));
+    assert_eq!(Porcentaje::checked_new(i64::MIN), Err(ErrorDinero::Desbordamiento));
+}
+
+#[test]
+#[should_panic(expected = "monto fuera de rango")]
+fn test_desde_unidades_desbordado() {
+    Dinero::desde_unidades(i64::MAX, Moneda::Ars);
+}
+
+#[test]
+fn test_sumas_exactas() {
+    // con f64, 0.1 + 0.2 != 0.3; en centavos la suma es exacta
+    let diez = Dinero::new(10, Moneda::Ars);
+    let veinte = Dinero::new(20, Moneda::Ars);
+    assert_eq!(diez.sumar(&veinte), Ok(Dinero::new(30, Moneda::Ars)));
+}
+
+#[test]
+fn test_redondeo() {
+    // 2,5 centavos
+    let monto = Dinero::new(25, Moneda::Ars);
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::MitadHaciaArriba), Ok(Dinero::new(3, Moneda::Ars)));
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::Bancario), Ok(Dinero::new(2, Moneda::Ars)));
+    // 3,5 centavos
+    let monto = Dinero::new(35, Moneda::Ars);
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::MitadHaciaArriba), Ok(Dinero::new(4, Moneda::Ars)));
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::Bancario), Ok(Dinero::new(4, Moneda::Ars)));
+    // -2,5 centavos
+    let monto = Dinero::new(-25, Moneda::Ars);
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::MitadHaciaArriba), Ok(Dinero::new(-3, Moneda::Ars)));
+    assert_eq!(monto.porcentaje(Porcentaje::new(10), Redondeo::Bancario), Ok(Dinero::new(-2, Moneda::Ars)));
+}
+
+#[test]
+fn test_porcentajes() {
+    let precio = Dinero::desde_unidades(1000, Moneda::Ars);
+    assert_eq!(precio.porcentaje(Porcentaje::con_decimales(1050), Redondeo::Bancario), Ok(Dinero::desde_unidades(105, Moneda::Ars)));
+    assert_eq!(precio.aplicar_recargo(Porcentaje::new(21), Redondeo::Bancario), Ok(Dinero::desde_unidades(1210, Moneda::Ars)));
+    assert_eq!(precio.aplicar_descuento(Porcentaje::new(15), Redondeo::Bancario), Ok(Dinero::desde_unidades(850, Moneda::Ars)));
+    assert_eq!(Dinero::new(i64::MAX, Moneda::Ars).aplicar_recargo(Porcentaje::new(200), Redondeo::Bancario), Err(ErrorDinero::Desbordamiento));
+}
+
+#[test]
+fn test_mostrar() {
+    assert_eq!(Dinero::new(123456, Moneda::Usd).to_string(), "USD 1234.56");
+    assert_eq!(Dinero::new(-5, Moneda::Ars).to_string(), "ARS -0.05");
+    assert_eq!(Porcentaje::con_decimales(1050).to_string(), "10.50%");
+}
@@ -1,6 +1,8 @@
+mod dinero;
 mod tp_2;
 mod tp_3;
 mod tp_4;
+mod variantes;
 
 fn main() {
 /*
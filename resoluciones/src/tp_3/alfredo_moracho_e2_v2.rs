@@ -1,5 +1,14 @@
 // Alfredo Moracho, legajo 15080/8, discord: alfrevp
 
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Color {
     Rojo,
     Verde,
@@ -9,6 +18,32 @@ enum Color {
     Negro,
 }
 
+impl ConNombre for Color {
+    const VARIANTES: &'static [(Color, &'static str)] = &[
+        (Color::Rojo, "rojo"),
+        (Color::Verde, "verde"),
+        (Color::Azul, "azul"),
+        (Color::Amarillo, "amarillo"),
+        (Color::Blanco, "blanco"),
+        (Color::Negro, "negro"),
+    ];
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for Color {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Color::desde_nombre(texto)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 struct Auto {
     marca: String,
     modelo: String,
@@ -24,7 +59,7 @@ impl Auto {
 
     fn calcular_precio(&self) -> f64 {
         let mut total = self.precio_bruto;
-        if self.color_comparable() == "rojo" || self.color_comparable() == "amarillo" || self.color_comparable() == "azul" {
+        if matches!(self.color, Color::Rojo | Color::Amarillo | Color::Azul) {
             let recargo_color = self.precio_bruto * 0.25;
             total += recargo_color;  
         } else {
@@ -41,25 +76,6 @@ impl Auto {
         }
         total
     }
-
-    fn es_igual(&self, auto:&Auto) -> bool {
-        self.marca == auto.marca &&
-        self.modelo == auto.modelo &&
-        self.anio == auto.anio &&
-        self.precio_bruto == auto.precio_bruto &&
-        self.color_comparable() == auto.color_comparable()
-    }
-
-    fn color_comparable(&self) -> &str {
-        match self.color {
-            Color::Amarillo => "amarillo",
-            Color::Azul => "azul",
-            Color::Blanco => "blanco",
-            Color::Negro => "negro",
-            Color::Rojo => "rojo",
-            Color::Verde => "verde",
-        }
-    }
 }
 
 struct ConcesionarioAuto {
@@ -88,13 +104,13 @@ impl ConcesionarioAuto {
     }
 
     fn eliminar_auto(&mut self, auto:Auto) {
-        if let Some(pos) = self.lista_autos.iter().position(|elem| elem.es_igual(&auto)) { 
+        if let Some(pos) = self.lista_autos.iter().position(|elem| *elem == auto) { 
             self.lista_autos.remove(pos);
         } 
     }
 
     fn buscar_auto(&self, auto:&Auto) -> Option<&Auto> {
-        self.lista_autos.iter().find(|elem| elem.es_igual(auto))
+        self.lista_autos.iter().find(|elem| *elem == auto)
     }
 
     // DESDE ACA LO CORRESPONDIENTE AL E2-T2 :
@@ -104,7 +120,7 @@ impl ConcesionarioAuto {
         let mut listado: Vec<ReporteAuto> = Vec::new();
         for i in &self.lista_autos {
             if i.marca == marca {
-                let reporte_auto = ReporteAuto::new(i.modelo.clone(), i.anio, i.color_comparable().to_string(), i.calcular_precio());
+                let reporte_auto = ReporteAuto::new(i.modelo.clone(), i.anio, i.color.to_string(), i.calcular_precio());
                 listado.push(reporte_auto);
                 cant += 1;
             }
@@ -112,6 +128,129 @@ impl ConcesionarioAuto {
         let reporte_general = ReporteGeneral::new(marca, listado, cant);
         reporte_general
     }
+
+    fn reporte_agrupado(&self, dimension: Dimension) -> ReporteAgrupado {
+        let mut precios_por_grupo: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for auto in &self.lista_autos {
+            precios_por_grupo.entry(dimension.clave_de(auto)).or_default().push(auto.calcular_precio());
+        }
+        let grupos = precios_por_grupo.into_iter().map(|(clave, precios)| GrupoReporte::new(clave, &precios)).collect();
+        ReporteAgrupado { dimension, grupos }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+    Marca,
+    Modelo,
+    Color,
+    Decada,
+}
+
+impl Dimension {
+    fn nombre(&self) -> &str {
+        match self {
+            Dimension::Marca => "marca",
+            Dimension::Modelo => "modelo",
+            Dimension::Color => "color",
+            Dimension::Decada => "decada",
+        }
+    }
+
+    fn clave_de(&self, auto: &Auto) -> String {
+        match self {
+            Dimension::Marca => auto.marca.clone(),
+            Dimension::Modelo => auto.modelo.clone(),
+            Dimension::Color => auto.color.to_string(),
+            Dimension::Decada => {
+                let inicio = auto.anio - auto.anio % 10;
+                format!("{}-{}", inicio, inicio + 9)
+            }
+        }
+    }
+}
+
+// los precios son los finales, con recargos y descuentos aplicados
+struct GrupoReporte {
+    clave: String,
+    cantidad: usize,
+    precio_minimo: f64,
+    precio_promedio: f64,
+    precio_maximo: f64,
+    valor_total: f64,
+}
+
+impl GrupoReporte {
+    fn new(clave: String, precios: &[f64]) -> GrupoReporte {
+        let valor_total: f64 = precios.iter().sum();
+        GrupoReporte {
+            clave,
+            cantidad: precios.len(),
+            precio_minimo: precios.iter().copied().fold(f64::INFINITY, f64::min),
+            precio_promedio: valor_total / precios.len() as f64,
+            precio_maximo: precios.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            valor_total,
+        }
+    }
+}
+
+struct ReporteAgrupado {
+    dimension: Dimension,
+    grupos: Vec<GrupoReporte>, // ordenados por clave
+}
+
+impl ReporteAgrupado {
+    fn total_autos(&self) -> usize {
+        self.grupos.iter().map(|grupo| grupo.cantidad).sum()
+    }
+
+    fn valor_total(&self) -> f64 {
+        self.grupos.iter().fold(0.0, |total, grupo| total + grupo.valor_total)
+    }
+
+    fn a_texto(&self) -> String {
+        let mut texto = String::new();
+        let _ = writeln!(texto, "{:<16}{:>10}{:>16}{:>16}{:>16}{:>18}", self.dimension.nombre(), "cantidad", "minimo", "promedio", "maximo", "valor total");
+        for grupo in &self.grupos {
+            let _ = writeln!(texto, "{:<16}{:>10}{:>16.2}{:>16.2}{:>16.2}{:>18.2}",
+                grupo.clave, grupo.cantidad, grupo.precio_minimo, grupo.precio_promedio, grupo.precio_maximo, grupo.valor_total);
+        }
+        let _ = write!(texto, "{:<16}{:>10}{:>66.2}", "total", self.total_autos(), self.valor_total());
+        texto
+    }
+
+    fn a_csv(&self) -> String {
+        let mut csv = format!("{},cantidad,minimo,promedio,maximo,valor_total\n", self.dimension.nombre());
+        for grupo in &self.grupos {
+            let clave = if grupo.clave.contains([',', '"', '\n', '\r']) { format!("\"{}\"", grupo.clave.replace('"', "\"\"")) } else { grupo.clave.clone() };
+            let _ = writeln!(csv, "{},{},{:.2},{:.2},{:.2},{:.2}",
+                clave, grupo.cantidad, grupo.precio_minimo, grupo.precio_promedio, grupo.precio_maximo, grupo.valor_total);
+        }
+        csv
+    }
+
+    fn a_json(&self) -> String {
+        let grupos: Vec<String> = self.grupos.iter().map(|grupo| format!(
+            "{{\"clave\":\"{}\",\"cantidad\":{},\"minimo\":{:.2},\"promedio\":{:.2},\"maximo\":{:.2},\"valor_total\":{:.2}}}",
+            escapar_json(&grupo.clave), grupo.cantidad, grupo.precio_minimo, grupo.precio_promedio, grupo.precio_maximo, grupo.valor_total
+        )).collect();
+        format!("{{\"dimension\":\"{}\",\"total_autos\":{},\"valor_total\":{:.2},\"grupos\":[{}]}}",
+            self.dimension.nombre(), self.total_autos(), self.valor_total(), grupos.join(","))
+    }
+}
+
+fn escapar_json(texto: &str) -> String {
+    let mut escapado = String::new();
+    for c in texto.chars() {
+        match c {
+            '"' => escapado.push_str("\\\""),
+            '\\' => escapado.push_str("\\\\"),
+            '\n' => escapado.push_str("\\n"),
+            c if (c as u32) < 0x20 => { let _ = write!(escapado, "\\u{:04x}", c as u32); }
+            c => escapado.push(c),
+        }
+    }
+    escapado
 }
 
 struct ReporteGeneral {
@@ -139,6 +278,13 @@ impl ReporteAuto {
 }
 
 
+#[test]
+fn test_color_texto_ida_y_vuelta() {
+    comprobar_variantes::<Color>();
+    assert_eq!(" Azul".parse::<Color>(), Ok(Color::Azul));
+    assert_eq!("violeta".parse::<Color>(), Err(NombreDesconocido("violeta".to_string())));
+}
+
 #[test]
 fn test_agregar_auto() {
     let mut concesionario: ConcesionarioAuto = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 2);
@@ -152,7 +298,7 @@ fn test_agregar_auto() {
     assert_eq!(concesionario.lista_autos[1].modelo, "ModeloB");
     assert_eq!(concesionario.lista_autos[1].anio, 1999);
     assert_eq!(concesionario.lista_autos[1].precio_bruto, 51656165.50);
-    assert_eq!(concesionario.lista_autos[1].color_comparable(), "blanco");
+    assert_eq!(concesionario.lista_autos[1].color, Color::Blanco);
     assert_eq!(concesionario.lista_autos.len(), 2);
 }
 
@@ -170,7 +316,7 @@ fn test_buscar_y_eliminar_auto() {
     let auto_existente = Auto::new("Marca2".to_string(), "ModeloB".to_string(), 1999, 51656165.50, Color::Blanco);
     let mut auto_encontrado = concesionario.buscar_auto(&auto_existente);
     assert!(auto_encontrado.is_some());
-    assert!(auto_encontrado.unwrap().es_igual(&auto_existente));
+    assert_eq!(auto_encontrado, Some(&auto_existente));
 
     let auto_inexistente = Auto::new("MarcaX".to_string(), "ModeloX".to_string(), 9999, 99999999.99, Color::Negro);
     auto_encontrado = concesionario.buscar_auto(&auto_inexistente);
@@ -218,4 +364,68 @@ fn test_listar_autos_por_marca() {
     assert!(reporte.listado.iter().any(|auto| auto.modelo == "ModeloA" && auto.anio == 2015 && auto.color == "rojo" && auto.precio == 4500000.00 * 1.25));
     assert!(reporte.listado.iter().any(|auto| auto.modelo == "ModeloC" && auto.anio == 2023 && auto.color == "azul" && auto.precio == 98489699.00 * 1.25));
     assert!(reporte.listado.iter().any(|auto| auto.modelo == "ModeloE" && auto.anio == 2018 && auto.color == "amarillo" && auto.precio == 8600000.00 * 1.25));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_reporte_agrupado_por_marca() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 5);
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2015, 10000.00, Color::Rojo));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Fiesta".to_string(), 1998, 4000.00, Color::Blanco));
+    concesionario.agregar_auto(Auto::new("BMW".to_string(), "Serie \"3\"".to_string(), 2019, 20000.00, Color::Negro));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2011, 8000.00, Color::Blanco));
+    let reporte = concesionario.reporte_agrupado(Dimension::Marca);
+    assert_eq!(reporte.grupos.len(), 2);
+    let ford = &reporte.grupos[1];
+    assert_eq!(ford.clave, "Ford");
+    assert_eq!(ford.cantidad, 3);
+    // 10000 * 1,25; 4000 * 0,85; 8000 * 0,90
+    assert_eq!(ford.precio_minimo, 3400.00);
+    assert_eq!(ford.precio_maximo, 12500.00);
+    assert_eq!(ford.valor_total, 23100.00);
+    assert_eq!(ford.precio_promedio, 7700.00);
+    assert_eq!(reporte.total_autos(), 4);
+    // BMW negro: 20000 * (1 - 0,10 + 0,15)
+    assert_eq!(reporte.grupos[0].valor_total, 21000.00);
+    assert_eq!(reporte.valor_total(), 44100.00);
+}
+
+#[test]
+fn test_reporte_agrupado_otras_dimensiones() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 5);
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2015, 10000.00, Color::Rojo));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Fiesta".to_string(), 1998, 4000.00, Color::Blanco));
+    concesionario.agregar_auto(Auto::new("BMW".to_string(), "Serie \"3\"".to_string(), 2019, 20000.00, Color::Negro));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2011, 8000.00, Color::Blanco));
+    let claves = |dimension| concesionario.reporte_agrupado(dimension).grupos.iter().map(|grupo| grupo.clave.clone()).collect::<Vec<String>>();
+    assert_eq!(claves(Dimension::Decada), vec!["1990-1999", "2010-2019"]);
+    assert_eq!(claves(Dimension::Color), vec!["blanco", "negro", "rojo"]);
+    assert_eq!(claves(Dimension::Modelo), vec!["Fiesta", "Focus", "Serie \"3\""]);
+
+    let vacio = ConcesionarioAuto::new("Vacio".to_string(), String::new(), 1).reporte_agrupado(Dimension::Marca);
+    assert_eq!(vacio.a_json(), "{\"dimension\":\"marca\",\"total_autos\":0,\"valor_total\":0.00,\"grupos\":[]}");
+}
+
+#[test]
+fn test_exportar_reporte() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 5);
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2015, 10000.00, Color::Rojo));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Fiesta".to_string(), 1998, 4000.00, Color::Blanco));
+    concesionario.agregar_auto(Auto::new("BMW".to_string(), "Serie \"3\"".to_string(), 2019, 20000.00, Color::Negro));
+    concesionario.agregar_auto(Auto::new("Ford".to_string(), "Focus".to_string(), 2011, 8000.00, Color::Blanco));
+    let reporte = concesionario.reporte_agrupado(Dimension::Modelo);
+    let texto = reporte.a_texto();
+    assert_eq!(texto.lines().next(), Some("modelo            cantidad          minimo        promedio          maximo       valor total"));
+    assert_eq!(texto.lines().nth(2), Some("Focus                    2         7200.00         9850.00        12500.00          19700.00"));
+    assert_eq!(texto.lines().last(), Some("total                    4                                                          44100.00"));
+
+    let csv = reporte.a_csv();
+    assert_eq!(csv.lines().next(), Some("modelo,cantidad,minimo,promedio,maximo,valor_total"));
+    assert_eq!(csv.lines().nth(3), Some("\"Serie \"\"3\"\"\",1,21000.00,21000.00,21000.00,21000.00"));
+    let mut con_salto = ConcesionarioAuto::new("Otro".to_string(), String::new(), 1);
+    con_salto.agregar_auto(Auto::new("Fiat".to_string(), "Uno\r\nFire".to_string(), 2015, 5000.00, Color::Rojo));
+    assert!(con_salto.reporte_agrupado(Dimension::Modelo).a_csv().contains("\n\"Uno\r\nFire\",1,"));
+
+    let json = reporte.a_json();
+    assert!(json.starts_with("{\"dimension\":\"modelo\",\"total_autos\":4,\"valor_total\":44100.00,\"grupos\":[{\"clave\":\"Fiesta\""));
+    assert!(json.contains("{\"clave\":\"Serie \\\"3\\\"\",\"cantidad\":1,\"minimo\":21000.00,"));
+}
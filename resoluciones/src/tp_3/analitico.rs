@@ -0,0 +1,234 @@
+// Analitico de un estudiante: todos sus examenes, la nota final y el estado de
+// cada materia, listo para imprimir como tabla de texto, CSV o Markdown.
+
+use std::fmt::Write;
+
+use super::ej_6::{EstadoAcademico, Estudiante, Examen, ReglasCursada};
+#[cfg(test)]
+use super::ej_3::Fecha;
+#[cfg(test)]
+use super::ej_6::{Escala, ReglaRecuperatorio, TipoExamen};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrdenAnalitico {
+    PorMateria,
+    PorFecha, // los examenes sin fecha van al final
+}
+
+struct ResumenMateria {
+    materia: String,
+    nota_final: Option<f64>,
+    estado: Option<EstadoAcademico>,
+}
+
+pub struct Analitico<'a> {
+    estudiante: &'a Estudiante,
+    reglas: &'a ReglasCursada,
+    orden: OrdenAnalitico,
+}
+
+impl<'a> Analitico<'a> {
+    pub fn new(estudiante: &'a Estudiante, reglas: &'a ReglasCursada) -> Analitico<'a> {
+        Analitico { estudiante, reglas, orden: OrdenAnalitico::PorMateria }
+    }
+
+    pub fn ordenado_por(mut self, orden: OrdenAnalitico) -> Analitico<'a> {
+        self.orden = orden;
+        self
+    }
+
+    fn examenes(&self) -> Vec<&Examen> {
+        let mut examenes: Vec<&Examen> = self.estudiante.calificaciones().iter().collect();
+        match self.orden {
+            OrdenAnalitico::PorMateria => examenes.sort_by(|a, b| a.materia().cmp(b.materia()).then(a.fecha().cmp(&b.fecha()))),
+            OrdenAnalitico::PorFecha => examenes.sort_by(|a, b| {
+                (a.fecha().is_none(), a.fecha()).cmp(&(b.fecha().is_none(), b.fecha())).then(a.materia().cmp(b.materia()))
+            }),
+        }
+        examenes
+    }
+
+    // una fila por materia, siempre en orden alfabetico
+    fn resumen(&self) -> Vec<ResumenMateria> {
+        let mut materias: Vec<&str> = self.estudiante.calificaciones().iter().map(|e| e.materia()).collect();
+        materias.sort();
+        materias.dedup();
+        materias.into_iter().map(|materia| ResumenMateria {
+            materia: materia.to_string(),
+            nota_final: self.estudiante.nota_final_en(materia, self.reglas),
+            estado: self.estudiante.estado_en(materia, self.reglas),
+        }).collect()
+    }
+
+    // promedio de las notas finales de cada materia, no de los examenes sueltos
+    fn promedio_general(&self) -> Option<f64> {
+        let notas: Vec<f64> = self.resumen().iter().filter_map(|fila| fila.nota_final).collect();
+        if notas.is_empty() {
+            return None;
+        }
+        Some(notas.iter().sum::<f64>() / notas.len() as f64)
+    }
+
+    pub fn a_texto(&self) -> String {
+        // la columna de materia se ajusta al nombre mas largo
+        let ancho = self.estudiante.calificaciones().iter()
+            .map(|e| e.materia().chars().count())
+            .max().unwrap_or(0).max("Materia".len()) + 2;
+        let mut texto = String::new();
+        let _ = writeln!(texto, "Analitico de {} (legajo {})", self.estudiante.nombre(), self.estudiante.id());
+        let _ = writeln!(texto);
+        let _ = writeln!(texto, "{:<ancho$}{:<18}{:<12}{:>8}{:>6}", "Materia", "Tipo", "Fecha", "Nota", "Peso");
+        for examen in self.examenes() {
+            let _ = writeln!(texto, "{:<ancho$}{:<18}{:<12}{:>8.2}{:>6}",
+                examen.materia(), examen.tipo().to_string(), mostrar_fecha(examen), examen.nota(), examen.peso());
+        }
+        let _ = writeln!(texto);
+        let _ = writeln!(texto, "{:<ancho$}{:>12}  Estado", "Materia", "Nota final");
+        for fila in self.resumen() {
+            let _ = writeln!(texto, "{:<ancho$}{:>12}  {}", fila.materia, mostrar_nota(fila.nota_final), mostrar_estado(&fila.estado));
+        }
+        let _ = writeln!(texto);
+        let _ = write!(texto, "Promedio general: {}", mostrar_nota(self.promedio_general()));
+        texto
+    }
+
+    // cada examen lleva la nota final y el estado de su materia para que el
+    // archivo se pueda filtrar sin cruzarlo con otra tabla
+    pub fn a_csv(&self) -> String {
+        let resumen = self.resumen();
+        let mut csv = String::from("legajo,estudiante,materia,tipo,fecha,nota,peso,nota_final,estado\n");
+        for examen in self.examenes() {
+            let materia = resumen.iter().find(|fila| fila.materia == examen.materia());
+            let nota_final = materia.map(|fila| mostrar_nota(fila.nota_final)).unwrap_or_default();
+            let estado = materia.map(|fila| mostrar_estado(&fila.estado)).unwrap_or_default();
+            let campos = [
+                self.estudiante.id().to_string(),
+                self.estudiante.nombre().to_string(),
+                examen.materia().to_string(),
+                examen.tipo().to_string(),
+                mostrar_fecha(examen),
+                format!("{:.2}", examen.nota()),
+                examen.peso().to_string(),
+                nota_final,
+                estado,
+            ];
+            let campos: Vec<String> = campos.iter().map(|campo| campo_csv(campo)).collect();
+            let _ = writeln!(csv, "{}", campos.join(","));
+        }
+        csv
+    }
+
+    pub fn a_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# Analitico de {} (legajo {})", self.estudiante.nombre(), self.estudiante.id());
+        let _ = writeln!(md);
+        let _ = writeln!(md, "## Examenes");
+        let _ = writeln!(md);
+        let _ = writeln!(md, "| Materia | Tipo | Fecha | Nota | Peso |");
+        let _ = writeln!(md, "|---|---|---|---:|---:|");
+        for examen in self.examenes() {
+            let _ = writeln!(md, "| {} | {} | {} | {:.2} | {} |",
+                campo_markdown(examen.materia()), examen.tipo(), mostrar_fecha(examen), examen.nota(), examen.peso());
+        }
+        let _ = writeln!(md);
+        let _ = writeln!(md, "## Resumen");
+        let _ = writeln!(md);
+        let _ = writeln!(md, "| Materia | Nota final | Estado |");
+        let _ = writeln!(md, "|---|---:|---|");
+        for fila in self.resumen() {
+            let _ = writeln!(md, "| {} | {} | {} |", campo_markdown(&fila.materia), mostrar_nota(fila.nota_final), mostrar_estado(&fila.estado));
+        }
+        let _ = writeln!(md);
+        let _ = writeln!(md, "**Promedio general:** {}", mostrar_nota(self.promedio_general()));
+        md
+    }
+}
+
+fn mostrar_fecha(examen: &Examen) -> String {
+    examen.fecha().map(|fecha| fecha.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn mostrar_nota(nota: Option<f64>) -> String {
+    nota.map(|nota| format!("{:.2}", nota)).unwrap_or_else(|| "-".to_string())
+}
+
+fn mostrar_estado(estado: &Option<EstadoAcademico>) -> String {
+    estado.as_ref().map(|estado| estado.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn campo_csv(campo: &str) -> String {
+    if campo.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", campo.replace('"', "\"\""))
+    } else {
+        campo.to_string()
+    }
+}
+
+fn campo_markdown(campo: &str) -> String {
+    campo.replace('|', "\\|")
+}
+
+#[test]
+fn test_analitico_texto() {
+    let estudiante = Estudiante::new("Maria".to_string(), 2, vec![
+        Examen::new("Matemáticas".to_string(), 90.0).con_fecha(Fecha::new(5, 6, 2024)),
+        Examen::new("Historia".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024)),
+        Examen::new("Matemáticas".to_string(), 70.0).con_fecha(Fecha::new(12, 4, 2024)),
+        Examen::new("Historia, Arte y Sociedad".to_string(), 85.0).con_tipo(TipoExamen::TrabajoPractico),
+    ]);
+    let reglas = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+    let texto = Analitico::new(&estudiante, &reglas).a_texto();
+    let lineas: Vec<&str> = texto.lines().collect();
+
+    assert_eq!(lineas[0], "Analitico de Maria (legajo 2)");
+    assert_eq!(lineas[2], "Materia                    Tipo              Fecha           Nota  Peso");
+    assert_eq!(lineas[3], "Historia                   Parcial           10/04/2024     40.00     1");
+    assert_eq!(lineas[4], "Historia, Arte y Sociedad  Trabajo práctico  -              85.00     1");
+    assert!(lineas[5].starts_with("Matemáticas                Parcial           12/04/2024"));
+    assert!(texto.contains("\nMatemáticas                       80.00  Promocionado\n"));
+    assert!(texto.contains("\nHistoria                          40.00  Desaprobado\n"));
+    // (80 + 40 + 85) / 3, no el promedio de los cuatro examenes
+    assert!(texto.ends_with("Promedio general: 68.33"));
+}
+
+#[test]
+fn test_analitico_por_fecha() {
+    let estudiante = Estudiante::new("Maria".to_string(), 2, vec![
+        Examen::new("Matemáticas".to_string(), 90.0).con_fecha(Fecha::new(5, 6, 2024)),
+        Examen::new("Historia".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024)),
+        Examen::new("Matemáticas".to_string(), 70.0).con_fecha(Fecha::new(12, 4, 2024)),
+        Examen::new("Historia, Arte y Sociedad".to_string(), 85.0).con_tipo(TipoExamen::TrabajoPractico),
+    ]);
+    let reglas = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+    let csv = Analitico::new(&estudiante, &reglas).ordenado_por(OrdenAnalitico::PorFecha).a_csv();
+    let lineas: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lineas[0], "legajo,estudiante,materia,tipo,fecha,nota,peso,nota_final,estado");
+    assert_eq!(lineas[1], "2,Maria,Historia,Parcial,10/04/2024,40.00,1,40.00,Desaprobado");
+    assert_eq!(lineas[2], "2,Maria,Matemáticas,Parcial,12/04/2024,70.00,1,80.00,Promocionado");
+    assert_eq!(lineas[3], "2,Maria,Matemáticas,Parcial,05/06/2024,90.00,1,80.00,Promocionado");
+    // los examenes sin fecha van al final y los campos con comas van entre comillas
+    assert_eq!(lineas[4], "2,Maria,\"Historia, Arte y Sociedad\",Trabajo práctico,-,85.00,1,85.00,Promocionado");
+    assert_eq!(lineas.len(), 5);
+}
+
+#[test]
+fn test_analitico_markdown() {
+    let estudiante = Estudiante::new("Maria".to_string(), 2, vec![
+        Examen::new("Matemáticas".to_string(), 90.0).con_fecha(Fecha::new(5, 6, 2024)),
+        Examen::new("Historia".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024)),
+        Examen::new("Matemáticas".to_string(), 70.0).con_fecha(Fecha::new(12, 4, 2024)),
+        Examen::new("Historia, Arte y Sociedad".to_string(), 85.0).con_tipo(TipoExamen::TrabajoPractico),
+    ]);
+    let reglas = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+    let md = Analitico::new(&estudiante, &reglas).a_markdown();
+
+    assert!(md.starts_with("# Analitico de Maria (legajo 2)\n"));
+    assert!(md.contains("| Materia | Tipo | Fecha | Nota | Peso |\n|---|---|---|---:|---:|\n| Historia | Parcial | 10/04/2024 | 40.00 | 1 |\n"));
+    assert!(md.contains("| Matemáticas | 80.00 | Promocionado |\n"));
+    assert!(md.ends_with("**Promedio general:** 68.33\n"));
+
+    let sin_examenes = Estudiante::new("Juan".to_string(), 1, Vec::new());
+    let md = Analitico::new(&sin_examenes, &reglas).a_markdown();
+    assert!(md.contains("|---|---:|---|\n\n**Promedio general:** -"));
+}
@@ -0,0 +1,331 @@
+// Biblioteca que guarda cada cancion una sola vez, con un id, y playlists que
+// solo guardan ids. Dos canciones son la misma si coinciden titulo y artista
+// normalizados, asi "Canción" de "Ñandú" y "cancion" de "nandu" no se duplican.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::comun::normalizar;
+use super::ej_8::{Cancion, Playlist};
+#[cfg(test)]
+use super::ej_8::Genero;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorBiblioteca {
+    PlaylistInexistente,
+    PlaylistDuplicada,
+    CancionInexistente,
+    CancionRepetida, // la cancion ya esta en esa playlist
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alta {
+    Nueva(u32),
+    Duplicada(u32), // ya estaba: se devuelve el id de la que se conserva
+}
+
+impl Alta {
+    pub fn id(&self) -> u32 {
+        match self {
+            Alta::Nueva(id) | Alta::Duplicada(id) => *id,
+        }
+    }
+}
+
+fn clave_de(cancion: &Cancion) -> (String, String) {
+    (clave(cancion.titulo()), clave(cancion.artista()))
+}
+
+// un texto que es solo puntuacion normaliza a vacio: se compara tal cual,
+// en minusculas y sin los espacios de los bordes, para que "?" y "!" no queden iguales
+fn clave(texto: &str) -> String {
+    let normalizado = normalizar(texto);
+    if normalizado.is_empty() {
+        return texto.trim().to_lowercase();
+    }
+    normalizado
+}
+
+pub struct BibliotecaMusical {
+    canciones: BTreeMap<u32, Cancion>,
+    por_clave: HashMap<(String, String), u32>, // titulo y artista normalizados
+    playlists: BTreeMap<String, Vec<u32>>,
+    proximo_id: u32,
+}
+
+impl BibliotecaMusical {
+    pub fn new() -> BibliotecaMusical {
+        BibliotecaMusical { canciones: BTreeMap::new(), por_clave: HashMap::new(), playlists: BTreeMap::new(), proximo_id: 1 }
+    }
+
+    // si ya hay una igual se conserva la primera, con sus datos
+    pub fn agregar_cancion(&mut self, cancion: Cancion) -> Alta {
+        let clave = clave_de(&cancion);
+        if let Some(&id) = self.por_clave.get(&clave) {
+            return Alta::Duplicada(id);
+        }
+        let id = self.proximo_id;
+        self.proximo_id += 1;
+        self.por_clave.insert(clave, id);
+        self.canciones.insert(id, cancion);
+        Alta::Nueva(id)
+    }
+
+    pub fn cancion(&self, id: u32) -> Option<&Cancion> {
+        self.canciones.get(&id)
+    }
+
+    pub fn cantidad_de_canciones(&self) -> usize {
+        self.canciones.len()
+    }
+
+    pub fn buscar_duplicado(&self, cancion: &Cancion) -> Option<u32> {
+        self.por_clave.get(&clave_de(cancion)).copied()
+    }
+
+    // la saca tambien de todas las playlists
+    pub fn eliminar_cancion(&mut self, id: u32) -> Option<Cancion> {
+        let cancion = self.canciones.remove(&id)?;
+        self.por_clave.remove(&clave_de(&cancion));
+        for ids in self.playlists.values_mut() {
+            ids.retain(|&otro| otro != id);
+        }
+        Some(cancion)
+    }
+
+    pub fn crear_playlist(&mut self, nombre: &str) -> Result<(), ErrorBiblioteca> {
+        if self.playlists.contains_key(nombre) {
+            return Err(ErrorBiblioteca::PlaylistDuplicada);
+        }
+        self.playlists.insert(nombre.to_string(), Vec::new());
+        Ok(())
+    }
+
+    pub fn nombres_de_playlists(&self) -> Vec<&str> {
+        self.playlists.keys().map(String::as_str).collect()
+    }
+
+    pub fn agregar_a_playlist(&mut self, nombre: &str, id: u32) -> Result<(), ErrorBiblioteca> {
+        if !self.canciones.contains_key(&id) {
+            return Err(ErrorBiblioteca::CancionInexistente);
+        }
+        let ids = self.playlists.get_mut(nombre).ok_or(ErrorBiblioteca::PlaylistInexistente)?;
+        if ids.contains(&id) {
+            return Err(ErrorBiblioteca::CancionRepetida);
+        }
+        ids.push(id);
+        Ok(())
+    }
+
+    pub fn quitar_de_playlist(&mut self, nombre: &str, id: u32) -> Result<(), ErrorBiblioteca> {
+        let ids = self.playlists.get_mut(nombre).ok_or(ErrorBiblioteca::PlaylistInexistente)?;
+        let posicion = ids.iter().position(|&otro| otro == id).ok_or(ErrorBiblioteca::CancionInexistente)?;
+        ids.remove(posicion);
+        Ok(())
+    }
+
+    pub fn ids_de(&self, nombre: &str) -> Option<&[u32]> {
+        self.playlists.get(nombre).map(Vec::as_slice)
+    }
+
+    pub fn canciones_de(&self, nombre: &str) -> Option<Vec<&Cancion>> {
+        let ids = self.playlists.get(nombre)?;
+        Some(ids.iter().filter_map(|id| self.canciones.get(id)).collect())
+    }
+
+    // trae una playlist suelta: sus canciones pasan a la biblioteca sin
+    // duplicarse y una cancion repetida dentro de la playlist queda una vez.
+    // Devuelve cuantas canciones distintas ya estaban en la biblioteca antes
+    // de importar; las repetidas dentro de la playlist no cuentan.
+    pub fn importar_playlist(&mut self, playlist: &Playlist) -> Result<usize, ErrorBiblioteca> {
+        self.crear_playlist(playlist.nombre())?;
+        let mut nuevas = HashSet::new();
+        let mut ya_estaban = HashSet::new();
+        for cancion in playlist.canciones() {
+            let alta = self.agregar_cancion(cancion.clone());
+            match alta {
+                Alta::Nueva(id) => { nuevas.insert(id); }
+                Alta::Duplicada(id) if !nuevas.contains(&id) => { ya_estaban.insert(id); }
+                Alta::Duplicada(_) => {}
+            }
+            match self.agregar_a_playlist(playlist.nombre(), alta.id()) {
+                Ok(()) | Err(ErrorBiblioteca::CancionRepetida) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(ya_estaban.len())
+    }
+
+    // arma una Playlist comun con copias, por ejemplo para el reproductor o para exportarla
+    pub fn exportar_playlist(&self, nombre: &str) -> Option<Playlist> {
+        let mut playlist = Playlist::new(nombre.to_string());
+        for cancion in self.canciones_de(nombre)? {
+            playlist.agregar_cancion(cancion.clone());
+        }
+        Some(playlist)
+    }
+
+    // las de la primera y despues las de la segunda que faltaban
+    pub fn fusionar(&mut self, primera: &str, segunda: &str, nueva: &str) -> Result<(), ErrorBiblioteca> {
+        self.combinar(primera, segunda, nueva, |primera, segunda| {
+            let mut ids = primera.to_vec();
+            ids.extend(segunda.iter().filter(|id| !primera.contains(id)));
+            ids
+        })
+    }
+
+    // las que estan en las dos, en el orden de la primera
+    pub fn intersecar(&mut self, primera: &str, segunda: &str, nueva: &str) -> Result<(), ErrorBiblioteca> {
+        self.combinar(primera, segunda, nueva, |primera, segunda| {
+            let segunda: HashSet<&u32> = segunda.iter().collect();
+            primera.iter().filter(|id| segunda.contains(id)).copied().collect()
+        })
+    }
+
+    // las de la primera que no estan en la segunda
+    pub fn diferencia(&mut self, primera: &str, segunda: &str, nueva: &str) -> Result<(), ErrorBiblioteca> {
+        self.combinar(primera, segunda, nueva, |primera, segunda| {
+            let segunda: HashSet<&u32> = segunda.iter().collect();
+            primera.iter().filter(|id| !segunda.contains(id)).copied().collect()
+        })
+    }
+
+    fn combinar(&mut self, primera: &str, segunda: &str, nueva: &str, operacion: impl Fn(&[u32], &[u32]) -> Vec<u32>) -> Result<(), ErrorBiblioteca> {
+        let a = self.playlists.get(primera).ok_or(ErrorBiblioteca::PlaylistInexistente)?;
+        let b = self.playlists.get(segunda).ok_or(ErrorBiblioteca::PlaylistInexistente)?;
+        if self.playlists.contains_key(nueva) {
+            return Err(ErrorBiblioteca::PlaylistDuplicada);
+        }
+        let ids = operacion(a, b);
+        self.playlists.insert(nueva.to_string(), ids);
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+fn cancion(titulo: &str, artista: &str) -> Cancion {
+    Cancion::new(titulo.to_string(), artista.to_string(), Genero::Otros)
+}
+
+#[test]
+fn test_importar_cuenta_solo_las_que_ya_estaban() {
+    let mut viaje = Playlist::new("Viaje".to_string());
+    viaje.agregar_cancion(cancion("Zamba de mi esperanza", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Alfonsina y el mar", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Blues local", "Pappo"));
+    let mut noche = Playlist::new("Noche".to_string());
+    noche.agregar_cancion(cancion("BLUES LOCAL", "pappo"));
+    noche.agregar_cancion(cancion("Canción para mi muerte", "Sui Generis"));
+    noche.agregar_cancion(cancion("Zamba  de mi esperanza!", "Mercedes Sosa"));
+
+    let mut biblioteca = BibliotecaMusical::new();
+    assert_eq!(biblioteca.importar_playlist(&viaje), Ok(0));
+    assert_eq!(biblioteca.importar_playlist(&noche), Ok(2));
+    let mut repetida = Playlist::new("Repetida".to_string());
+    repetida.agregar_cancion(cancion("Demoliendo hoteles", "Charly García"));
+    repetida.agregar_cancion(cancion("demoliendo hoteles", "Charly Garcia"));
+    repetida.agregar_cancion(cancion("Blues local", "Pappo"));
+    repetida.agregar_cancion(cancion("Blues Local", "Pappo"));
+    assert_eq!(biblioteca.importar_playlist(&repetida), Ok(1));
+    assert_eq!(biblioteca.ids_de("Repetida").unwrap().len(), 2);
+}
+
+#[test]
+fn test_titulos_solo_con_puntuacion_no_se_confunden() {
+    let mut biblioteca = BibliotecaMusical::new();
+    let pregunta = biblioteca.agregar_cancion(cancion("?", "Charly García")).id();
+    assert_eq!(biblioteca.agregar_cancion(cancion("!", "Charly Garcia")), Alta::Nueva(pregunta + 1));
+    assert_eq!(biblioteca.agregar_cancion(cancion(" ? ", "charly garcia")), Alta::Duplicada(pregunta));
+}
+
+#[cfg(test)]
+fn titulos(canciones: Vec<&Cancion>) -> Vec<&str> {
+    canciones.iter().map(|cancion| cancion.titulo()).collect()
+}
+
+#[test]
+fn test_canciones_sin_duplicar() {
+    let mut viaje = Playlist::new("Viaje".to_string());
+    viaje.agregar_cancion(cancion("Zamba de mi esperanza", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Alfonsina y el mar", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Blues local", "Pappo"));
+    let mut noche = Playlist::new("Noche".to_string());
+    noche.agregar_cancion(cancion("BLUES LOCAL", "pappo"));
+    noche.agregar_cancion(cancion("Canción para mi muerte", "Sui Generis"));
+    noche.agregar_cancion(cancion("Zamba  de mi esperanza!", "Mercedes Sosa"));
+
+    let mut biblioteca = BibliotecaMusical::new();
+    biblioteca.importar_playlist(&viaje).unwrap();
+    biblioteca.importar_playlist(&noche).unwrap();
+    assert_eq!(biblioteca.cantidad_de_canciones(), 4);
+    // las dos playlists apuntan a la misma cancion, con los datos de la primera vez
+    let viaje = biblioteca.ids_de("Viaje").unwrap();
+    let noche = biblioteca.ids_de("Noche").unwrap();
+    assert_eq!(viaje[2], noche[0]);
+    assert_eq!(biblioteca.cancion(noche[0]).unwrap().titulo(), "Blues local");
+    assert_eq!(biblioteca.buscar_duplicado(&cancion("alfonsina y el mar", "MERCEDES SOSA")), Some(viaje[1]));
+    assert_eq!(biblioteca.buscar_duplicado(&cancion("Alfonsina y el mar", "Otra")), None);
+}
+
+#[test]
+fn test_playlists_por_referencia() {
+    let mut viaje = Playlist::new("Viaje".to_string());
+    viaje.agregar_cancion(cancion("Zamba de mi esperanza", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Alfonsina y el mar", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Blues local", "Pappo"));
+    let mut noche = Playlist::new("Noche".to_string());
+    noche.agregar_cancion(cancion("BLUES LOCAL", "pappo"));
+    noche.agregar_cancion(cancion("Canción para mi muerte", "Sui Generis"));
+    noche.agregar_cancion(cancion("Zamba  de mi esperanza!", "Mercedes Sosa"));
+
+    let mut biblioteca = BibliotecaMusical::new();
+    biblioteca.importar_playlist(&viaje).unwrap();
+    biblioteca.importar_playlist(&noche).unwrap();
+    let id = biblioteca.agregar_cancion(cancion("Demoliendo hoteles", "Charly García")).id();
+    assert_eq!(biblioteca.agregar_cancion(cancion("Demoliendo Hoteles", "Charly Garcia")), Alta::Duplicada(id));
+    assert_eq!(biblioteca.agregar_a_playlist("Viaje", id), Ok(()));
+    assert_eq!(biblioteca.agregar_a_playlist("Viaje", id), Err(ErrorBiblioteca::CancionRepetida));
+    assert_eq!(biblioteca.agregar_a_playlist("Otra", id), Err(ErrorBiblioteca::PlaylistInexistente));
+    assert_eq!(biblioteca.agregar_a_playlist("Viaje", 99), Err(ErrorBiblioteca::CancionInexistente));
+    assert_eq!(biblioteca.crear_playlist("Noche"), Err(ErrorBiblioteca::PlaylistDuplicada));
+
+    // eliminar una cancion la saca de todas las playlists
+    let blues = biblioteca.ids_de("Noche").unwrap()[0];
+    assert!(biblioteca.eliminar_cancion(blues).is_some());
+    assert_eq!(titulos(biblioteca.canciones_de("Viaje").unwrap()), vec!["Zamba de mi esperanza", "Alfonsina y el mar", "Demoliendo hoteles"]);
+    assert_eq!(biblioteca.canciones_de("Noche").unwrap().len(), 2);
+    assert_eq!(biblioteca.buscar_duplicado(&cancion("Blues local", "Pappo")), None);
+
+    assert_eq!(biblioteca.quitar_de_playlist("Viaje", id), Ok(()));
+    assert_eq!(biblioteca.quitar_de_playlist("Viaje", id), Err(ErrorBiblioteca::CancionInexistente));
+    let exportada = biblioteca.exportar_playlist("Viaje").unwrap();
+    assert_eq!(exportada.nombre(), "Viaje");
+    assert_eq!(exportada.canciones().len(), 2);
+}
+
+#[test]
+fn test_operaciones_entre_playlists() {
+    let mut viaje = Playlist::new("Viaje".to_string());
+    viaje.agregar_cancion(cancion("Zamba de mi esperanza", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Alfonsina y el mar", "Mercedes Sosa"));
+    viaje.agregar_cancion(cancion("Blues local", "Pappo"));
+    let mut noche = Playlist::new("Noche".to_string());
+    noche.agregar_cancion(cancion("BLUES LOCAL", "pappo"));
+    noche.agregar_cancion(cancion("Canción para mi muerte", "Sui Generis"));
+    noche.agregar_cancion(cancion("Zamba  de mi esperanza!", "Mercedes Sosa"));
+
+    let mut biblioteca = BibliotecaMusical::new();
+    biblioteca.importar_playlist(&viaje).unwrap();
+    biblioteca.importar_playlist(&noche).unwrap();
+    biblioteca.fusionar("Viaje", "Noche", "Todo").unwrap();
+    biblioteca.intersecar("Viaje", "Noche", "Comunes").unwrap();
+    biblioteca.diferencia("Viaje", "Noche", "Solo viaje").unwrap();
+    assert_eq!(titulos(biblioteca.canciones_de("Todo").unwrap()),
+        vec!["Zamba de mi esperanza", "Alfonsina y el mar", "Blues local", "Canción para mi muerte"]);
+    assert_eq!(titulos(biblioteca.canciones_de("Comunes").unwrap()), vec!["Zamba de mi esperanza", "Blues local"]);
+    assert_eq!(titulos(biblioteca.canciones_de("Solo viaje").unwrap()), vec!["Alfonsina y el mar"]);
+    assert_eq!(biblioteca.nombres_de_playlists(), vec!["Comunes", "Noche", "Solo viaje", "Todo", "Viaje"]);
+
+    assert_eq!(biblioteca.fusionar("Viaje", "Noche", "Todo"), Err(ErrorBiblioteca::PlaylistDuplicada));
+    assert_eq!(biblioteca.diferencia("Viaje", "Nada", "Otra"), Err(ErrorBiblioteca::PlaylistInexistente));
+}
@@ -0,0 +1,44 @@
+// Piezas que usan varios ejercicios: la direccion de un ordenamiento y la
+// forma de comparar textos sin importar mayusculas, tildes ni puntuacion.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direccion {
+    Ascendente,
+    Descendente,
+}
+
+// minusculas, sin tildes y con la puntuacion y los espacios repetidos
+// convertidos en un solo espacio
+pub fn normalizar(texto: &str) -> String {
+    let mut normalizado = String::new();
+    for c in texto.chars().flat_map(char::to_lowercase).map(sin_tilde) {
+        if c.is_alphanumeric() {
+            normalizado.push(c);
+        } else if !normalizado.is_empty() && !normalizado.ends_with(' ') {
+            normalizado.push(' ');
+        }
+    }
+    normalizado.trim_end().to_string()
+}
+
+fn sin_tilde(c: char) -> char {
+    match c {
+        'á' | 'à' | 'ä' | 'â' | 'ã' => 'a',
+        'é' | 'è' | 'ë' | 'ê' => 'e',
+        'í' | 'ì' | 'ï' | 'î' => 'i',
+        'ó' | 'ò' | 'ö' | 'ô' | 'õ' => 'o',
+        'ú' | 'ù' | 'ü' | 'û' => 'u',
+        'ñ' => 'n',
+        'ç' => 'c',
+        _ => c,
+    }
+}
+
+
+#[test]
+fn test_normalizar() {
+    assert_eq!(normalizar("  Canción   de  Ñandú! "), "cancion de nandu");
+    assert_eq!(normalizar("AC/DC"), "ac dc");
+    assert_eq!(normalizar("Über-Öl"), "uber ol");
+    assert_eq!(normalizar("..."), "");
+}
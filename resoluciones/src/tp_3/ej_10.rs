@@ -1,7 +1,13 @@
 use std::{collections::HashMap, u32};
+use std::fmt;
+use std::str::FromStr;
 
 use super::ej_3::Fecha;
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Genero {
     Novela,
     Infantil,
@@ -9,6 +15,29 @@ enum Genero {
     Otros
 }
 
+impl ConNombre for Genero {
+    const VARIANTES: &'static [(Genero, &'static str)] = &[
+        (Genero::Novela, "novela"),
+        (Genero::Infantil, "infantil"),
+        (Genero::Tecnico, "tecnico"),
+        (Genero::Otros, "otros"),
+    ];
+}
+
+impl fmt::Display for Genero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for Genero {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Genero::desde_nombre(texto)
+    }
+}
+
 struct Libro<'a> {
     isbn: u32,
     titulo: &'a str,
@@ -34,11 +63,32 @@ impl<'a> Cliente<'a> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum EstadoPrestamo {
     Devuelto,
     EnPrestamo,
 }
+
+impl ConNombre for EstadoPrestamo {
+    const VARIANTES: &'static [(EstadoPrestamo, &'static str)] = &[
+        (EstadoPrestamo::Devuelto, "devuelto"),
+        (EstadoPrestamo::EnPrestamo, "en prestamo"),
+    ];
+}
+
+impl fmt::Display for EstadoPrestamo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for EstadoPrestamo {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        EstadoPrestamo::desde_nombre(texto)
+    }
+}
 #[derive(Clone)]
 struct Prestamo<'a> {
     isbn_libro: u32,
@@ -53,10 +103,7 @@ impl<'a> Prestamo<'a> {
     }
 
     fn fue_devuelto(&self) -> bool {
-        match &self.estado {
-            EstadoPrestamo::Devuelto => true,
-            EstadoPrestamo::EnPrestamo => false,
-        }
+        self.estado == EstadoPrestamo::Devuelto
     }
 }
 
@@ -165,6 +212,15 @@ impl<'a> Biblioteca<'a> {
 
 }
 
+#[test]
+fn test_enums_texto_ida_y_vuelta() {
+    comprobar_variantes::<Genero>();
+    comprobar_variantes::<EstadoPrestamo>();
+    assert_eq!("En Prestamo".parse::<EstadoPrestamo>(), Ok(EstadoPrestamo::EnPrestamo));
+    assert_eq!("poesia".parse::<Genero>(), Err(NombreDesconocido("poesia".to_string())));
+    assert_eq!("perdido".parse::<EstadoPrestamo>(), Err(NombreDesconocido("perdido".to_string())));
+}
+
 #[test]
 fn test_incrementar_copias() {
     let mut biblioteca = Biblioteca::new("Biblioteca1", "Calle 123");
@@ -1,34 +1,83 @@
+use super::geometria::{ErrorFigura, Punto, RectanguloPlano};
+use super::medidas::{ErrorMedida, Medida, Superficie, Unidad};
+
+// los dos lados se guardan siempre en la misma unidad
 struct Rectangulo {
-    longitud: u32,
-    ancho: u32,
+    longitud: Medida,
+    ancho: Medida,
 }
 
 impl Rectangulo {
-    
+
+    // sin unidad explicita los lados se toman en metros
     fn new (longitud: u32, ancho: u32) -> Rectangulo {
         Rectangulo {
-            longitud,
-            ancho,
+            longitud: Medida::new(longitud as u64, Unidad::Metro),
+            ancho: Medida::new(ancho as u64, Unidad::Metro),
         }
     }
 
-    fn calcular_area(&self) -> u32 {
-        self.longitud * self.ancho
+    fn con_medidas(longitud: Medida, ancho: Medida) -> Result<Rectangulo, ErrorMedida> {
+        let unidad = longitud.unidad().min(ancho.unidad());
+        Ok(Rectangulo {
+            longitud: longitud.convertir_a(unidad)?,
+            ancho: ancho.convertir_a(unidad)?,
+        })
     }
 
-    fn calcular_perimetro(&self) -> u32 {
-        2*self.longitud + 2*self.ancho
+    fn calcular_area(&self) -> Result<Superficie, ErrorMedida> {
+        self.longitud.multiplicar(&self.ancho)
+    }
+
+    fn calcular_perimetro(&self) -> Result<Medida, ErrorMedida> {
+        self.longitud.sumar(&self.ancho)?.multiplicar_por(2)
     }
 
     fn es_cuadrado(&self) -> bool {
         self.longitud == self.ancho
     }
+
+    // las coordenadas del plano quedan expresadas en la unidad del rectangulo
+    fn ubicar_en(&self, origen: Punto) -> Result<RectanguloPlano, ErrorFigura> {
+        RectanguloPlano::new(origen, self.longitud.valor() as f64, self.ancho.valor() as f64)
+    }
 }
 
 #[test]
 fn test_rectangulo() {
     let rectangulo1 = Rectangulo::new(5, 7);
-    assert_eq!(35, rectangulo1.calcular_area());
-    assert_eq!(24, rectangulo1.calcular_perimetro());
+    assert_eq!(Ok(Superficie::new(35, Unidad::Metro)), rectangulo1.calcular_area());
+    assert_eq!(Ok(Medida::new(24, Unidad::Metro)), rectangulo1.calcular_perimetro());
     assert!(!rectangulo1.es_cuadrado());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_rectangulo_con_unidades() {
+    let rectangulo = Rectangulo::con_medidas(Medida::new(2, Unidad::Metro), Medida::new(55, Unidad::Centimetro)).unwrap();
+    assert_eq!(rectangulo.calcular_area(), Ok(Superficie::new(11_000, Unidad::Centimetro)));
+    assert_eq!(rectangulo.calcular_area().unwrap().convertir_a(Unidad::Metro), Err(ErrorMedida::ConversionInexacta));
+    assert_eq!(rectangulo.calcular_perimetro(), Ok(Medida::new(510, Unidad::Centimetro)));
+
+    let cuadrado = Rectangulo::con_medidas(Medida::new(1, Unidad::Kilometro), Medida::new(1_000, Unidad::Metro)).unwrap();
+    assert!(cuadrado.es_cuadrado());
+}
+
+#[test]
+fn test_rectangulo_sin_desbordamiento() {
+    // antes esta area desbordaba el u32 en silencio
+    let grande = Rectangulo::new(u32::MAX, u32::MAX);
+    assert_eq!(grande.calcular_area(), Ok(Superficie::new(u32::MAX as u64 * u32::MAX as u64, Unidad::Metro)));
+
+    let enorme = Rectangulo::con_medidas(Medida::new(u64::MAX / 10, Unidad::Milimetro), Medida::new(1, Unidad::Kilometro)).unwrap();
+    assert_eq!(enorme.calcular_area(), Err(ErrorMedida::Desbordamiento));
+    assert_eq!(Rectangulo::con_medidas(Medida::new(u64::MAX, Unidad::Metro), Medida::new(1, Unidad::Milimetro)).err(), Some(ErrorMedida::Desbordamiento));
+}
+
+#[test]
+fn test_ubicar_rectangulo() {
+    use super::geometria::Figura;
+
+    let rectangulo = Rectangulo::new(5, 7).ubicar_en(Punto::new(1.0, 2.0)).unwrap();
+    assert_eq!(rectangulo.caja_contenedora(), (Punto::new(1.0, 2.0), Punto::new(6.0, 9.0)));
+    assert_eq!(rectangulo.area(), 35.0);
+}
@@ -1,3 +1,13 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFecha {
+    FormatoInvalido,
+    FechaInexistente,
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Fecha {
     dia: u32,
@@ -97,6 +107,46 @@ impl Fecha {
     }
 }
 
+// los campos estan en orden dia/mes/anio, asi que el orden no se puede derivar
+impl Ord for Fecha {
+    fn cmp(&self, otra: &Self) -> Ordering {
+        (self.anio, self.mes, self.dia).cmp(&(otra.anio, otra.mes, otra.dia))
+    }
+}
+
+impl PartialOrd for Fecha {
+    fn partial_cmp(&self, otra: &Self) -> Option<Ordering> {
+        Some(self.cmp(otra))
+    }
+}
+
+// acepta el mismo formato que muestra Display: dd/mm/aaaa
+impl FromStr for Fecha {
+    type Err = ErrorFecha;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        let partes: Vec<&str> = texto.trim().split('/').collect();
+        if partes.len() != 3 {
+            return Err(ErrorFecha::FormatoInvalido);
+        }
+        let mut numeros = [0; 3];
+        for (numero, parte) in numeros.iter_mut().zip(&partes) {
+            *numero = parte.parse().map_err(|_| ErrorFecha::FormatoInvalido)?;
+        }
+        let fecha = Fecha::new(numeros[0], numeros[1], numeros[2]);
+        if !fecha.es_fecha_valida() {
+            return Err(ErrorFecha::FechaInexistente);
+        }
+        Ok(fecha)
+    }
+}
+
+impl fmt::Display for Fecha {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}/{:02}/{}", self.dia, self.mes, self.anio)
+    }
+}
+
 
 #[test]
     fn test_es_fecha_valida() {
@@ -148,4 +198,22 @@ impl Fecha {
         // Misma fecha
         let fecha3 = Fecha::new(1, 1, 2022);
         assert!(!fecha3.es_mayor(&fecha1));
-    }
\ No newline at end of file
+    }
+
+    #[test]
+    fn test_orden_de_fechas() {
+        let mut fechas = vec![Fecha::new(1, 1, 2022), Fecha::new(31, 12, 2021), Fecha::new(15, 6, 2022)];
+        fechas.sort();
+        assert_eq!(fechas, vec![Fecha::new(31, 12, 2021), Fecha::new(1, 1, 2022), Fecha::new(15, 6, 2022)]);
+        assert!(Fecha::new(2, 1, 2022) > Fecha::new(1, 1, 2022));
+        assert_eq!(Fecha::new(5, 3, 2024).to_string(), "05/03/2024");
+    }
+
+    #[test]
+    fn test_leer_fecha() {
+        assert_eq!("05/03/2024".parse(), Ok(Fecha::new(5, 3, 2024)));
+        assert_eq!(" 29/2/2024 ".parse(), Ok(Fecha::new(29, 2, 2024)));
+        assert_eq!("29/02/2023".parse::<Fecha>(), Err(ErrorFecha::FechaInexistente));
+        assert_eq!("2024-03-05".parse::<Fecha>(), Err(ErrorFecha::FormatoInvalido));
+        assert_eq!("5/marzo/2024".parse::<Fecha>(), Err(ErrorFecha::FormatoInvalido));
+    }
@@ -1,81 +1,369 @@
+use super::geometria::{ErrorFigura, Punto, TrianguloPlano};
+use super::medidas::{ErrorMedida, Medida, Superficie, Unidad};
+
+// tolerancia relativa para comparar lados y angulos calculados con f64
+const TOLERANCIA: f64 = 1e-9;
+
+#[derive(Debug, PartialEq)]
+enum ErrorTriangulo {
+    LadoInvalido,
+    DesigualdadTriangular,
+    Medida(ErrorMedida),
+}
+
+#[derive(Debug, PartialEq)]
+enum TipoPorLados {
+    Equilatero,
+    Isosceles,
+    Escaleno,
+}
+
+#[derive(Debug, PartialEq)]
+enum TipoPorAngulos {
+    Acutangulo,
+    Rectangulo,
+    Obtusangulo,
+}
+
+#[derive(Debug)]
 struct Triangulo {
     lado_a: f64,
     lado_b: f64,
     lado_c: f64,
+    unidad: Unidad,
+    // los mismos lados como medidas enteras, para calcular area y perimetro sin
+    // pasar por f64; no estan si algun lado no es un numero entero de la unidad
+    exactos: Option<[Medida; 3]>,
+}
+
+fn son_iguales(x: f64, y: f64) -> bool {
+    (x - y).abs() <= TOLERANCIA * x.abs().max(y.abs())
 }
 
 impl Triangulo {
 
-    fn new(a:f64, b:f64, c:f64) -> Self {
-        Triangulo {
+    // sin unidad explicita los lados se toman en metros
+    fn new(a:f64, b:f64, c:f64) -> Result<Self, ErrorTriangulo> {
+        Triangulo::con_unidad(a, b, c, Unidad::Metro)
+    }
+
+    // los lados se llevan a la menor de las unidades recibidas y la desigualdad
+    // triangular se controla con enteros, asi lados enormes no pierden precision
+    fn con_medidas(a: Medida, b: Medida, c: Medida) -> Result<Self, ErrorTriangulo> {
+        let unidad = a.unidad().min(b.unidad()).min(c.unidad());
+        let mut lados = [a, b, c];
+        for lado in lados.iter_mut() {
+            *lado = lado.convertir_a(unidad).map_err(ErrorTriangulo::Medida)?;
+        }
+        let mut valores = lados.map(|lado| lado.valor() as u128);
+        valores.sort();
+        if valores[0] == 0 {
+            return Err(ErrorTriangulo::LadoInvalido);
+        }
+        if valores[0] + valores[1] <= valores[2] {
+            return Err(ErrorTriangulo::DesigualdadTriangular);
+        }
+        let [a, b, c] = lados.map(|lado| lado.valor() as f64);
+        Ok(Triangulo { lado_a: a, lado_b: b, lado_c: c, unidad, exactos: Some(lados) })
+    }
+
+    fn con_unidad(a:f64, b:f64, c:f64, unidad: Unidad) -> Result<Self, ErrorTriangulo> {
+        for lado in [a, b, c] {
+            if !lado.is_finite() || lado <= 0.0 {
+                return Err(ErrorTriangulo::LadoInvalido);
+            }
+        }
+        // se exige la desigualdad estricta: un triangulo degenerado (a + b == c) no tiene area
+        let mut lados = [a, b, c];
+        lados.sort_by(|x, y| x.total_cmp(y));
+        if lados[0] + lados[1] <= lados[2] || son_iguales(lados[0] + lados[1], lados[2]) {
+            return Err(ErrorTriangulo::DesigualdadTriangular);
+        }
+        Ok(Triangulo {
             lado_a: a,
             lado_b: b,
             lado_c: c,
-        }  
+            unidad,
+            exactos: medidas_exactas([a, b, c], unidad),
+        })
     }
 
-    fn determinar_tipo(&self) -> String {
-        if self.lado_a == self.lado_b && self.lado_b == self.lado_c {
-            return "Equilatero".to_string();
-        } else if self.lado_a == self.lado_b || self.lado_a == self.lado_c || self.lado_b == self.lado_c {
-            return "Isosceles".to_string();
+    fn determinar_tipo(&self) -> TipoPorLados {
+        let ab = son_iguales(self.lado_a, self.lado_b);
+        let bc = son_iguales(self.lado_b, self.lado_c);
+        let ac = son_iguales(self.lado_a, self.lado_c);
+        if ab && bc {
+            TipoPorLados::Equilatero
+        } else if ab || bc || ac {
+            TipoPorLados::Isosceles
         } else {
-            return "Escaleno".to_string();
+            TipoPorLados::Escaleno
         }
     }
 
+    fn determinar_tipo_por_angulos(&self) -> TipoPorAngulos {
+        let mut lados = [self.lado_a, self.lado_b, self.lado_c];
+        lados.sort_by(|x, y| x.total_cmp(y));
+        let catetos = lados[0] * lados[0] + lados[1] * lados[1];
+        let hipotenusa = lados[2] * lados[2];
+        if son_iguales(catetos, hipotenusa) {
+            TipoPorAngulos::Rectangulo
+        } else if hipotenusa < catetos {
+            TipoPorAngulos::Acutangulo
+        } else {
+            TipoPorAngulos::Obtusangulo
+        }
+    }
+
+    // angulos en grados opuestos a lado_a, lado_b y lado_c respectivamente
+    fn calcular_angulos(&self) -> (f64, f64, f64) {
+        let (a, b, c) = (self.lado_a, self.lado_b, self.lado_c);
+        let angulo = |opuesto: f64, x: f64, y: f64| {
+            let coseno = (x * x + y * y - opuesto * opuesto) / (2.0 * x * y);
+            coseno.clamp(-1.0, 1.0).acos().to_degrees()
+        };
+        (angulo(a, b, c), angulo(b, a, c), angulo(c, a, b))
+    }
+
+    // alturas relativas a lado_a, lado_b y lado_c respectivamente
+    fn calcular_alturas(&self) -> (f64, f64, f64) {
+        let doble_area = 2.0 * self.calcular_area();
+        (doble_area / self.lado_a, doble_area / self.lado_b, doble_area / self.lado_c)
+    }
+
+    fn calcular_inradio(&self) -> f64 {
+        self.calcular_area() / (self.calcular_perimetro() / 2.0)
+    }
+
+    fn calcular_circunradio(&self) -> f64 {
+        (self.lado_a * self.lado_b * self.lado_c) / (4.0 * self.calcular_area())
+    }
+
+    // lado_c queda apoyado sobre el eje x a partir de origen y el tercer vertice hacia arriba
+    fn ubicar_en(&self, origen: Punto) -> Result<TrianguloPlano, ErrorFigura> {
+        let (a, b, c) = (self.lado_a, self.lado_b, self.lado_c);
+        let x = (b * b + c * c - a * a) / (2.0 * c);
+        let y = (b * b - x * x).max(0.0).sqrt();
+        TrianguloPlano::new(
+            origen,
+            Punto::new(origen.x() + c, origen.y()),
+            Punto::new(origen.x() + x, origen.y() + y),
+        )
+    }
+
     fn calcular_area(&self) -> f64 {
         let s = (self.lado_a + self.lado_b + self.lado_c) / 2.0;
-        let area = (s * (s - self.lado_a) * (s - self.lado_b) * (s - self.lado_c)).sqrt();
-        area
+        (s * (s - self.lado_a) * (s - self.lado_b) * (s - self.lado_c)).sqrt()
     }
 
     fn calcular_perimetro(&self) -> f64 {
         self.lado_a + self.lado_b + self.lado_c
     }
+
+    // area en unidades cuadradas de la unidad pedida, calculada con la formula de
+    // Heron en enteros: 16 * area² = (a+b+c)(-a+b+c)(a-b+c)(a+b-c). Casi nunca
+    // da un numero entero, asi que se redondea a la unidad cuadrada mas cercana
+    fn calcular_area_en(&self, unidad: Unidad) -> Result<Superficie, ErrorMedida> {
+        let [a, b, c] = self.lados_exactos()?.map(|lado| lado.valor() as u128);
+        let mut producto = Some(a + b + c);
+        for factor in [b + c - a, a + c - b, a + b - c] {
+            producto = producto.and_then(|producto| producto.checked_mul(factor));
+        }
+        // area en la unidad pedida = sqrt(producto) * escala / divisor
+        let (origen, destino) = (self.unidad.en_milimetros() as u128, unidad.en_milimetros() as u128);
+        let (escala, divisor) = if origen >= destino { ((origen / destino).pow(2), 4) } else { (1, 4 * (destino / origen).pow(2)) };
+        // redondear sqrt(x) / d es lo mismo que (isqrt(4 * x) + d) / (2 * d)
+        let cuadruple = producto
+            .and_then(|producto| producto.checked_mul(escala * escala))
+            .and_then(|producto| producto.checked_mul(4))
+            .ok_or(ErrorMedida::Desbordamiento)?;
+        let area = (cuadruple.isqrt() + divisor) / (2 * divisor);
+        let area = u64::try_from(area).map_err(|_| ErrorMedida::Desbordamiento)?;
+        Ok(Superficie::new(area, unidad))
+    }
+
+    fn calcular_perimetro_en(&self, unidad: Unidad) -> Result<Medida, ErrorMedida> {
+        let [a, b, c] = self.lados_exactos()?;
+        a.sumar(&b)?.sumar(&c)?.convertir_a(unidad)
+    }
+
+    fn lados_exactos(&self) -> Result<[Medida; 3], ErrorMedida> {
+        self.exactos.ok_or(ErrorMedida::ConversionInexacta)
+    }
+}
+
+// solo si los tres lados son enteros que un f64 representa sin perder precision
+fn medidas_exactas(lados: [f64; 3], unidad: Unidad) -> Option<[Medida; 3]> {
+    const MAXIMO_EXACTO: f64 = (1u64 << 53) as f64;
+    if lados.iter().all(|lado| lado.fract() == 0.0 && *lado <= MAXIMO_EXACTO) {
+        Some(lados.map(|lado| Medida::new(lado as u64, unidad)))
+    } else {
+        None
+    }
 }
 
 #[test]
     fn test_determinar_tipo() {
         // Triángulo equilátero
-        let equilatero = Triangulo::new(3.0, 3.0, 3.0);
-        assert_eq!(equilatero.determinar_tipo(), "Equilatero");
+        let equilatero = Triangulo::new(3.0, 3.0, 3.0).unwrap();
+        assert_eq!(equilatero.determinar_tipo(), TipoPorLados::Equilatero);
 
         // Triángulo isósceles
-        let isosceles = Triangulo::new(3.0, 3.0, 4.0);
-        assert_eq!(isosceles.determinar_tipo(), "Isosceles");
+        let isosceles = Triangulo::new(3.0, 3.0, 4.0).unwrap();
+        assert_eq!(isosceles.determinar_tipo(), TipoPorLados::Isosceles);
 
         // Triángulo escaleno
-        let escaleno = Triangulo::new(3.0, 4.0, 5.0);
-        assert_eq!(escaleno.determinar_tipo(), "Escaleno");
+        let escaleno = Triangulo::new(3.0, 4.0, 5.0).unwrap();
+        assert_eq!(escaleno.determinar_tipo(), TipoPorLados::Escaleno);
     }
 
     #[test]
     fn test_calcular_area() {
         // Triángulo equilátero
-        let equilatero = Triangulo::new(3.0, 3.0, 3.0);
+        let equilatero = Triangulo::new(3.0, 3.0, 3.0).unwrap();
         assert!(equilatero.calcular_area() - 3.8971143170299753 < 0.000001);
 
         // Triángulo isósceles
-        let isosceles = Triangulo::new(3.0, 3.0, 4.0);
+        let isosceles = Triangulo::new(3.0, 3.0, 4.0).unwrap();
         assert!(isosceles.calcular_area() - 4.47213595499958 < 0.000001);
 
         // Triángulo escaleno
-        let escaleno = Triangulo::new(3.0, 4.0, 5.0);
+        let escaleno = Triangulo::new(3.0, 4.0, 5.0).unwrap();
         assert!(escaleno.calcular_area() - 6.0 < 0.000001);
     }
 
     #[test]
     fn test_calcular_perimetro() {
         // Triángulo equilátero
-        let equilatero = Triangulo::new(3.0, 3.0, 3.0);
+        let equilatero = Triangulo::new(3.0, 3.0, 3.0).unwrap();
         assert_eq!(equilatero.calcular_perimetro(), 9.0);
 
         // Triángulo isósceles
-        let isosceles = Triangulo::new(3.0, 3.0, 4.0);
+        let isosceles = Triangulo::new(3.0, 3.0, 4.0).unwrap();
         assert_eq!(isosceles.calcular_perimetro(), 10.0);
 
         // Triángulo escaleno
-        let escaleno = Triangulo::new(3.0, 4.0, 5.0);
+        let escaleno = Triangulo::new(3.0, 4.0, 5.0).unwrap();
         assert_eq!(escaleno.calcular_perimetro(), 12.0);
-    }
\ No newline at end of file
+    }
+
+    #[test]
+    fn test_triangulos_invalidos() {
+        assert_eq!(Triangulo::new(1.0, 2.0, 10.0).unwrap_err(), ErrorTriangulo::DesigualdadTriangular);
+        // degenerado: los tres vertices quedan alineados
+        assert_eq!(Triangulo::new(1.0, 2.0, 3.0).unwrap_err(), ErrorTriangulo::DesigualdadTriangular);
+        assert_eq!(Triangulo::new(0.1, 0.2, 0.3).unwrap_err(), ErrorTriangulo::DesigualdadTriangular);
+        assert_eq!(Triangulo::new(0.0, 3.0, 3.0).unwrap_err(), ErrorTriangulo::LadoInvalido);
+        assert_eq!(Triangulo::new(-3.0, 3.0, 3.0).unwrap_err(), ErrorTriangulo::LadoInvalido);
+        assert_eq!(Triangulo::new(f64::NAN, 3.0, 3.0).unwrap_err(), ErrorTriangulo::LadoInvalido);
+        assert_eq!(Triangulo::new(f64::INFINITY, 3.0, 3.0).unwrap_err(), ErrorTriangulo::LadoInvalido);
+    }
+
+    #[test]
+    fn test_lados_casi_iguales() {
+        let equilatero = Triangulo::new(0.1 + 0.2, 0.3, 0.3).unwrap();
+        assert_eq!(equilatero.determinar_tipo(), TipoPorLados::Equilatero);
+
+        let isosceles = Triangulo::new(3.0, 3.0 + 1e-12, 4.0).unwrap();
+        assert_eq!(isosceles.determinar_tipo(), TipoPorLados::Isosceles);
+
+        let escaleno = Triangulo::new(3.0, 3.001, 4.0).unwrap();
+        assert_eq!(escaleno.determinar_tipo(), TipoPorLados::Escaleno);
+    }
+
+    #[test]
+    fn test_determinar_tipo_por_angulos() {
+        assert_eq!(Triangulo::new(3.0, 4.0, 5.0).unwrap().determinar_tipo_por_angulos(), TipoPorAngulos::Rectangulo);
+        assert_eq!(Triangulo::new(1.0, 1.0, 2.0_f64.sqrt()).unwrap().determinar_tipo_por_angulos(), TipoPorAngulos::Rectangulo);
+        assert_eq!(Triangulo::new(3.0, 3.0, 3.0).unwrap().determinar_tipo_por_angulos(), TipoPorAngulos::Acutangulo);
+        assert_eq!(Triangulo::new(3.0, 3.0, 5.0).unwrap().determinar_tipo_por_angulos(), TipoPorAngulos::Obtusangulo);
+    }
+
+    #[test]
+    fn test_calcular_angulos() {
+        let (a, b, c) = Triangulo::new(3.0, 4.0, 5.0).unwrap().calcular_angulos();
+        assert!((a - 36.86989764584402).abs() < 0.000001);
+        assert!((b - 53.13010235415598).abs() < 0.000001);
+        assert!((c - 90.0).abs() < 0.000001);
+
+        let (a, b, c) = Triangulo::new(3.0, 3.0, 3.0).unwrap().calcular_angulos();
+        assert!((a - 60.0).abs() < 0.000001 && (b - 60.0).abs() < 0.000001 && (c - 60.0).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_alturas_y_radios() {
+        let triangulo = Triangulo::new(3.0, 4.0, 5.0).unwrap();
+        let (ha, hb, hc) = triangulo.calcular_alturas();
+        assert!((ha - 4.0).abs() < 0.000001);
+        assert!((hb - 3.0).abs() < 0.000001);
+        assert!((hc - 2.4).abs() < 0.000001);
+        assert!((triangulo.calcular_inradio() - 1.0).abs() < 0.000001);
+        assert!((triangulo.calcular_circunradio() - 2.5).abs() < 0.000001);
+
+        let equilatero = Triangulo::new(3.0, 3.0, 3.0).unwrap();
+        assert!((equilatero.calcular_inradio() - 3.0_f64.sqrt() / 2.0).abs() < 0.000001);
+        assert!((equilatero.calcular_circunradio() - 3.0_f64.sqrt()).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_ubicar_triangulo() {
+        use super::geometria::Figura;
+
+        let triangulo = Triangulo::new(5.0, 4.0, 3.0).unwrap();
+        let ubicado = triangulo.ubicar_en(Punto::new(1.0, 1.0)).unwrap();
+        assert!((ubicado.area() - triangulo.calcular_area()).abs() < 0.000001);
+        assert!((ubicado.perimetro() - triangulo.calcular_perimetro()).abs() < 0.000001);
+        assert!(ubicado.contiene_punto(&Punto::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn test_triangulo_con_unidades() {
+        let triangulo = Triangulo::con_medidas(
+            Medida::new(30, Unidad::Centimetro),
+            Medida::new(400, Unidad::Milimetro),
+            Medida::new(5, Unidad::Centimetro).multiplicar_por(10).unwrap(),
+        ).unwrap();
+        assert_eq!(triangulo.unidad, Unidad::Milimetro);
+        assert_eq!(triangulo.calcular_perimetro(), 1200.0);
+        assert_eq!(triangulo.calcular_area_en(Unidad::Centimetro), Ok(Superficie::new(600, Unidad::Centimetro)));
+        assert_eq!(triangulo.calcular_area_en(Unidad::Milimetro), Ok(Superficie::new(60_000, Unidad::Milimetro)));
+        assert_eq!(triangulo.calcular_perimetro_en(Unidad::Centimetro), Ok(Medida::new(120, Unidad::Centimetro)));
+        assert_eq!(triangulo.calcular_perimetro_en(Unidad::Metro), Err(ErrorMedida::ConversionInexacta));
+
+        // el equilatero de 3 m tiene 3,897 m²: se redondea
+        let equilatero = Triangulo::new(3.0, 3.0, 3.0).unwrap();
+        assert_eq!(equilatero.calcular_area_en(Unidad::Metro), Ok(Superficie::new(4, Unidad::Metro)));
+        assert_eq!(equilatero.calcular_area_en(Unidad::Centimetro), Ok(Superficie::new(38_971, Unidad::Centimetro)));
+        assert_eq!(Triangulo::new(0.1 + 0.2, 0.3, 0.3).unwrap().calcular_area_en(Unidad::Metro), Err(ErrorMedida::ConversionInexacta));
+
+        let desbordado = Triangulo::con_medidas(
+            Medida::new(u64::MAX, Unidad::Kilometro),
+            Medida::new(3, Unidad::Metro),
+            Medida::new(3, Unidad::Metro),
+        );
+        assert_eq!(desbordado.unwrap_err(), ErrorTriangulo::Medida(ErrorMedida::Desbordamiento));
+    }
+
+    #[test]
+    fn test_triangulo_con_lados_enormes() {
+        // con f64 el perimetro perderia las unidades sueltas
+        let lado = (1u64 << 60) + 1;
+        let triangulo = Triangulo::con_medidas(
+            Medida::new(lado, Unidad::Milimetro),
+            Medida::new(lado, Unidad::Milimetro),
+            Medida::new(2, Unidad::Milimetro),
+        ).unwrap();
+        assert_eq!(triangulo.calcular_perimetro_en(Unidad::Milimetro), Ok(Medida::new(2 * lado + 2, Unidad::Milimetro)));
+        // area = sqrt(lado² - 1), a menos de medio mm² de lado
+        assert_eq!(triangulo.calcular_area_en(Unidad::Milimetro), Ok(Superficie::new(lado, Unidad::Milimetro)));
+        let enorme = Medida::new(1 << 62, Unidad::Milimetro);
+        assert_eq!(Triangulo::con_medidas(enorme, enorme, enorme).unwrap().calcular_area_en(Unidad::Milimetro), Err(ErrorMedida::Desbordamiento));
+
+        let degenerado = Triangulo::con_medidas(
+            Medida::new(lado, Unidad::Milimetro),
+            Medida::new(lado - 1, Unidad::Milimetro),
+            Medida::new(1, Unidad::Milimetro),
+        );
+        assert_eq!(degenerado.unwrap_err(), ErrorTriangulo::DesigualdadTriangular);
+        assert_eq!(Triangulo::con_medidas(Medida::new(0, Unidad::Metro), Medida::new(1, Unidad::Metro), Medida::new(1, Unidad::Metro)).unwrap_err(), ErrorTriangulo::LadoInvalido);
+    }
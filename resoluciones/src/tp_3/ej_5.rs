@@ -1,12 +1,22 @@
+use std::collections::HashMap;
+
+use crate::dinero::{Dinero, ErrorDinero, Porcentaje, Redondeo};
+#[cfg(test)]
+use crate::dinero::{pesos, Moneda};
+use super::motor_precios::{BaseDeCalculo, Desglose, MotorDePrecios, Regla, TipoAjuste};
+
+const REDONDEO: Redondeo = Redondeo::MitadHaciaArriba;
+
+#[derive(Debug, Clone, PartialEq)]
 struct Producto {
     nombre: String,
-    precio_bruto: f64,
+    precio_bruto: Dinero,
     id: u32,
 }
 
 impl Producto {
 
-    fn new(nombre: String, precio_bruto:f64, id:u32) -> Self {
+    fn new(nombre: String, precio_bruto:Dinero, id:u32) -> Self {
         Producto {
             nombre,
             precio_bruto,
@@ -14,59 +24,292 @@ impl Producto {
         }
     }
 
-    fn calcular_impuestos(&self, porcentaje_de_impuestos: f64) -> f64 {
-        self.precio_bruto * (porcentaje_de_impuestos / 100.0)
+    fn calcular_impuestos(&self, porcentaje_de_impuestos: Porcentaje) -> Result<Dinero, ErrorDinero> {
+        self.precio_bruto.porcentaje(porcentaje_de_impuestos, REDONDEO)
+    }
+
+    fn aplicar_descuento(&self, porcentaje_de_descuento: Porcentaje) -> Result<Dinero, ErrorDinero> {
+        self.precio_bruto.porcentaje(porcentaje_de_descuento, REDONDEO)
     }
 
-    fn aplicar_descuento(&self, porcentaje_de_descuento: f64) -> f64 {
-        self.precio_bruto * (porcentaje_de_descuento / 100.0)
+    // impuesto y descuento se calculan ambos sobre el precio bruto
+    fn calcular_precio_total(&self, porcentaje_de_impuestos: Option<Porcentaje>, porcentaje_de_descuento: Option<Porcentaje>) -> Result<Dinero, ErrorDinero> {
+        let mut motor = MotorDePrecios::new(REDONDEO);
+        if let Some(porcentaje) = porcentaje_de_impuestos {
+            motor.agregar_regla(Regla::new("Impuestos", TipoAjuste::Impuesto(porcentaje), BaseDeCalculo::PrecioBruto));
+        }
+        if let Some(porcentaje) = porcentaje_de_descuento {
+            motor.agregar_regla(Regla::new("Descuento", TipoAjuste::Descuento(porcentaje), BaseDeCalculo::PrecioBruto));
+        }
+        Ok(self.calcular_precio_con(&motor)?.precio_final())
     }
 
-    fn calcular_precio_total(&self, porcentaje_de_impuestos: Option<f64>, porcentaje_de_descuento: Option<f64>) -> f64 {
-        let impuestos = match porcentaje_de_impuestos {
-            Some(porcentaje) => self.calcular_impuestos(porcentaje),
-            None => 0.0,
-        };
-        let descuento = match porcentaje_de_descuento {
-            Some(porcentaje) => self.aplicar_descuento(porcentaje),
-            None => 0.0,
-        };
-        self.precio_bruto + impuestos - descuento
+    fn calcular_precio_con(&self, motor: &MotorDePrecios) -> Result<Desglose, ErrorDinero> {
+        motor.aplicar(self.precio_bruto)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ErrorCatalogo {
+    ProductoInexistente,
+    ProductoDuplicado,
+    ProductoConReservas,
+    StockInsuficiente,
+    ReservaInsuficiente,
+    StockDesbordado, // la reposicion supera el maximo que se puede guardar
+    Precio(ErrorDinero),
+}
+
+struct EntradaCatalogo {
+    producto: Producto,
+    stock: u32,
+    reservado: u32, // unidades apartadas que todavia forman parte del stock
+    umbral_stock_bajo: u32,
+}
+
+impl EntradaCatalogo {
+    fn disponible(&self) -> u32 {
+        self.stock - self.reservado
+    }
+}
+
+struct Catalogo {
+    productos: HashMap<u32, EntradaCatalogo>,
+}
+
+impl Catalogo {
+    fn new() -> Catalogo {
+        Catalogo { productos: HashMap::new() }
+    }
+
+    fn agregar_producto(&mut self, producto: Producto, stock: u32, umbral_stock_bajo: u32) -> Result<(), ErrorCatalogo> {
+        if self.productos.contains_key(&producto.id) {
+            return Err(ErrorCatalogo::ProductoDuplicado);
+        }
+        self.productos.insert(producto.id, EntradaCatalogo { producto, stock, reservado: 0, umbral_stock_bajo });
+        Ok(())
+    }
+
+    // reemplaza los datos del producto con el mismo id sin tocar su stock
+    fn actualizar_producto(&mut self, producto: Producto) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&producto.id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        entrada.producto = producto;
+        Ok(())
+    }
+
+    fn eliminar_producto(&mut self, id: u32) -> Result<Producto, ErrorCatalogo> {
+        let entrada = self.productos.get(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        if entrada.reservado > 0 {
+            return Err(ErrorCatalogo::ProductoConReservas);
+        }
+        Ok(self.productos.remove(&id).unwrap().producto)
+    }
+
+    fn obtener_producto(&self, id: u32) -> Option<&Producto> {
+        self.productos.get(&id).map(|entrada| &entrada.producto)
+    }
+
+    fn stock_disponible(&self, id: u32) -> Option<u32> {
+        self.productos.get(&id).map(|entrada| entrada.disponible())
+    }
+
+    fn modificar_umbral(&mut self, id: u32, umbral_stock_bajo: u32) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        entrada.umbral_stock_bajo = umbral_stock_bajo;
+        Ok(())
+    }
+
+    fn reponer_stock(&mut self, id: u32, cantidad: u32) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        entrada.stock = entrada.stock.checked_add(cantidad).ok_or(ErrorCatalogo::StockDesbordado)?;
+        Ok(())
+    }
+
+    fn reservar(&mut self, id: u32, cantidad: u32) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        if entrada.disponible() < cantidad {
+            return Err(ErrorCatalogo::StockInsuficiente);
+        }
+        entrada.reservado += cantidad;
+        Ok(())
+    }
+
+    // devuelve al stock disponible unidades que estaban reservadas
+    fn liberar(&mut self, id: u32, cantidad: u32) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        if entrada.reservado < cantidad {
+            return Err(ErrorCatalogo::ReservaInsuficiente);
+        }
+        entrada.reservado -= cantidad;
+        Ok(())
+    }
+
+    // las unidades reservadas salen definitivamente del stock
+    fn confirmar_reserva(&mut self, id: u32, cantidad: u32) -> Result<(), ErrorCatalogo> {
+        let entrada = self.productos.get_mut(&id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        if entrada.reservado < cantidad {
+            return Err(ErrorCatalogo::ReservaInsuficiente);
+        }
+        entrada.reservado -= cantidad;
+        entrada.stock -= cantidad;
+        Ok(())
+    }
+
+    fn productos_con_stock_bajo(&self) -> Vec<&Producto> {
+        let mut bajos: Vec<&Producto> = self.productos.values()
+            .filter(|entrada| entrada.disponible() <= entrada.umbral_stock_bajo)
+            .map(|entrada| &entrada.producto)
+            .collect();
+        bajos.sort_by_key(|producto| producto.id);
+        bajos
+    }
+
+    // sin distinguir mayusculas, ordenado por nombre
+    fn buscar_por_prefijo(&self, prefijo: &str) -> Vec<&Producto> {
+        let prefijo = prefijo.to_lowercase();
+        let mut encontrados: Vec<&Producto> = self.productos.values()
+            .map(|entrada| &entrada.producto)
+            .filter(|producto| producto.nombre.to_lowercase().starts_with(&prefijo))
+            .collect();
+        encontrados.sort_by(|a, b| a.nombre.cmp(&b.nombre).then(a.id.cmp(&b.id)));
+        encontrados
+    }
+
+    fn calcular_precio(&self, id: u32, motor: &MotorDePrecios) -> Result<Desglose, ErrorCatalogo> {
+        let producto = self.obtener_producto(id).ok_or(ErrorCatalogo::ProductoInexistente)?;
+        producto.calcular_precio_con(motor).map_err(ErrorCatalogo::Precio)
     }
 }
 
 #[test]
     fn test_calcular_impuestos() {
-        let producto = Producto::new("Producto A".to_string(), 100.0, 1);
-        assert_eq!(producto.calcular_impuestos(10.0), 10.0);
+        let producto = Producto::new("Producto A".to_string(), pesos(100), 1);
+        assert_eq!(producto.calcular_impuestos(Porcentaje::new(10)), Ok(pesos(10)));
     }
 
     #[test]
     fn test_aplicar_descuento() {
-        let producto = Producto::new("Producto B".to_string(), 100.0, 2);
-        assert_eq!(producto.aplicar_descuento(20.0), 20.0);
+        let producto = Producto::new("Producto B".to_string(), pesos(100), 2);
+        assert_eq!(producto.aplicar_descuento(Porcentaje::new(20)), Ok(pesos(20)));
     }
 
     #[test]
     fn test_calcular_precio_total_con_impuestos_y_descuento() {
-        let producto = Producto::new("Producto C".to_string(), 100.0, 3);
-        assert_eq!(producto.calcular_precio_total(Some(10.0), Some(20.0)), 90.0);
+        let producto = Producto::new("Producto C".to_string(), pesos(100), 3);
+        assert_eq!(producto.calcular_precio_total(Some(Porcentaje::new(10)), Some(Porcentaje::new(20))), Ok(pesos(90)));
     }
 
     #[test]
     fn test_calcular_precio_total_con_impuestos() {
-        let producto = Producto::new("Producto D".to_string(), 100.0, 4);
-        assert_eq!(producto.calcular_precio_total(Some(10.0), None), 110.0);
+        let producto = Producto::new("Producto D".to_string(), pesos(100), 4);
+        assert_eq!(producto.calcular_precio_total(Some(Porcentaje::new(10)), None), Ok(pesos(110)));
     }
 
     #[test]
     fn test_calcular_precio_total_con_descuento() {
-        let producto = Producto::new("Producto E".to_string(), 100.0, 5);
-        assert_eq!(producto.calcular_precio_total(None, Some(20.0)), 80.0);
+        let producto = Producto::new("Producto E".to_string(), pesos(100), 5);
+        assert_eq!(producto.calcular_precio_total(None, Some(Porcentaje::new(20))), Ok(pesos(80)));
     }
 
     #[test]
     fn test_calcular_precio_total_sin_impuestos_ni_descuento() {
-        let producto = Producto::new("Producto F".to_string(), 100.0, 6);
-        assert_eq!(producto.calcular_precio_total(None, None), 100.0);
-    }
\ No newline at end of file
+        let producto = Producto::new("Producto F".to_string(), pesos(100), 6);
+        assert_eq!(producto.calcular_precio_total(None, None), Ok(pesos(100)));
+    }
+
+    #[test]
+    fn test_calcular_precio_total_con_centavos() {
+        // 10,5 % de 19,99 = 2,09895 -> 2,10
+        let producto = Producto::new("Producto G".to_string(), Dinero::new(1999, Moneda::Ars), 7);
+        assert_eq!(producto.calcular_impuestos(Porcentaje::con_decimales(1050)), Ok(Dinero::new(210, Moneda::Ars)));
+        assert_eq!(producto.calcular_precio_total(Some(Porcentaje::con_decimales(1050)), Some(Porcentaje::new(5))), Ok(Dinero::new(2109, Moneda::Ars)));
+    }
+
+    #[test]
+    fn test_calcular_precio_con_reglas() {
+        let producto = Producto::new("Producto H".to_string(), pesos(200), 8);
+        let motor = MotorDePrecios::con_reglas(vec![
+            Regla::new("Descuento 10%", TipoAjuste::Descuento(Porcentaje::new(10)), BaseDeCalculo::Subtotal),
+            Regla::iva_reducido(),
+        ], REDONDEO);
+        let desglose = producto.calcular_precio_con(&motor).unwrap();
+        assert_eq!(desglose.precio_bruto(), pesos(200));
+        assert_eq!(desglose.ajustes()[0].monto(), pesos(-20));
+        assert_eq!(desglose.ajustes()[1].monto(), Dinero::new(1890, Moneda::Ars));
+        assert_eq!(desglose.precio_final(), Dinero::new(19890, Moneda::Ars));
+    }
+
+    #[test]
+    fn test_catalogo_altas_bajas_y_modificaciones() {
+        let mut catalogo = Catalogo::new();
+        catalogo.agregar_producto(Producto::new("Yerba".to_string(), pesos(1500), 1), 10, 3).unwrap();
+        catalogo.agregar_producto(Producto::new("Azucar".to_string(), pesos(900), 3), 20, 5).unwrap();
+        assert_eq!(catalogo.agregar_producto(Producto::new("Otra yerba".to_string(), pesos(1), 1), 1, 1), Err(ErrorCatalogo::ProductoDuplicado));
+
+        catalogo.actualizar_producto(Producto::new("Yerba mate".to_string(), pesos(1700), 1)).unwrap();
+        assert_eq!(catalogo.obtener_producto(1).unwrap().nombre, "Yerba mate");
+        assert_eq!(catalogo.stock_disponible(1), Some(10));
+        assert_eq!(catalogo.actualizar_producto(Producto::new("Nada".to_string(), pesos(1), 99)), Err(ErrorCatalogo::ProductoInexistente));
+
+        assert_eq!(catalogo.eliminar_producto(3).unwrap().nombre, "Azucar");
+        assert!(catalogo.obtener_producto(3).is_none());
+        assert_eq!(catalogo.eliminar_producto(3), Err(ErrorCatalogo::ProductoInexistente));
+    }
+
+    #[test]
+    fn test_catalogo_reservas() {
+        let mut catalogo = Catalogo::new();
+        catalogo.agregar_producto(Producto::new("Yerba".to_string(), pesos(1500), 1), 10, 3).unwrap();
+        catalogo.reservar(1, 4).unwrap();
+        assert_eq!(catalogo.stock_disponible(1), Some(6));
+        assert_eq!(catalogo.reservar(1, 7), Err(ErrorCatalogo::StockInsuficiente));
+        assert_eq!(catalogo.eliminar_producto(1), Err(ErrorCatalogo::ProductoConReservas));
+
+        catalogo.liberar(1, 1).unwrap();
+        assert_eq!(catalogo.stock_disponible(1), Some(7));
+        assert_eq!(catalogo.liberar(1, 4), Err(ErrorCatalogo::ReservaInsuficiente));
+
+        catalogo.confirmar_reserva(1, 3).unwrap();
+        assert_eq!(catalogo.stock_disponible(1), Some(7));
+        assert_eq!(catalogo.productos.get(&1).unwrap().stock, 7);
+        assert_eq!(catalogo.reservar(99, 1), Err(ErrorCatalogo::ProductoInexistente));
+    }
+
+    #[test]
+    fn test_catalogo_stock_bajo() {
+        let mut catalogo = Catalogo::new();
+        catalogo.agregar_producto(Producto::new("Yerba".to_string(), pesos(1500), 1), 10, 3).unwrap();
+        catalogo.agregar_producto(Producto::new("Yogur".to_string(), pesos(800), 2), 2, 5).unwrap();
+        catalogo.agregar_producto(Producto::new("Azucar".to_string(), pesos(900), 3), 20, 5).unwrap();
+        let bajos: Vec<u32> = catalogo.productos_con_stock_bajo().iter().map(|p| p.id).collect();
+        assert_eq!(bajos, vec![2]);
+
+        // las unidades reservadas no cuentan como disponibles
+        catalogo.reservar(1, 7).unwrap();
+        let bajos: Vec<u32> = catalogo.productos_con_stock_bajo().iter().map(|p| p.id).collect();
+        assert_eq!(bajos, vec![1, 2]);
+
+        catalogo.reponer_stock(2, 10).unwrap();
+        catalogo.modificar_umbral(1, 0).unwrap();
+        assert!(catalogo.productos_con_stock_bajo().is_empty());
+
+        // reponer por encima del maximo no deja el stock a medias
+        assert_eq!(catalogo.reponer_stock(3, u32::MAX), Err(ErrorCatalogo::StockDesbordado));
+        assert_eq!(catalogo.stock_disponible(3), Some(20));
+    }
+
+    #[test]
+    fn test_catalogo_busqueda_y_precio() {
+        let mut catalogo = Catalogo::new();
+        catalogo.agregar_producto(Producto::new("Yerba".to_string(), pesos(1500), 1), 10, 3).unwrap();
+        catalogo.agregar_producto(Producto::new("Yogur".to_string(), pesos(800), 2), 2, 5).unwrap();
+        catalogo.agregar_producto(Producto::new("Azucar".to_string(), pesos(900), 3), 20, 5).unwrap();
+        let nombres: Vec<&str> = catalogo.buscar_por_prefijo("y").iter().map(|p| p.nombre.as_str()).collect();
+        assert_eq!(nombres, vec!["Yerba", "Yogur"]);
+        assert_eq!(catalogo.buscar_por_prefijo("AZU").len(), 1);
+        assert!(catalogo.buscar_por_prefijo("leche").is_empty());
+
+        let motor = MotorDePrecios::con_reglas(vec![Regla::iva_general()], REDONDEO);
+        assert_eq!(catalogo.calcular_precio(1, &motor).unwrap().precio_final(), pesos(1815));
+        assert_eq!(catalogo.calcular_precio(99, &motor).err(), Some(ErrorCatalogo::ProductoInexistente));
+    }
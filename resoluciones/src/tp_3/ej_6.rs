@@ -1,29 +1,338 @@
-struct Examen {
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::ej_3::Fecha;
+
+// pendientes mas chicas que esto se consideran sin cambio
+const TOLERANCIA_TENDENCIA: f64 = 1e-9;
+
+#[derive(Debug, PartialEq)]
+enum Tendencia {
+    Mejorando,
+    Empeorando,
+    Estable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TipoExamen {
+    Parcial,
+    Recuperatorio,
+    Final,
+    TrabajoPractico,
+}
+
+impl fmt::Display for TipoExamen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nombre = match self {
+            TipoExamen::Parcial => "Parcial",
+            TipoExamen::Recuperatorio => "Recuperatorio",
+            TipoExamen::Final => "Final",
+            TipoExamen::TrabajoPractico => "Trabajo práctico",
+        };
+        write!(f, "{}", nombre)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorEscala {
+    NotaFueraDeRango,
+    NotaNoNumerica,
+    EscalaInvalida,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Letra {
+    F,
+    D,
+    C,
+    B,
+    A,
+}
+
+// escala de calificaciones con sus notas minimas para aprobar y para promocionar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Escala {
+    minima: f64,
+    maxima: f64,
+    aprobacion: f64,
+    promocion: f64,
+}
+
+impl Escala {
+    pub fn new(minima: f64, maxima: f64, aprobacion: f64, promocion: f64) -> Result<Escala, ErrorEscala> {
+        let valores = [minima, maxima, aprobacion, promocion];
+        if valores.iter().any(|v| !v.is_finite()) || !(minima <= aprobacion && aprobacion <= promocion && promocion <= maxima && minima < maxima) {
+            return Err(ErrorEscala::EscalaInvalida);
+        }
+        Ok(Escala { minima, maxima, aprobacion, promocion })
+    }
+
+    pub fn centesimal() -> Escala {
+        Escala { minima: 0.0, maxima: 100.0, aprobacion: 60.0, promocion: 80.0 }
+    }
+
+    pub fn unlp() -> Escala {
+        Escala { minima: 1.0, maxima: 10.0, aprobacion: 4.0, promocion: 6.0 }
+    }
+
+    pub fn validar(&self, nota: f64) -> Result<f64, ErrorEscala> {
+        if nota.is_nan() {
+            return Err(ErrorEscala::NotaNoNumerica);
+        }
+        if nota < self.minima || nota > self.maxima {
+            return Err(ErrorEscala::NotaFueraDeRango);
+        }
+        Ok(nota)
+    }
+
+    pub fn aprueba(&self, nota: f64) -> bool {
+        nota >= self.aprobacion
+    }
+
+    pub fn promociona(&self, nota: f64) -> bool {
+        nota >= self.promocion
+    }
+
+    // tramos que se corresponden entre escalas: asi un 4 de la UNLP sigue siendo
+    // la nota de aprobacion al pasarlo a otra escala
+    fn tramos(&self) -> [(f64, f64); 3] {
+        [(self.minima, self.aprobacion), (self.aprobacion, self.promocion), (self.promocion, self.maxima)]
+    }
+
+    fn convertir(&self, nota: f64, destino: &Escala) -> Result<f64, ErrorEscala> {
+        let nota = self.validar(nota)?;
+        for ((desde, hasta), (destino_desde, destino_hasta)) in self.tramos().into_iter().zip(destino.tramos()) {
+            if nota <= hasta {
+                if hasta == desde {
+                    return Ok(destino_hasta);
+                }
+                return Ok(destino_desde + (nota - desde) / (hasta - desde) * (destino_hasta - destino_desde));
+            }
+        }
+        Ok(destino.maxima)
+    }
+
+    // desaprobado es F; de la aprobacion al maximo se reparte en cuatro tramos iguales
+    fn a_letra(&self, nota: f64) -> Result<Letra, ErrorEscala> {
+        let nota = self.validar(nota)?;
+        if !self.aprueba(nota) {
+            return Ok(Letra::F);
+        }
+        let tramo = (self.maxima - self.aprobacion) / 4.0;
+        let letra = if nota >= self.aprobacion + 3.0 * tramo {
+            Letra::A
+        } else if nota >= self.aprobacion + 2.0 * tramo {
+            Letra::B
+        } else if nota >= self.aprobacion + tramo {
+            Letra::C
+        } else {
+            Letra::D
+        };
+        Ok(letra)
+    }
+
+    // la nota mas baja que corresponde a la letra
+    fn desde_letra(&self, letra: Letra) -> f64 {
+        let tramo = (self.maxima - self.aprobacion) / 4.0;
+        match letra {
+            Letra::F => self.minima,
+            Letra::D => self.aprobacion,
+            Letra::C => self.aprobacion + tramo,
+            Letra::B => self.aprobacion + 2.0 * tramo,
+            Letra::A => self.aprobacion + 3.0 * tramo,
+        }
+    }
+}
+
+// el peso que se quiso usar: tiene que ser un numero mayor que 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PesoInvalido(pub f64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Examen {
     materia: String,
     nota: f64,
+    fecha: Option<Fecha>,
+    tipo: TipoExamen,
+    peso: f64,
 }
 
-struct Estudiante {
+pub struct Estudiante {
     nombre: String,
     id: u32,
     calificaciones: Vec<Examen>,
 }
 
 impl Examen {
-    fn new(materia: String, nota: f64) -> Examen {
+    pub fn new(materia: String, nota: f64) -> Examen {
         Examen {
             materia,
             nota,
+            fecha: None,
+            tipo: TipoExamen::Parcial,
+            peso: 1.0,
+        }
+    }
+
+    pub fn con_fecha(mut self, fecha: Fecha) -> Examen {
+        self.fecha = Some(fecha);
+        self
+    }
+
+    pub fn con_tipo(mut self, tipo: TipoExamen) -> Examen {
+        self.tipo = tipo;
+        self
+    }
+
+    pub fn con_peso(mut self, peso: f64) -> Result<Examen, PesoInvalido> {
+        if !peso.is_finite() || peso <= 0.0 {
+            return Err(PesoInvalido(peso));
+        }
+        self.peso = peso;
+        Ok(self)
+    }
+
+    pub fn materia(&self) -> &str {
+        &self.materia
+    }
+
+    pub fn nota(&self) -> f64 {
+        self.nota
+    }
+
+    pub fn fecha(&self) -> Option<&Fecha> {
+        self.fecha.as_ref()
+    }
+
+    pub fn tipo(&self) -> TipoExamen {
+        self.tipo
+    }
+
+    pub fn peso(&self) -> f64 {
+        self.peso
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReglaRecuperatorio {
+    Reemplaza, // la nota del recuperatorio pisa la del parcial aunque sea menor
+    MejorNota,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum EstadoAcademico {
+    Promocionado,
+    CursadaAprobada,
+    Desaprobado,
+}
+
+impl fmt::Display for EstadoAcademico {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nombre = match self {
+            EstadoAcademico::Promocionado => "Promocionado",
+            EstadoAcademico::CursadaAprobada => "Cursada aprobada",
+            EstadoAcademico::Desaprobado => "Desaprobado",
+        };
+        write!(f, "{}", nombre)
+    }
+}
+
+pub struct ReglasCursada {
+    escala: Escala,
+    recuperatorio: ReglaRecuperatorio,
+}
+
+impl ReglasCursada {
+    pub fn new(escala: Escala, recuperatorio: ReglaRecuperatorio) -> ReglasCursada {
+        ReglasCursada { escala, recuperatorio }
+    }
+
+    // las instancias que cuentan para la nota, con los recuperatorios ya aplicados: (nota, peso)
+    fn instancias_evaluadas(&self, examenes: &[&Examen]) -> Vec<(f64, f64)> {
+        let mut ordenados = examenes.to_vec();
+        // los examenes sin fecha quedan primero, en el orden en que se cargaron
+        ordenados.sort_by(|a, b| a.fecha.cmp(&b.fecha));
+
+        let mut instancias: Vec<(f64, f64)> = Vec::new();
+        let mut parciales_sin_recuperar: Vec<usize> = Vec::new();
+        for examen in ordenados {
+            match examen.tipo {
+                TipoExamen::Recuperatorio => {
+                    // recupera el ultimo parcial desaprobado; si no hay ninguno, no cuenta
+                    let pendiente = parciales_sin_recuperar.iter()
+                        .rposition(|i| !self.escala.aprueba(instancias[*i].0));
+                    if let Some(posicion) = pendiente {
+                        let i = parciales_sin_recuperar.remove(posicion);
+                        instancias[i].0 = match self.recuperatorio {
+                            ReglaRecuperatorio::Reemplaza => examen.nota,
+                            ReglaRecuperatorio::MejorNota => instancias[i].0.max(examen.nota),
+                        };
+                    }
+                }
+                tipo => {
+                    if tipo == TipoExamen::Parcial {
+                        parciales_sin_recuperar.push(instancias.len());
+                    }
+                    instancias.push((examen.nota, examen.peso));
+                }
+            }
         }
+        instancias
     }
 }
 
 impl Estudiante {
-    fn new(nombre: String, id: u32, calificaciones: Vec<Examen>) -> Estudiante {
+    pub fn new(nombre: String, id: u32, calificaciones: Vec<Examen>) -> Estudiante {
         Estudiante {nombre, id, calificaciones}
     }
 
-    fn obtener_promedio(&self) -> f64 {
+    pub fn nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn calificaciones(&self) -> &[Examen] {
+        &self.calificaciones
+    }
+
+    pub fn agregar_examen(&mut self, examen: Examen) {
+        self.calificaciones.push(examen);
+    }
+
+    fn examenes_de(&self, materia: &str) -> Vec<&Examen> {
+        self.calificaciones.iter().filter(|e| e.materia == materia).collect()
+    }
+
+    // promedio ponderado por el peso de cada examen
+    pub fn nota_final_en(&self, materia: &str, reglas: &ReglasCursada) -> Option<f64> {
+        let instancias = reglas.instancias_evaluadas(&self.examenes_de(materia));
+        let peso_total: f64 = instancias.iter().map(|(_, peso)| peso).sum();
+        if peso_total <= 0.0 {
+            return None;
+        }
+        Some(instancias.iter().map(|(nota, peso)| nota * peso).sum::<f64>() / peso_total)
+    }
+
+    pub fn estado_en(&self, materia: &str, reglas: &ReglasCursada) -> Option<EstadoAcademico> {
+        let nota_final = self.nota_final_en(materia, reglas)?;
+        let instancias = reglas.instancias_evaluadas(&self.examenes_de(materia));
+        // con alguna instancia desaprobada no alcanza el promedio
+        if instancias.iter().any(|(nota, _)| !reglas.escala.aprueba(*nota)) {
+            return Some(EstadoAcademico::Desaprobado);
+        }
+        if reglas.escala.promociona(nota_final) {
+            Some(EstadoAcademico::Promocionado)
+        } else if reglas.escala.aprueba(nota_final) {
+            Some(EstadoAcademico::CursadaAprobada)
+        } else {
+            Some(EstadoAcademico::Desaprobado)
+        }
+    }
+
+    pub fn obtener_promedio(&self) -> f64 {
         let cant = self.calificaciones.len() as f64;
         if cant == 0.0 {
             return 0.0
@@ -35,27 +344,179 @@ impl Estudiante {
         suma as f64 / cant
     }
 
-    fn obtener_calificacion_mas_alta(&self) -> f64 {
-        let mut max: f64 = -1.0;
-        for elem in &self.calificaciones {
-            if elem.nota > max {
-                max = elem.nota
-            }
+    fn notas(&self) -> Vec<f64> {
+        self.calificaciones.iter().map(|e| e.nota).collect()
+    }
+
+    fn obtener_calificacion_mas_alta(&self) -> Option<f64> {
+        self.notas().into_iter().reduce(f64::max)
+    }
+
+    fn obtener_calificacion_mas_baja(&self) -> Option<f64> {
+        self.notas().into_iter().reduce(f64::min)
+    }
+
+    fn obtener_mediana(&self) -> Option<f64> {
+        let mut notas = self.notas();
+        if notas.is_empty() {
+            return None;
+        }
+        notas.sort_by(f64::total_cmp);
+        let medio = notas.len() / 2;
+        if notas.len().is_multiple_of(2) {
+            Some((notas[medio - 1] + notas[medio]) / 2.0)
+        } else {
+            Some(notas[medio])
         }
-        max
     }
 
-    fn obtener_calificacion_mas_baja(&self) -> f64 {
-        let mut min: f64 = 99.0;
-        for elem in &self.calificaciones {
-            if elem.nota < min {
-                min = elem.nota
-            }
+    // desvio estandar poblacional
+    fn obtener_desvio_estandar(&self) -> Option<f64> {
+        let notas = self.notas();
+        if notas.is_empty() {
+            return None;
+        }
+        let promedio = self.obtener_promedio();
+        let varianza = notas.iter().map(|nota| (nota - promedio).powi(2)).sum::<f64>() / notas.len() as f64;
+        Some(varianza.sqrt())
+    }
+
+    // pendiente de la recta de regresion de las notas en el orden en que se rindieron
+    fn obtener_tendencia(&self) -> Option<Tendencia> {
+        let notas = self.notas();
+        if notas.len() < 2 {
+            return None;
+        }
+        let n = notas.len() as f64;
+        let promedio_x = (n - 1.0) / 2.0;
+        let promedio_y = notas.iter().sum::<f64>() / n;
+        let mut covarianza = 0.0;
+        let mut varianza_x = 0.0;
+        for (i, nota) in notas.iter().enumerate() {
+            let dx = i as f64 - promedio_x;
+            covarianza += dx * (nota - promedio_y);
+            varianza_x += dx * dx;
+        }
+        let pendiente = covarianza / varianza_x;
+        if pendiente.abs() < TOLERANCIA_TENDENCIA {
+            Some(Tendencia::Estable)
+        } else if pendiente > 0.0 {
+            Some(Tendencia::Mejorando)
+        } else {
+            Some(Tendencia::Empeorando)
         }
-        min
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorCurso {
+    EstudianteInexistente,
+    EstudianteDuplicado,
+    MateriaInexistente,
+    NoInscripto,
+    NotaInvalida(ErrorEscala),
+}
+
+pub struct Curso {
+    nombre: String,
+    reglas: ReglasCursada,
+    estudiantes: BTreeMap<u32, Estudiante>,
+    inscripciones: BTreeMap<String, BTreeSet<u32>>, // materia -> ids de los inscriptos
+}
+
+impl Curso {
+    // los recuperatorios reemplazan al parcial salvo que se indique otra regla
+    pub fn new(nombre: String, escala: Escala) -> Curso {
+        let reglas = ReglasCursada::new(escala, ReglaRecuperatorio::Reemplaza);
+        Curso { nombre, reglas, estudiantes: BTreeMap::new(), inscripciones: BTreeMap::new() }
+    }
+
+    pub fn con_recuperatorio(mut self, recuperatorio: ReglaRecuperatorio) -> Curso {
+        self.reglas.recuperatorio = recuperatorio;
+        self
+    }
+
+    pub fn agregar_materia(&mut self, materia: &str) -> bool {
+        if self.inscripciones.contains_key(materia) {
+            return false;
+        }
+        self.inscripciones.insert(materia.to_string(), BTreeSet::new());
+        true
+    }
+
+    pub fn inscribir_estudiante(&mut self, estudiante: Estudiante) -> Result<(), ErrorCurso> {
+        if self.estudiantes.contains_key(&estudiante.id) {
+            return Err(ErrorCurso::EstudianteDuplicado);
+        }
+        for examen in &estudiante.calificaciones {
+            self.reglas.escala.validar(examen.nota).map_err(ErrorCurso::NotaInvalida)?;
+        }
+        self.estudiantes.insert(estudiante.id, estudiante);
+        Ok(())
+    }
+
+    pub fn inscribir_en_materia(&mut self, id: u32, materia: &str) -> Result<(), ErrorCurso> {
+        if !self.estudiantes.contains_key(&id) {
+            return Err(ErrorCurso::EstudianteInexistente);
+        }
+        let inscriptos = self.inscripciones.get_mut(materia).ok_or(ErrorCurso::MateriaInexistente)?;
+        inscriptos.insert(id);
+        Ok(())
+    }
+
+    pub fn escala(&self) -> Escala {
+        self.reglas.escala
+    }
+
+    pub fn obtener_estudiante(&self, id: u32) -> Option<&Estudiante> {
+        self.estudiantes.get(&id)
+    }
+
+    pub fn cargar_nota(&mut self, id: u32, materia: &str, nota: f64) -> Result<(), ErrorCurso> {
+        self.cargar_examen(id, Examen::new(materia.to_string(), nota))
+    }
+
+    pub fn cargar_examen(&mut self, id: u32, examen: Examen) -> Result<(), ErrorCurso> {
+        let inscriptos = self.inscripciones.get(&examen.materia).ok_or(ErrorCurso::MateriaInexistente)?;
+        let estudiante = self.estudiantes.get_mut(&id).ok_or(ErrorCurso::EstudianteInexistente)?;
+        if !inscriptos.contains(&id) {
+            return Err(ErrorCurso::NoInscripto);
+        }
+        self.reglas.escala.validar(examen.nota).map_err(ErrorCurso::NotaInvalida)?;
+        estudiante.agregar_examen(examen);
+        Ok(())
+    }
+
+    fn inscriptos_en(&self, materia: &str) -> Result<Vec<&Estudiante>, ErrorCurso> {
+        let inscriptos = self.inscripciones.get(materia).ok_or(ErrorCurso::MateriaInexistente)?;
+        Ok(inscriptos.iter().filter_map(|id| self.estudiantes.get(id)).collect())
+    }
+
+    // promedio de las notas finales de los que ya rindieron la materia
+    fn promedio_por_materia(&self, materia: &str) -> Option<f64> {
+        let notas: Vec<f64> = self.inscriptos_en(materia).ok()?.iter()
+            .filter_map(|e| e.nota_final_en(materia, &self.reglas))
+            .collect();
+        if notas.is_empty() {
+            return None;
+        }
+        Some(notas.iter().sum::<f64>() / notas.len() as f64)
+    }
+
+    // de mayor a menor promedio; a igual promedio, por id
+    fn ranking(&self) -> Vec<&Estudiante> {
+        let mut ranking: Vec<&Estudiante> = self.estudiantes.values().collect();
+        ranking.sort_by(|a, b| b.obtener_promedio().total_cmp(&a.obtener_promedio()).then(a.id.cmp(&b.id)));
+        ranking
+    }
+
+    // los que todavia no rindieron la materia no cuentan como desaprobados
+    fn desaprobados(&self, materia: &str) -> Result<Vec<&Estudiante>, ErrorCurso> {
+        Ok(self.inscriptos_en(materia)?.into_iter()
+            .filter(|e| e.estado_en(materia, &self.reglas) == Some(EstadoAcademico::Desaprobado))
+            .collect())
+    }
+}
 
 #[test]
     fn test_obtener_promedio_sin_calificaciones() {
@@ -82,7 +543,7 @@ impl Estudiante {
             Examen::new("Historia".to_string(), 75.0),
         ];
         let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
-        assert_eq!(estudiante.obtener_calificacion_mas_alta(), 90.0);
+        assert_eq!(estudiante.obtener_calificacion_mas_alta(), Some(90.0));
     }
 
     #[test]
@@ -93,5 +554,265 @@ impl Estudiante {
             Examen::new("Historia".to_string(), 75.0),
         ];
         let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
-        assert_eq!(estudiante.obtener_calificacion_mas_baja(), 75.0);
-    }
\ No newline at end of file
+        assert_eq!(estudiante.obtener_calificacion_mas_baja(), Some(75.0));
+    }
+
+    #[test]
+    fn test_estadisticas_sin_calificaciones() {
+        let estudiante = Estudiante::new("Juan".to_string(), 1, Vec::new());
+        assert_eq!(estudiante.obtener_calificacion_mas_alta(), None);
+        assert_eq!(estudiante.obtener_calificacion_mas_baja(), None);
+        assert_eq!(estudiante.obtener_mediana(), None);
+        assert_eq!(estudiante.obtener_desvio_estandar(), None);
+        assert_eq!(estudiante.obtener_tendencia(), None);
+    }
+
+    #[test]
+    fn test_estadisticas_con_una_calificacion() {
+        let estudiante = Estudiante::new("Juan".to_string(), 1, vec![Examen::new("Historia".to_string(), 70.0)]);
+        assert_eq!(estudiante.obtener_calificacion_mas_alta(), Some(70.0));
+        assert_eq!(estudiante.obtener_calificacion_mas_baja(), Some(70.0));
+        assert_eq!(estudiante.obtener_mediana(), Some(70.0));
+        assert_eq!(estudiante.obtener_desvio_estandar(), Some(0.0));
+        assert_eq!(estudiante.obtener_tendencia(), None);
+    }
+
+    #[test]
+    fn test_estadisticas_fuera_de_rango() {
+        // antes un 100 nunca era la minima y sin notas la maxima daba -1
+        let calificaciones = vec![
+            Examen::new("Matemáticas".to_string(), 100.0),
+            Examen::new("Ciencias".to_string(), 120.0),
+            Examen::new("Historia".to_string(), -4.0),
+            Examen::new("Lengua".to_string(), 100.0),
+        ];
+        let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
+        assert_eq!(estudiante.obtener_calificacion_mas_alta(), Some(120.0));
+        assert_eq!(estudiante.obtener_calificacion_mas_baja(), Some(-4.0));
+        assert_eq!(estudiante.obtener_mediana(), Some(100.0));
+
+        let solo_cien = Estudiante::new("Pedro".to_string(), 3, vec![Examen::new("Lengua".to_string(), 100.0)]);
+        assert_eq!(solo_cien.obtener_calificacion_mas_baja(), Some(100.0));
+    }
+
+    #[test]
+    fn test_mediana_y_desvio() {
+        let calificaciones = [20.0, 40.0, 40.0, 40.0, 50.0, 50.0, 70.0, 90.0]
+            .iter().map(|nota| Examen::new("Matemáticas".to_string(), *nota)).collect();
+        let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
+        assert_eq!(estudiante.obtener_mediana(), Some(45.0));
+        assert_eq!(estudiante.obtener_desvio_estandar(), Some(20.0));
+    }
+
+    #[test]
+    fn test_tendencia() {
+        let con_notas = |notas: &[f64]| {
+            let calificaciones = notas.iter().map(|nota| Examen::new("Matemáticas".to_string(), *nota)).collect();
+            Estudiante::new("Juan".to_string(), 1, calificaciones)
+        };
+        assert_eq!(con_notas(&[40.0, 55.0, 50.0, 80.0]).obtener_tendencia(), Some(Tendencia::Mejorando));
+        assert_eq!(con_notas(&[90.0, 70.0, 75.0, 40.0]).obtener_tendencia(), Some(Tendencia::Empeorando));
+        assert_eq!(con_notas(&[60.0, 80.0, 70.0, 80.0, 60.0]).obtener_tendencia(), Some(Tendencia::Estable));
+    }
+
+    #[test]
+    fn test_escalas() {
+        assert_eq!(Escala::new(1.0, 10.0, 4.0, 6.0), Ok(Escala::unlp()));
+        assert_eq!(Escala::new(0.0, 10.0, 7.0, 6.0), Err(ErrorEscala::EscalaInvalida));
+        assert_eq!(Escala::new(5.0, 5.0, 5.0, 5.0), Err(ErrorEscala::EscalaInvalida));
+
+        let unlp = Escala::unlp();
+        assert_eq!(unlp.validar(7.5), Ok(7.5));
+        assert_eq!(unlp.validar(0.0), Err(ErrorEscala::NotaFueraDeRango));
+        assert_eq!(unlp.validar(11.0), Err(ErrorEscala::NotaFueraDeRango));
+        assert!(unlp.aprueba(4.0));
+        assert!(!unlp.promociona(5.5));
+    }
+
+    #[test]
+    fn test_convertir_entre_escalas() {
+        let centesimal = Escala::centesimal();
+        let unlp = Escala::unlp();
+        // los umbrales se corresponden entre escalas
+        assert_eq!(centesimal.convertir(60.0, &unlp), Ok(4.0));
+        assert_eq!(centesimal.convertir(80.0, &unlp), Ok(6.0));
+        assert_eq!(centesimal.convertir(0.0, &unlp), Ok(1.0));
+        assert_eq!(centesimal.convertir(100.0, &unlp), Ok(10.0));
+        assert_eq!(centesimal.convertir(70.0, &unlp), Ok(5.0));
+        assert_eq!(unlp.convertir(8.0, &centesimal), Ok(90.0));
+        assert_eq!(unlp.convertir(12.0, &centesimal), Err(ErrorEscala::NotaFueraDeRango));
+    }
+
+    #[test]
+    fn test_letras() {
+        let centesimal = Escala::centesimal();
+        assert_eq!(centesimal.a_letra(95.0), Ok(Letra::A));
+        assert_eq!(centesimal.a_letra(80.0), Ok(Letra::B));
+        assert_eq!(centesimal.a_letra(72.0), Ok(Letra::C));
+        assert_eq!(centesimal.a_letra(60.0), Ok(Letra::D));
+        assert_eq!(centesimal.a_letra(59.9), Ok(Letra::F));
+        assert_eq!(centesimal.a_letra(-1.0), Err(ErrorEscala::NotaFueraDeRango));
+        assert_eq!(Escala::unlp().a_letra(10.0), Ok(Letra::A));
+        assert_eq!(centesimal.desde_letra(Letra::B), 80.0);
+        assert_eq!(Escala::unlp().desde_letra(Letra::F), 1.0);
+    }
+
+    #[test]
+    fn test_estado_en_escala_unlp() {
+        let calificaciones = vec![
+            Examen::new("Matemáticas".to_string(), 5.0),
+            Examen::new("Matemáticas".to_string(), 7.0),
+        ];
+        let estudiante = Estudiante::new("Juan".to_string(), 1, calificaciones);
+        let reglas = ReglasCursada::new(Escala::unlp(), ReglaRecuperatorio::Reemplaza);
+        assert_eq!(estudiante.estado_en("Matemáticas", &reglas), Some(EstadoAcademico::Promocionado));
+    }
+
+    #[test]
+    fn test_nota_final_ponderada() {
+        let calificaciones = vec![
+            Examen::new("Matemáticas".to_string(), 70.0).con_fecha(Fecha::new(10, 4, 2024)),
+            Examen::new("Matemáticas".to_string(), 90.0).con_fecha(Fecha::new(5, 6, 2024)).con_peso(2.0).unwrap(),
+            Examen::new("Matemáticas".to_string(), 100.0).con_tipo(TipoExamen::TrabajoPractico).con_peso(0.5).unwrap(),
+            Examen::new("Historia".to_string(), 20.0),
+        ];
+        let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
+        let reglas = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+        // (70 + 90 * 2 + 100 * 0,5) / 3,5
+        assert_eq!(estudiante.nota_final_en("Matemáticas", &reglas), Some(300.0 / 3.5));
+        assert_eq!(estudiante.estado_en("Matemáticas", &reglas), Some(EstadoAcademico::Promocionado));
+        assert_eq!(estudiante.estado_en("Historia", &reglas), Some(EstadoAcademico::Desaprobado));
+        assert_eq!(estudiante.nota_final_en("Química", &reglas), None);
+        assert_eq!(estudiante.estado_en("Química", &reglas), None);
+    }
+
+    #[test]
+    fn test_peso_invalido() {
+        let examen = Examen::new("Matemáticas".to_string(), 70.0);
+        assert_eq!(examen.clone().con_peso(-1.0).err(), Some(PesoInvalido(-1.0)));
+        assert_eq!(examen.clone().con_peso(0.0).err(), Some(PesoInvalido(0.0)));
+        assert!(examen.clone().con_peso(f64::NAN).is_err());
+        assert_eq!(examen.con_peso(f64::INFINITY).err(), Some(PesoInvalido(f64::INFINITY)));
+    }
+
+    #[test]
+    fn test_recuperatorio_reemplaza_parcial_desaprobado() {
+        // se cargan desordenados: cuenta la fecha, no el orden de carga
+        let calificaciones = vec![
+            Examen::new("Matemáticas".to_string(), 65.0).con_tipo(TipoExamen::Recuperatorio).con_fecha(Fecha::new(20, 5, 2024)),
+            Examen::new("Matemáticas".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024)),
+            Examen::new("Matemáticas".to_string(), 75.0).con_fecha(Fecha::new(1, 5, 2024)),
+        ];
+        let estudiante = Estudiante::new("Juan".to_string(), 1, calificaciones);
+        let reglas = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+        assert_eq!(estudiante.nota_final_en("Matemáticas", &reglas), Some(70.0));
+        assert_eq!(estudiante.estado_en("Matemáticas", &reglas), Some(EstadoAcademico::CursadaAprobada));
+
+        // sin el recuperatorio el primer parcial queda desaprobado
+        let sin_recuperar = Estudiante::new("Pedro".to_string(), 3, vec![
+            Examen::new("Matemáticas".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024)),
+            Examen::new("Matemáticas".to_string(), 95.0).con_fecha(Fecha::new(1, 5, 2024)),
+        ]);
+        assert_eq!(sin_recuperar.estado_en("Matemáticas", &reglas), Some(EstadoAcademico::Desaprobado));
+    }
+
+    #[test]
+    fn test_reglas_de_recuperatorio() {
+        let calificaciones = vec![
+            Examen::new("Historia".to_string(), 50.0).con_fecha(Fecha::new(10, 4, 2024)),
+            Examen::new("Historia".to_string(), 30.0).con_tipo(TipoExamen::Recuperatorio).con_fecha(Fecha::new(20, 4, 2024)),
+            // no hay otro parcial desaprobado: este recuperatorio no cuenta
+            Examen::new("Historia".to_string(), 10.0).con_tipo(TipoExamen::Recuperatorio).con_fecha(Fecha::new(30, 4, 2024)),
+            Examen::new("Historia".to_string(), 70.0).con_tipo(TipoExamen::Final).con_fecha(Fecha::new(1, 7, 2024)),
+        ];
+        let estudiante = Estudiante::new("Maria".to_string(), 2, calificaciones);
+        let reemplaza = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::Reemplaza);
+        assert_eq!(estudiante.nota_final_en("Historia", &reemplaza), Some(50.0));
+        let mejor_nota = ReglasCursada::new(Escala::centesimal(), ReglaRecuperatorio::MejorNota);
+        assert_eq!(estudiante.nota_final_en("Historia", &mejor_nota), Some(60.0));
+        assert_eq!(estudiante.estado_en("Historia", &mejor_nota), Some(EstadoAcademico::Desaprobado));
+    }
+
+    #[test]
+    fn test_curso_inscripciones() {
+        let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal());
+        curso.agregar_materia("Matemáticas");
+        curso.agregar_materia("Historia");
+        curso.inscribir_estudiante(Estudiante::new("Juan".to_string(), 1, Vec::new())).unwrap();
+        curso.inscribir_estudiante(Estudiante::new("Maria".to_string(), 2, Vec::new())).unwrap();
+        curso.inscribir_en_materia(1, "Matemáticas").unwrap();
+        curso.inscribir_en_materia(2, "Historia").unwrap();
+        assert!(!curso.agregar_materia("Historia"));
+        assert_eq!(curso.inscribir_estudiante(Estudiante::new("Otro Juan".to_string(), 1, Vec::new())), Err(ErrorCurso::EstudianteDuplicado));
+        assert_eq!(curso.inscribir_en_materia(9, "Historia"), Err(ErrorCurso::EstudianteInexistente));
+        assert_eq!(curso.inscribir_en_materia(1, "Química"), Err(ErrorCurso::MateriaInexistente));
+        assert_eq!(curso.cargar_nota(1, "Historia", 80.0), Err(ErrorCurso::NoInscripto));
+        assert_eq!(curso.cargar_nota(9, "Historia", 80.0), Err(ErrorCurso::EstudianteInexistente));
+        assert_eq!(curso.inscriptos_en("Historia").unwrap().len(), 1);
+        assert_eq!(curso.cargar_nota(1, "Matemáticas", 120.0), Err(ErrorCurso::NotaInvalida(ErrorEscala::NotaFueraDeRango)));
+        assert_eq!(curso.cargar_nota(1, "Matemáticas", f64::NAN), Err(ErrorCurso::NotaInvalida(ErrorEscala::NotaNoNumerica)));
+        let con_nota_fuera_de_escala = Estudiante::new("Ana".to_string(), 9, vec![Examen::new("Matemáticas".to_string(), 150.0)]);
+        assert_eq!(curso.inscribir_estudiante(con_nota_fuera_de_escala), Err(ErrorCurso::NotaInvalida(ErrorEscala::NotaFueraDeRango)));
+        assert_eq!(curso.inscribir_en_materia(9, "Matemáticas"), Err(ErrorCurso::EstudianteInexistente));
+    }
+
+    #[test]
+    fn test_curso_promedios_y_ranking() {
+        let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal());
+        curso.agregar_materia("Matemáticas");
+        curso.agregar_materia("Historia");
+        for (nombre, id) in [("Juan", 1), ("Maria", 2), ("Pedro", 3)] {
+            curso.inscribir_estudiante(Estudiante::new(nombre.to_string(), id, Vec::new())).unwrap();
+            curso.inscribir_en_materia(id, "Matemáticas").unwrap();
+        }
+        curso.inscribir_en_materia(2, "Historia").unwrap();
+        curso.cargar_nota(1, "Matemáticas", 40.0).unwrap();
+        curso.cargar_nota(1, "Matemáticas", 70.0).unwrap();
+        curso.cargar_nota(2, "Matemáticas", 90.0).unwrap();
+        curso.cargar_nota(2, "Historia", 50.0).unwrap();
+        curso.cargar_nota(3, "Matemáticas", 30.0).unwrap();
+        // las notas finales son 55, 90 y 30
+        assert!((curso.promedio_por_materia("Matemáticas").unwrap() - 175.0 / 3.0).abs() < 1e-9);
+        assert_eq!(curso.promedio_por_materia("Historia"), Some(50.0));
+        assert_eq!(curso.promedio_por_materia("Química"), None);
+
+        let ranking: Vec<u32> = curso.ranking().iter().map(|e| e.id).collect();
+        assert_eq!(ranking, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn test_curso_desaprobados() {
+        let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal());
+        curso.agregar_materia("Matemáticas");
+        curso.agregar_materia("Historia");
+        for (nombre, id) in [("Juan", 1), ("Maria", 2), ("Pedro", 3)] {
+            curso.inscribir_estudiante(Estudiante::new(nombre.to_string(), id, Vec::new())).unwrap();
+            curso.inscribir_en_materia(id, "Matemáticas").unwrap();
+        }
+        curso.inscribir_en_materia(2, "Historia").unwrap();
+        curso.cargar_nota(1, "Matemáticas", 40.0).unwrap();
+        curso.cargar_nota(1, "Matemáticas", 70.0).unwrap();
+        curso.cargar_nota(2, "Matemáticas", 90.0).unwrap();
+        curso.cargar_nota(2, "Historia", 50.0).unwrap();
+        curso.cargar_nota(3, "Matemáticas", 30.0).unwrap();
+        let desaprobados: Vec<u32> = curso.desaprobados("Matemáticas").unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(desaprobados, vec![1, 3]);
+
+        curso.inscribir_en_materia(1, "Historia").unwrap();
+        let desaprobados: Vec<u32> = curso.desaprobados("Historia").unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(desaprobados, vec![2]);
+        assert_eq!(curso.desaprobados("Química").err(), Some(ErrorCurso::MateriaInexistente));
+    }
+
+    #[test]
+    fn test_curso_usa_las_reglas_de_recuperatorio() {
+        let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal()).con_recuperatorio(ReglaRecuperatorio::Reemplaza);
+        curso.agregar_materia("Matemáticas");
+        curso.inscribir_estudiante(Estudiante::new("Juan".to_string(), 1, Vec::new())).unwrap();
+        curso.inscribir_en_materia(1, "Matemáticas").unwrap();
+        curso.cargar_examen(1, Examen::new("Matemáticas".to_string(), 40.0).con_fecha(Fecha::new(10, 4, 2024))).unwrap();
+        curso.cargar_examen(1, Examen::new("Matemáticas".to_string(), 70.0).con_tipo(TipoExamen::Recuperatorio).con_fecha(Fecha::new(20, 4, 2024))).unwrap();
+
+        assert_eq!(curso.promedio_por_materia("Matemáticas"), Some(70.0));
+        assert!(curso.desaprobados("Matemáticas").unwrap().is_empty());
+    }
@@ -1,4 +1,25 @@
-enum Color {
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::comun::Direccion;
+use super::ej_3::Fecha;
+use super::motor_precios::escribir_renglones;
+use super::valuacion::ModeloValuacion;
+use crate::dinero::{Dinero, ErrorDinero, Moneda, Porcentaje, Redondeo};
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
+#[cfg(test)]
+use super::valuacion::CurvaDepreciacion;
+
+const REDONDEO: Redondeo = Redondeo::MitadHaciaArriba;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
     Rojo,
     Verde,
     Azul,
@@ -7,94 +28,803 @@ enum Color {
     Negro,
 }
 
-struct Auto {
+// estado general del auto a la hora de tasarlo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EstadoAuto {
+    Excelente,
+    Bueno,
+    Regular,
+    Malo,
+}
+
+impl ConNombre for Color {
+    const VARIANTES: &'static [(Color, &'static str)] = &[
+        (Color::Rojo, "rojo"),
+        (Color::Verde, "verde"),
+        (Color::Azul, "azul"),
+        (Color::Amarillo, "amarillo"),
+        (Color::Blanco, "blanco"),
+        (Color::Negro, "negro"),
+    ];
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for Color {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Color::desde_nombre(texto)
+    }
+}
+
+// todas las condiciones de una regla tienen que cumplirse para que se aplique
+#[derive(Debug, Clone, PartialEq)]
+enum Condicion {
+    Marca(String), // sin distinguir mayusculas
+    ColorEn(Vec<Color>),
+    ColorFueraDe(Vec<Color>),
+    AnioDesde(u32),
+    AnioHasta(u32),
+    PrecioDesde(Dinero), // un auto en otra moneda no cumple las condiciones de precio
+    PrecioHasta(Dinero),
+}
+
+impl Condicion {
+    fn se_cumple(&self, auto: &Auto) -> bool {
+        match self {
+            Condicion::Marca(marca) => auto.marca.eq_ignore_ascii_case(marca),
+            Condicion::ColorEn(colores) => colores.contains(&auto.color),
+            Condicion::ColorFueraDe(colores) => !colores.contains(&auto.color),
+            Condicion::AnioDesde(anio) => auto.anio >= *anio,
+            Condicion::AnioHasta(anio) => auto.anio <= *anio,
+            Condicion::PrecioDesde(precio) => precio.moneda() == auto.precio_bruto.moneda() && auto.precio_bruto.centavos() >= precio.centavos(),
+            Condicion::PrecioHasta(precio) => precio.moneda() == auto.precio_bruto.moneda() && auto.precio_bruto.centavos() <= precio.centavos(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AjusteAuto {
+    Recargo(Porcentaje),
+    Descuento(Porcentaje),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ReglaAuto {
+    nombre: String,
+    ajuste: AjusteAuto,
+    condiciones: Vec<Condicion>,
+}
+
+impl ReglaAuto {
+    fn new(nombre: &str, ajuste: AjusteAuto) -> ReglaAuto {
+        ReglaAuto { nombre: nombre.to_string(), ajuste, condiciones: Vec::new() }
+    }
+
+    fn con_condicion(mut self, condicion: Condicion) -> ReglaAuto {
+        self.condiciones.push(condicion);
+        self
+    }
+
+    fn se_aplica_a(&self, auto: &Auto) -> bool {
+        self.condiciones.iter().all(|condicion| condicion.se_cumple(auto))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ErrorLinea {
+    CampoDesconocido(String),
+    ValorInvalido(String),
+    SinNombre,
+    SinAjuste,
+    AjusteRepetido,
+}
+
+#[derive(Debug, PartialEq)]
+enum ErrorReglas {
+    Archivo(io::ErrorKind),
+    Linea { linea: usize, error: ErrorLinea },
+}
+
+// una regla por linea, con campos clave=valor separados por ';':
+//   nombre=Marca BMW; recargo=15; marca=BMW
+// los campos de condicion son marca, color, color!, anio_desde, anio_hasta,
+// precio_desde y precio_hasta; los colores van separados por comas y los
+// precios llevan la moneda ("ARS 1500000.50"). Las lineas con # se ignoran.
+#[derive(Debug, Clone, PartialEq)]
+struct ReglasDePrecio {
+    reglas: Vec<ReglaAuto>,
+}
+
+impl ReglasDePrecio {
+    fn new() -> ReglasDePrecio {
+        ReglasDePrecio { reglas: Vec::new() }
+    }
+
+    // las reglas que antes estaban fijas en calcular_precio
+    fn por_defecto() -> ReglasDePrecio {
+        let llamativos = vec![Color::Rojo, Color::Amarillo, Color::Azul];
+        let mut reglas = ReglasDePrecio::new();
+        reglas.agregar(ReglaAuto::new("Color llamativo", AjusteAuto::Recargo(Porcentaje::new(25)))
+            .con_condicion(Condicion::ColorEn(llamativos.clone())));
+        reglas.agregar(ReglaAuto::new("Color sobrio", AjusteAuto::Descuento(Porcentaje::new(10)))
+            .con_condicion(Condicion::ColorFueraDe(llamativos)));
+        reglas.agregar(ReglaAuto::new("Marca BMW", AjusteAuto::Recargo(Porcentaje::new(15)))
+            .con_condicion(Condicion::Marca("BMW".to_string())));
+        reglas.agregar(ReglaAuto::new("Anterior al 2000", AjusteAuto::Descuento(Porcentaje::new(5)))
+            .con_condicion(Condicion::AnioHasta(1999)));
+        reglas
+    }
+
+    fn agregar(&mut self, regla: ReglaAuto) {
+        self.reglas.push(regla);
+    }
+
+    fn desde_archivo(ruta: &Path) -> Result<ReglasDePrecio, ErrorReglas> {
+        let texto = fs::read_to_string(ruta).map_err(|error| ErrorReglas::Archivo(error.kind()))?;
+        ReglasDePrecio::desde_texto(&texto)
+    }
+
+    fn desde_texto(texto: &str) -> Result<ReglasDePrecio, ErrorReglas> {
+        let mut reglas = ReglasDePrecio::new();
+        for (i, linea) in texto.lines().enumerate() {
+            let linea_limpia = linea.trim();
+            if linea_limpia.is_empty() || linea_limpia.starts_with('#') {
+                continue;
+            }
+            let regla = leer_regla(linea_limpia).map_err(|error| ErrorReglas::Linea { linea: i + 1, error })?;
+            reglas.agregar(regla);
+        }
+        Ok(reglas)
+    }
+
+    // los ajustes se calculan sobre el precio base del auto (el bruto o el
+    // depreciado), pero las condiciones de precio se evaluan contra el bruto
+    fn calcular(&self, auto: &Auto) -> Result<PrecioExplicado, ErrorDinero> {
+        let base = auto.precio_base()?;
+        let mut total = base;
+        let mut aplicadas = Vec::new();
+        for regla in self.reglas.iter().filter(|regla| regla.se_aplica_a(auto)) {
+            let monto = match regla.ajuste {
+                AjusteAuto::Recargo(porcentaje) => base.porcentaje(porcentaje, REDONDEO)?,
+                AjusteAuto::Descuento(porcentaje) => base.porcentaje(porcentaje, REDONDEO)?.negar()?,
+            };
+            total = total.sumar(&monto)?;
+            aplicadas.push((regla.nombre.clone(), monto));
+        }
+        Ok(PrecioExplicado { precio_base: base, aplicadas, precio_final: total })
+    }
+}
+
+fn leer_regla(linea: &str) -> Result<ReglaAuto, ErrorLinea> {
+    let mut nombre = None;
+    let mut ajuste = None;
+    let mut condiciones = Vec::new();
+    for campo in linea.split(';').map(str::trim).filter(|campo| !campo.is_empty()) {
+        let (clave, valor) = campo.split_once('=').ok_or_else(|| ErrorLinea::ValorInvalido(campo.to_string()))?;
+        let (clave, valor) = (clave.trim(), valor.trim());
+        let invalido = || ErrorLinea::ValorInvalido(valor.to_string());
+        match clave {
+            "nombre" => nombre = Some(valor.to_string()),
+            "recargo" | "descuento" => {
+                if ajuste.is_some() {
+                    return Err(ErrorLinea::AjusteRepetido);
+                }
+                let porcentaje = leer_porcentaje(valor).ok_or_else(invalido)?;
+                ajuste = Some(if clave == "recargo" { AjusteAuto::Recargo(porcentaje) } else { AjusteAuto::Descuento(porcentaje) });
+            }
+            "marca" => condiciones.push(Condicion::Marca(valor.to_string())),
+            "color" | "color!" => {
+                let colores = valor.split(',').map(str::parse).collect::<Result<Vec<Color>, _>>().map_err(|_| invalido())?;
+                condiciones.push(if clave == "color" { Condicion::ColorEn(colores) } else { Condicion::ColorFueraDe(colores) });
+            }
+            "anio_desde" => condiciones.push(Condicion::AnioDesde(valor.parse().map_err(|_| invalido())?)),
+            "anio_hasta" => condiciones.push(Condicion::AnioHasta(valor.parse().map_err(|_| invalido())?)),
+            "precio_desde" => condiciones.push(Condicion::PrecioDesde(leer_dinero(valor).ok_or_else(invalido)?)),
+            "precio_hasta" => condiciones.push(Condicion::PrecioHasta(leer_dinero(valor).ok_or_else(invalido)?)),
+            _ => return Err(ErrorLinea::CampoDesconocido(clave.to_string())),
+        }
+    }
+    let nombre = nombre.filter(|nombre| !nombre.is_empty()).ok_or(ErrorLinea::SinNombre)?;
+    let ajuste = ajuste.ok_or(ErrorLinea::SinAjuste)?;
+    Ok(ReglaAuto { nombre, ajuste, condiciones })
+}
+
+// "12.5" -> 1250 centesimas; a lo sumo dos decimales
+fn leer_centesimas(texto: &str) -> Option<i64> {
+    let (entero, decimales) = texto.split_once('.').unwrap_or((texto, ""));
+    if entero.is_empty() || decimales.len() > 2 || !entero.chars().chain(decimales.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let decimales = format!("{:0<2}", decimales);
+    entero.parse::<i64>().ok()?.checked_mul(100)?.checked_add(decimales.parse().ok()?)
+}
+
+fn leer_porcentaje(texto: &str) -> Option<Porcentaje> {
+    leer_centesimas(texto.trim_end_matches('%').trim()).map(Porcentaje::con_decimales)
+}
+
+fn leer_dinero(texto: &str) -> Option<Dinero> {
+    let (codigo, monto) = texto.split_once(' ')?;
+    let moneda = codigo.parse::<Moneda>().ok()?;
+    Some(Dinero::new(leer_centesimas(monto.trim())?, moneda))
+}
+
+#[derive(Debug, PartialEq)]
+struct PrecioExplicado {
+    precio_base: Dinero, // el bruto, o el valor depreciado si el auto tiene uno
+    aplicadas: Vec<(String, Dinero)>, // nombre de la regla y cuanto movio el precio
+    precio_final: Dinero,
+}
+
+impl fmt::Display for PrecioExplicado {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let aplicadas = self.aplicadas.iter().map(|(nombre, monto)| (nombre.as_str(), *monto));
+        let renglones = [("Precio base", self.precio_base)].into_iter()
+            .chain(aplicadas)
+            .chain([("Precio final", self.precio_final)]);
+        escribir_renglones(f, renglones)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Auto {
+    patente: String,
     marca: String,
     modelo: String,
     anio: u32,
-    precio_bruto: f64,
+    precio_bruto: Dinero,
     color: Color,
+    kilometraje: u32,
+    estado: EstadoAuto,
+    depreciacion: Option<(ModeloValuacion, u32)>, // modelo y anio en que se tasa
+}
+
+// el kilometraje y el estado cambian con el uso: un auto sigue siendo el
+// mismo aunque se actualicen
+impl PartialEq for Auto {
+    fn eq(&self, otro: &Auto) -> bool {
+        self.patente == otro.patente &&
+        self.marca == otro.marca &&
+        self.modelo == otro.modelo &&
+        self.anio == otro.anio &&
+        self.precio_bruto == otro.precio_bruto &&
+        self.color == otro.color
+    }
 }
 
 impl Auto {
-    fn new(marca:String, modelo:String, anio:u32, precio_bruto:f64, color:Color) -> Auto {
-        Auto {marca, modelo, precio_bruto, anio, color}
+    // sin datos de uso el auto se toma como 0 km y en buen estado
+    pub fn new(patente:String, marca:String, modelo:String, anio:u32, precio_bruto:Dinero, color:Color) -> Auto {
+        Auto {patente, marca, modelo, precio_bruto, anio, color, kilometraje: 0, estado: EstadoAuto::Bueno, depreciacion: None}
+    }
+
+    // calcular_precio parte del valor de mercado segun el modelo en vez del precio bruto
+    pub fn con_depreciacion(mut self, modelo: ModeloValuacion, anio_actual: u32) -> Auto {
+        self.depreciacion = Some((modelo, anio_actual));
+        self
+    }
+
+    pub fn con_uso(mut self, kilometraje: u32, estado: EstadoAuto) -> Auto {
+        self.kilometraje = kilometraje;
+        self.estado = estado;
+        self
+    }
+
+    pub fn patente(&self) -> &str {
+        &self.patente
+    }
+
+    pub fn marca(&self) -> &str {
+        &self.marca
+    }
+
+    pub fn anio(&self) -> u32 {
+        self.anio
+    }
+
+    pub fn precio_bruto(&self) -> Dinero {
+        self.precio_bruto
+    }
+
+    pub fn kilometraje(&self) -> u32 {
+        self.kilometraje
+    }
+
+    pub fn estado(&self) -> EstadoAuto {
+        self.estado
+    }
+
+    pub fn calcular_precio(&self) -> Result<Dinero, ErrorDinero> {
+        Ok(ReglasDePrecio::por_defecto().calcular(self)?.precio_final)
     }
 
-    fn calcular_precio(&self) -> f64 {
-        let mut total = self.precio_bruto;
-        if self.color_comparable() == "rojo" || self.color_comparable() == "amarillo" || self.color_comparable() == "azul" {
-            let recargo_color = self.precio_bruto * 0.25;
-            total += recargo_color;  
-        } else {
-            let descuento_color = self.precio_bruto * 0.10;
-            total -= descuento_color; 
+    fn precio_base(&self) -> Result<Dinero, ErrorDinero> {
+        match &self.depreciacion {
+            Some((modelo, anio_actual)) => Ok(modelo.valuar(self, *anio_actual)?.valor_de_mercado()),
+            None => Ok(self.precio_bruto),
         }
-        if self.marca == "BMW" {
-            let recargo_marca = self.precio_bruto as f64 * 0.15;
-            total += recargo_marca;
+    }
+
+    fn calcular_precio_con(&self, reglas: &ReglasDePrecio) -> Result<PrecioExplicado, ErrorDinero> {
+        reglas.calcular(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CampoOrden {
+    Precio,
+    Anio,
+    Marca,
+}
+
+// filtros combinables: un auto tiene que cumplir todos los que esten cargados
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsultaAutos {
+    marca: Option<String>,
+    modelo: Option<String>,
+    color: Option<Color>,
+    anio_desde: Option<u32>,
+    anio_hasta: Option<u32>,
+    precio_desde: Option<Dinero>,
+    precio_hasta: Option<Dinero>,
+    orden: Option<(CampoOrden, Direccion)>,
+    pagina: Option<(usize, usize)>, // numero de pagina (desde 1) y autos por pagina
+}
+
+impl ConsultaAutos {
+    pub fn new() -> ConsultaAutos {
+        ConsultaAutos {
+            marca: None,
+            modelo: None,
+            color: None,
+            anio_desde: None,
+            anio_hasta: None,
+            precio_desde: None,
+            precio_hasta: None,
+            orden: None,
+            pagina: None,
         }
-        if self.anio < 2000 {
-            let descuento_anio = self.precio_bruto as f64 * 0.05;
-            total -= descuento_anio;
+    }
+
+    pub fn con_marca(mut self, marca: &str) -> ConsultaAutos {
+        self.marca = Some(marca.to_lowercase());
+        self
+    }
+
+    pub fn con_modelo(mut self, modelo: &str) -> ConsultaAutos {
+        self.modelo = Some(modelo.to_lowercase());
+        self
+    }
+
+    pub fn con_color(mut self, color: Color) -> ConsultaAutos {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn anio_entre(mut self, desde: u32, hasta: u32) -> ConsultaAutos {
+        self.anio_desde = Some(desde);
+        self.anio_hasta = Some(hasta);
+        self
+    }
+
+    pub fn precio_desde(mut self, precio: Dinero) -> ConsultaAutos {
+        self.precio_desde = Some(precio);
+        self
+    }
+
+    pub fn precio_hasta(mut self, precio: Dinero) -> ConsultaAutos {
+        self.precio_hasta = Some(precio);
+        self
+    }
+
+    pub fn ordenar_por(mut self, campo: CampoOrden, direccion: Direccion) -> ConsultaAutos {
+        self.orden = Some((campo, direccion));
+        self
+    }
+
+    pub fn paginar(mut self, pagina: usize, por_pagina: usize) -> ConsultaAutos {
+        self.pagina = Some((pagina, por_pagina));
+        self
+    }
+
+    // sirve para listas de autos o de elementos que contienen un auto
+    pub fn ordenar_y_paginar<T>(&self, mut elementos: Vec<T>, auto: impl Fn(&T) -> &Auto) -> Vec<T> {
+        elementos.sort_by(|a, b| auto(a).patente.cmp(&auto(b).patente));
+        if let Some((campo, direccion)) = self.orden {
+            // el ordenamiento es estable: a igual valor quedan por patente
+            elementos.sort_by(|a, b| {
+                let (a, b) = (auto(a), auto(b));
+                let orden = match campo {
+                    // no se comparan montos de distintas monedas: se agrupan por moneda
+                    CampoOrden::Precio => (a.precio_bruto.moneda() as u8).cmp(&(b.precio_bruto.moneda() as u8))
+                        .then(a.precio_bruto.centavos().cmp(&b.precio_bruto.centavos())),
+                    CampoOrden::Anio => a.anio.cmp(&b.anio),
+                    CampoOrden::Marca => a.marca.to_lowercase().cmp(&b.marca.to_lowercase()),
+                };
+                match direccion {
+                    Direccion::Ascendente => orden,
+                    Direccion::Descendente => orden.reverse(),
+                }
+            });
+        }
+        match self.pagina {
+            // una pagina que no entra en un usize queda vacia
+            Some((pagina, por_pagina)) => match pagina.saturating_sub(1).checked_mul(por_pagina) {
+                Some(salteados) => elementos.into_iter().skip(salteados).take(por_pagina).collect(),
+                None => Vec::new(),
+            },
+            None => elementos,
         }
-        total
     }
 
-    fn es_igual(&self, auto:&Auto) -> bool {
-        self.marca == auto.marca &&
-        self.modelo == auto.modelo &&
-        self.anio == auto.anio &&
-        self.precio_bruto == auto.precio_bruto &&
-        self.color_comparable() == auto.color_comparable()
+    // sin orden ni paginado, para combinar resultados de varias fuentes
+    pub fn solo_filtros(&self) -> ConsultaAutos {
+        ConsultaAutos { orden: None, pagina: None, ..self.clone() }
+    }
+
+    // los precios en otra moneda no entran en el rango
+    fn precio_en_rango(&self, precio: &Dinero) -> bool {
+        let desde = self.precio_desde.is_none_or(|desde| desde.moneda() == precio.moneda() && precio.centavos() >= desde.centavos());
+        let hasta = self.precio_hasta.is_none_or(|hasta| hasta.moneda() == precio.moneda() && precio.centavos() <= hasta.centavos());
+        desde && hasta
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cliente {
+    dni: u32,
+    nombre: String,
+    telefono: String,
+}
+
+impl Cliente {
+    pub fn new(dni: u32, nombre: String, telefono: String) -> Cliente {
+        Cliente { dni, nombre, telefono }
+    }
+
+    pub fn dni(&self) -> u32 {
+        self.dni
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PruebaDeManejo {
+    patente: String,
+    dni_cliente: u32,
+    fecha: Fecha,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reserva {
+    patente: String,
+    dni_cliente: u32,
+    sena: Dinero,
+    vencimiento: Fecha, // el ultimo dia en que la reserva sigue vigente
+}
+
+impl Reserva {
+    pub fn sena(&self) -> Dinero {
+        self.sena
     }
+}
+
+pub struct Venta {
+    auto: Auto,
+    dni_cliente: u32,
+    vendedor: String,
+    precio: Dinero, // lo que se cobro en total, sena incluida
+    sena: Option<Dinero>,
+    fecha: Fecha,
+}
 
-    fn color_comparable(&self) -> &str {
-        match self.color {
-            Color::Amarillo => "amarillo",
-            Color::Azul => "azul",
-            Color::Blanco => "blanco",
-            Color::Negro => "negro",
-            Color::Rojo => "rojo",
-            Color::Verde => "verde",
+impl Venta {
+    // lo que faltaba pagar al momento de la venta
+    pub fn saldo(&self) -> Result<Dinero, ErrorDinero> {
+        match self.sena {
+            Some(sena) => self.precio.restar(&sena),
+            None => Ok(self.precio),
         }
     }
 }
 
-struct ConcesionarioAuto {
+#[derive(Debug, PartialEq)]
+pub enum ErrorOperacion {
+    ClienteDuplicado,
+    ClienteInexistente,
+    AutoInexistente,
+    AutoReservado, // tiene una reserva vigente de otro cliente
+    YaReservado,   // el mismo cliente ya tiene una reserva vigente
+    TurnoOcupado,
+    SenaInvalida,
+    ReservaInexistente,
+    Precio(ErrorDinero),
+}
+
+pub struct ConcesionarioAuto {
     nombre: String,
     dir: String,
     cap_max: usize,
     lista_autos: Vec<Auto>,
+    // indices: la patente lleva a la posicion en lista_autos, los demas a patentes
+    por_patente: HashMap<String, usize>,
+    por_marca: HashMap<String, BTreeSet<String>>,
+    por_modelo: HashMap<String, BTreeSet<String>>,
+    por_anio: BTreeMap<u32, BTreeSet<String>>,
+    por_color: HashMap<Color, BTreeSet<String>>,
+    clientes: HashMap<u32, Cliente>,
+    pruebas_de_manejo: Vec<PruebaDeManejo>,
+    reservas: HashMap<String, Reserva>, // por patente
+    ventas: Vec<Venta>,
 }
 
 impl ConcesionarioAuto {
-    fn new(nombre:String, dir:String, cap_max:usize) -> ConcesionarioAuto {
+    pub fn new(nombre:String, dir:String, cap_max:usize) -> ConcesionarioAuto {
         ConcesionarioAuto {
             nombre,
             dir,
             cap_max,
             lista_autos: Vec::new(),
+            por_patente: HashMap::new(),
+            por_marca: HashMap::new(),
+            por_modelo: HashMap::new(),
+            por_anio: BTreeMap::new(),
+            por_color: HashMap::new(),
+            clientes: HashMap::new(),
+            pruebas_de_manejo: Vec::new(),
+            reservas: HashMap::new(),
+            ventas: Vec::new(),
         }
     }
 
-    fn agregar_auto (&mut self, auto:Auto) -> bool {
-        if self.lista_autos.len() < self.cap_max {
-            self.lista_autos.push(auto);
-            return true
+    pub fn nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn cap_max(&self) -> usize {
+        self.cap_max
+    }
+
+    pub fn autos(&self) -> &[Auto] {
+        &self.lista_autos
+    }
+
+    pub fn lugares_libres(&self) -> usize {
+        self.cap_max.saturating_sub(self.lista_autos.len())
+    }
+
+    // no entra si no hay lugar o si la patente ya esta cargada
+    pub fn agregar_auto (&mut self, auto:Auto) -> bool {
+        if self.lista_autos.len() >= self.cap_max || self.por_patente.contains_key(&auto.patente) {
+            return false
         }
-        false
+        let patente = auto.patente.clone();
+        self.por_marca.entry(auto.marca.to_lowercase()).or_default().insert(patente.clone());
+        self.por_modelo.entry(auto.modelo.to_lowercase()).or_default().insert(patente.clone());
+        self.por_anio.entry(auto.anio).or_default().insert(patente.clone());
+        self.por_color.entry(auto.color).or_default().insert(patente.clone());
+        self.por_patente.insert(patente, self.lista_autos.len());
+        self.lista_autos.push(auto);
+        true
     }
 
     fn eliminar_auto(&mut self, auto:Auto) {
-        if let Some(pos) = self.lista_autos.iter().position(|elem| elem.es_igual(&auto)) { 
-            self.lista_autos.remove(pos);
-        } 
+        if self.buscar_auto(&auto).is_some() {
+            self.eliminar_por_patente(&auto.patente);
+        }
+    }
+
+    // el auto deja de existir para el concesionario, junto con su reserva y sus turnos
+    pub fn eliminar_por_patente(&mut self, patente: &str) -> Option<Auto> {
+        let auto = self.desvincular_auto(patente)?;
+        self.reservas.remove(patente);
+        self.pruebas_de_manejo.retain(|prueba| prueba.patente != patente);
+        Some(auto)
+    }
+
+    // saca el auto del inventario y de los indices sin tocar reservas ni turnos,
+    // por ejemplo para llevarlo a otra sucursal
+    pub fn desvincular_auto(&mut self, patente: &str) -> Option<Auto> {
+        let pos = self.por_patente.remove(patente)?;
+        let auto = self.lista_autos.remove(pos);
+        for posicion in self.por_patente.values_mut() {
+            if *posicion > pos {
+                *posicion -= 1;
+            }
+        }
+        quitar_de_indice(&mut self.por_marca, &auto.marca.to_lowercase(), patente);
+        quitar_de_indice(&mut self.por_modelo, &auto.modelo.to_lowercase(), patente);
+        quitar_de_indice(&mut self.por_color, &auto.color, patente);
+        if let Some(patentes) = self.por_anio.get_mut(&auto.anio) {
+            patentes.remove(patente);
+            if patentes.is_empty() {
+                self.por_anio.remove(&auto.anio);
+            }
+        }
+        Some(auto)
     }
 
     fn buscar_auto(&self, auto:&Auto) -> Option<&Auto> {
-        self.lista_autos.iter().find(|elem| elem.es_igual(auto))
+        self.buscar_por_patente(&auto.patente).filter(|elem| *elem == auto)
+    }
+
+    pub fn buscar_por_patente(&self, patente: &str) -> Option<&Auto> {
+        self.por_patente.get(patente).map(|pos| &self.lista_autos[*pos])
+    }
+
+    // arranca por los indices y despues filtra por precio, ordena y pagina
+    pub fn consultar(&self, consulta: &ConsultaAutos) -> Vec<&Auto> {
+        let mut candidatos: Option<BTreeSet<&String>> = None;
+        if let Some(marca) = &consulta.marca {
+            restringir(&mut candidatos, self.por_marca.get(marca).map(|p| p.iter().collect()).unwrap_or_default());
+        }
+        if let Some(modelo) = &consulta.modelo {
+            restringir(&mut candidatos, self.por_modelo.get(modelo).map(|p| p.iter().collect()).unwrap_or_default());
+        }
+        if let Some(color) = &consulta.color {
+            restringir(&mut candidatos, self.por_color.get(color).map(|p| p.iter().collect()).unwrap_or_default());
+        }
+        if consulta.anio_desde.is_some() || consulta.anio_hasta.is_some() {
+            let desde = consulta.anio_desde.unwrap_or(u32::MIN);
+            let hasta = consulta.anio_hasta.unwrap_or(u32::MAX);
+            let patentes = if desde <= hasta {
+                self.por_anio.range(desde..=hasta).flat_map(|(_, patentes)| patentes.iter()).collect()
+            } else {
+                BTreeSet::new()
+            };
+            restringir(&mut candidatos, patentes);
+        }
+        let candidatos = candidatos.unwrap_or_else(|| self.por_patente.keys().collect());
+
+        let autos: Vec<&Auto> = candidatos.into_iter()
+            .filter_map(|patente| self.buscar_por_patente(patente))
+            .filter(|auto| consulta.precio_en_rango(&auto.precio_bruto))
+            .collect();
+        consulta.ordenar_y_paginar(autos, |auto| auto)
+    }
+
+    pub fn registrar_cliente(&mut self, cliente: Cliente) -> Result<(), ErrorOperacion> {
+        if self.clientes.contains_key(&cliente.dni) {
+            return Err(ErrorOperacion::ClienteDuplicado);
+        }
+        self.clientes.insert(cliente.dni, cliente);
+        Ok(())
+    }
+
+    // las operaciones con dni buscan al cliente en este concesionario; las que
+    // reciben el Cliente lo toman de otro registro, como el de una red
+    fn cliente(&self, dni_cliente: u32) -> Result<&Cliente, ErrorOperacion> {
+        self.clientes.get(&dni_cliente).ok_or(ErrorOperacion::ClienteInexistente)
+    }
+
+    fn validar_operacion(&self, patente: &str, dni_cliente: u32, fecha: &Fecha) -> Result<&Auto, ErrorOperacion> {
+        let auto = self.buscar_por_patente(patente).ok_or(ErrorOperacion::AutoInexistente)?;
+        if self.reserva_vigente(patente, fecha).is_some_and(|reserva| reserva.dni_cliente != dni_cliente) {
+            return Err(ErrorOperacion::AutoReservado);
+        }
+        Ok(auto)
+    }
+
+    // un turno por auto por dia
+    pub fn agendar_prueba_de_manejo(&mut self, patente: &str, dni_cliente: u32, fecha: Fecha) -> Result<(), ErrorOperacion> {
+        self.cliente(dni_cliente)?;
+        self.agendar_prueba_de(patente, dni_cliente, fecha)
+    }
+
+    pub fn agendar_prueba_para(&mut self, cliente: &Cliente, patente: &str, fecha: Fecha) -> Result<(), ErrorOperacion> {
+        self.agendar_prueba_de(patente, cliente.dni, fecha)
+    }
+
+    fn agendar_prueba_de(&mut self, patente: &str, dni_cliente: u32, fecha: Fecha) -> Result<(), ErrorOperacion> {
+        self.validar_operacion(patente, dni_cliente, &fecha)?;
+        if self.pruebas_de_manejo.iter().any(|prueba| prueba.patente == patente && prueba.fecha == fecha) {
+            return Err(ErrorOperacion::TurnoOcupado);
+        }
+        self.pruebas_de_manejo.push(PruebaDeManejo { patente: patente.to_string(), dni_cliente, fecha });
+        Ok(())
+    }
+
+    fn pruebas_de_manejo_del(&self, fecha: &Fecha) -> Vec<&PruebaDeManejo> {
+        self.pruebas_de_manejo.iter().filter(|prueba| prueba.fecha == *fecha).collect()
+    }
+
+    pub fn tiene_pruebas_pendientes(&self, patente: &str, desde: &Fecha) -> bool {
+        self.pruebas_de_manejo.iter().any(|prueba| prueba.patente == patente && prueba.fecha >= *desde)
+    }
+
+    // la reserva vence dias_de_validez dias despues de la fecha en que se hace.
+    // Una reserva vigente no se pisa, aunque sea del mismo cliente: su sena ya
+    // se cobro, y para cambiarla hay que cancelarla primero
+    pub fn reservar(&mut self, patente: &str, dni_cliente: u32, sena: Dinero, fecha: Fecha, dias_de_validez: u32) -> Result<&Reserva, ErrorOperacion> {
+        self.cliente(dni_cliente)?;
+        self.reservar_de(patente, dni_cliente, sena, fecha, dias_de_validez)
+    }
+
+    pub fn reservar_para(&mut self, cliente: &Cliente, patente: &str, sena: Dinero, fecha: Fecha, dias_de_validez: u32) -> Result<&Reserva, ErrorOperacion> {
+        self.reservar_de(patente, cliente.dni, sena, fecha, dias_de_validez)
+    }
+
+    fn reservar_de(&mut self, patente: &str, dni_cliente: u32, sena: Dinero, fecha: Fecha, dias_de_validez: u32) -> Result<&Reserva, ErrorOperacion> {
+        let auto = self.validar_operacion(patente, dni_cliente, &fecha)?;
+        if self.reserva_vigente(patente, &fecha).is_some() {
+            return Err(ErrorOperacion::YaReservado);
+        }
+        // la sena es parte del pago: no puede superar el precio
+        let precio = auto.calcular_precio().map_err(ErrorOperacion::Precio)?;
+        if sena.es_negativo() || sena.es_cero() || sena.moneda() != precio.moneda() || sena.centavos() > precio.centavos() {
+            return Err(ErrorOperacion::SenaInvalida);
+        }
+        let mut vencimiento = fecha;
+        vencimiento.sumar_dias(dias_de_validez);
+        let reserva = Reserva { patente: patente.to_string(), dni_cliente, sena, vencimiento };
+        self.reservas.insert(patente.to_string(), reserva);
+        Ok(&self.reservas[patente])
+    }
+
+    pub fn reserva_vigente(&self, patente: &str, fecha: &Fecha) -> Option<&Reserva> {
+        self.reservas.get(patente).filter(|reserva| reserva.vencimiento >= *fecha)
+    }
+
+    fn cancelar_reserva(&mut self, patente: &str) -> Result<Reserva, ErrorOperacion> {
+        self.reservas.remove(patente).ok_or(ErrorOperacion::ReservaInexistente)
+    }
+
+    // devuelve las reservas vencidas para poder gestionar sus senas
+    pub fn liberar_reservas_vencidas(&mut self, fecha: &Fecha) -> Vec<Reserva> {
+        let vencidas: Vec<String> = self.reservas.values()
+            .filter(|reserva| reserva.vencimiento < *fecha)
+            .map(|reserva| reserva.patente.clone())
+            .collect();
+        let mut liberadas: Vec<Reserva> = vencidas.iter().filter_map(|patente| self.reservas.remove(patente)).collect();
+        liberadas.sort_by(|a, b| a.patente.cmp(&b.patente));
+        liberadas
+    }
+
+    // el auto sale del inventario y libera su lugar; si el cliente lo tenia
+    // reservado, la sena queda registrada como parte del pago
+    fn vender(&mut self, patente: &str, dni_cliente: u32, vendedor: &str, fecha: Fecha) -> Result<&Venta, ErrorOperacion> {
+        self.cliente(dni_cliente)?;
+        self.vender_de(patente, dni_cliente, vendedor, fecha)
+    }
+
+    pub fn vender_a(&mut self, cliente: &Cliente, patente: &str, vendedor: &str, fecha: Fecha) -> Result<&Venta, ErrorOperacion> {
+        self.vender_de(patente, cliente.dni, vendedor, fecha)
+    }
+
+    fn vender_de(&mut self, patente: &str, dni_cliente: u32, vendedor: &str, fecha: Fecha) -> Result<&Venta, ErrorOperacion> {
+        let auto = self.validar_operacion(patente, dni_cliente, &fecha)?;
+        let precio = auto.calcular_precio().map_err(ErrorOperacion::Precio)?;
+        let sena = self.reserva_vigente(patente, &fecha).map(|reserva| reserva.sena);
+        let auto = self.eliminar_por_patente(patente).ok_or(ErrorOperacion::AutoInexistente)?;
+        self.ventas.push(Venta { auto, dni_cliente, vendedor: vendedor.to_string(), precio, sena, fecha });
+        Ok(&self.ventas[self.ventas.len() - 1])
+    }
+
+    fn ventas_de_cliente(&self, dni_cliente: u32) -> Vec<&Venta> {
+        self.ventas.iter().filter(|venta| venta.dni_cliente == dni_cliente).collect()
+    }
+}
+
+// se queda con las patentes que esten en ambos conjuntos
+fn restringir<'a>(candidatos: &mut Option<BTreeSet<&'a String>>, patentes: BTreeSet<&'a String>) {
+    *candidatos = Some(match candidatos.take() {
+        Some(actuales) => actuales.intersection(&patentes).copied().collect(),
+        None => patentes,
+    });
+}
+
+fn quitar_de_indice<K: std::hash::Hash + Eq>(indice: &mut HashMap<K, BTreeSet<String>>, clave: &K, patente: &str) {
+    if let Some(patentes) = indice.get_mut(clave) {
+        patentes.remove(patente);
+        if patentes.is_empty() {
+            indice.remove(clave);
+        }
     }
-   
 }
 
 
@@ -102,54 +832,328 @@ impl ConcesionarioAuto {
 #[test]
 fn test_agregar_auto() {
     let mut concesionario: ConcesionarioAuto = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 2);
-    let auto1 = Auto::new("Marca1".to_string(), "ModeloA".to_string(), 2015, 4500000.00, Color::Rojo);
-    let auto2 = Auto::new("Marca2".to_string(), "ModeloB".to_string(), 1999, 51656165.50, Color::Blanco);
-    let auto3 = Auto::new("Marca3".to_string(), "ModeloC".to_string(), 2023, 98489699.00, Color::Azul);
+    let auto1 = Auto::new("AA100AA".to_string(), "Marca1".to_string(), "ModeloA".to_string(), 2015, Dinero::desde_unidades(4500000, Moneda::Ars), Color::Rojo);
+    let auto2 = Auto::new("AB200BB".to_string(), "Marca2".to_string(), "ModeloB".to_string(), 1999, Dinero::new(5165616550, Moneda::Ars), Color::Blanco);
+    let auto3 = Auto::new("AC300CC".to_string(), "Marca3".to_string(), "ModeloC".to_string(), 2023, Dinero::desde_unidades(98489699, Moneda::Ars), Color::Azul);
     assert!(concesionario.agregar_auto(auto1));
     assert!(concesionario.agregar_auto(auto2));
     assert!(!concesionario.agregar_auto(auto3));
     assert_eq!(concesionario.lista_autos[1].marca, "Marca2");
     assert_eq!(concesionario.lista_autos[1].modelo, "ModeloB");
     assert_eq!(concesionario.lista_autos[1].anio, 1999);
-    assert_eq!(concesionario.lista_autos[1].precio_bruto, 51656165.50);
-    assert_eq!(concesionario.lista_autos[1].color_comparable(), "blanco");
+    assert_eq!(concesionario.lista_autos[1].precio_bruto, Dinero::new(5165616550, Moneda::Ars));
+    assert_eq!(concesionario.lista_autos[1].color, Color::Blanco);
     assert_eq!(concesionario.lista_autos.len(), 2);
 }
 
 #[test]
 fn test_buscar_y_eliminar_auto() {
     let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 3);
-    let auto1 = Auto::new("Marca1".to_string(), "ModeloA".to_string(), 2015, 4500000.00, Color::Rojo);
-    let auto2 = Auto::new("Marca2".to_string(), "ModeloB".to_string(), 1999, 51656165.50, Color::Blanco);
-    let auto3 = Auto::new("Marca3".to_string(), "ModeloC".to_string(), 2023, 98489699.00, Color::Azul);
+    let auto1 = Auto::new("AA100AA".to_string(), "Marca1".to_string(), "ModeloA".to_string(), 2015, Dinero::desde_unidades(4500000, Moneda::Ars), Color::Rojo);
+    let auto2 = Auto::new("AB200BB".to_string(), "Marca2".to_string(), "ModeloB".to_string(), 1999, Dinero::new(5165616550, Moneda::Ars), Color::Blanco);
+    let auto3 = Auto::new("AC300CC".to_string(), "Marca3".to_string(), "ModeloC".to_string(), 2023, Dinero::desde_unidades(98489699, Moneda::Ars), Color::Azul);
     concesionario.agregar_auto(auto1);
     concesionario.agregar_auto(auto2);
     concesionario.agregar_auto(auto3);
     assert_eq!(concesionario.lista_autos.len(), 3);
     
-    let auto_existente = Auto::new("Marca2".to_string(), "ModeloB".to_string(), 1999, 51656165.50, Color::Blanco);
+    let auto_existente = Auto::new("AB200BB".to_string(), "Marca2".to_string(), "ModeloB".to_string(), 1999, Dinero::new(5165616550, Moneda::Ars), Color::Blanco);
     let mut auto_encontrado = concesionario.buscar_auto(&auto_existente);
     assert!(auto_encontrado.is_some());
-    assert!(auto_encontrado.unwrap().es_igual(&auto_existente));
+    assert_eq!(auto_encontrado, Some(&auto_existente));
+    // con otro kilometraje y estado sigue siendo el mismo auto
+    let mismo_con_uso = auto_existente.clone().con_uso(80_000, EstadoAuto::Regular);
+    assert_eq!(concesionario.buscar_auto(&mismo_con_uso), Some(&auto_existente));
 
-    let auto_inexistente = Auto::new("MarcaX".to_string(), "ModeloX".to_string(), 9999, 99999999.99, Color::Negro);
+    let auto_inexistente = Auto::new("ZZ999ZZ".to_string(), "MarcaX".to_string(), "ModeloX".to_string(), 9999, Dinero::new(9999999999, Moneda::Ars), Color::Negro);
     auto_encontrado = concesionario.buscar_auto(&auto_inexistente);
     assert!(auto_encontrado.is_none());
     
-    let auto_a_eliminar = Auto::new("Marca2".to_string(), "ModeloB".to_string(), 1999, 51656165.50, Color::Blanco);
+    let auto_a_eliminar = Auto::new("AB200BB".to_string(), "Marca2".to_string(), "ModeloB".to_string(), 1999, Dinero::new(5165616550, Moneda::Ars), Color::Blanco);
     concesionario.eliminar_auto(auto_a_eliminar);
 
     assert_eq!(concesionario.lista_autos.len(), 2);
 }
 
+#[test]
+fn test_color_texto_ida_y_vuelta() {
+    comprobar_variantes::<Color>();
+    assert_eq!(" Negro ".parse::<Color>(), Ok(Color::Negro));
+    assert_eq!("violeta".parse::<Color>(), Err(NombreDesconocido("violeta".to_string())));
+}
+
 #[test]
 fn test_calcular_precio() {
-    let auto1 = Auto::new("Marca1".to_string(), "ModeloA".to_string(), 2015, 10000.00, Color::Rojo);
-    assert_eq!(auto1.calcular_precio(), 12500.00);
+    let auto1 = Auto::new("AA100AA".to_string(), "Marca1".to_string(), "ModeloA".to_string(), 2015, Dinero::desde_unidades(10000, Moneda::Ars), Color::Rojo);
+    assert_eq!(auto1.calcular_precio(), Ok(Dinero::desde_unidades(12500, Moneda::Ars)));
+
+    let auto2 = Auto::new("AD400DD".to_string(), "BMW".to_string(), "ModeloX".to_string(), 2001, Dinero::desde_unidades(10000, Moneda::Ars), Color::Blanco);
+    assert_eq!(auto2.calcular_precio(), Ok(Dinero::desde_unidades(10500, Moneda::Ars)));
+
+    let auto3 = Auto::new("AC300CC".to_string(), "Marca3".to_string(), "ModeloC".to_string(), 1999, Dinero::desde_unidades(10000, Moneda::Ars), Color::Azul);
+    assert_eq!(auto3.calcular_precio(), Ok(Dinero::desde_unidades(12000, Moneda::Ars)));
+}
+
+#[test]
+fn test_calcular_precio_con_centavos() {
+    // 25 % de 999,99 = 249,9975 -> 250,00
+    let auto = Auto::new("AA100AA".to_string(), "Marca1".to_string(), "ModeloA".to_string(), 2015, Dinero::new(99999, Moneda::Ars), Color::Rojo);
+    assert_eq!(auto.calcular_precio(), Ok(Dinero::new(124999, Moneda::Ars)));
+}
 
-    let auto2 = Auto::new("BMW".to_string(), "ModeloX".to_string(), 2001, 10000.00, Color::Blanco);
-    assert_eq!(auto2.calcular_precio(), 10500.00);
+#[test]
+fn test_calcular_precio_depreciado() {
+    let modelo = ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 10, residual: Porcentaje::new(20) }).unwrap();
+    let auto = Auto::new("AA100AA".to_string(), "Marca1".to_string(), "ModeloA".to_string(), 2019, Dinero::desde_unidades(10000, Moneda::Ars), Color::Rojo)
+        .con_uso(75_000, EstadoAuto::Bueno);
+    assert_eq!(auto.calcular_precio(), Ok(Dinero::desde_unidades(12500, Moneda::Ars)));
+    // las mismas reglas, sobre el valor de mercado de 6000
+    let auto = auto.con_depreciacion(modelo, 2024);
+    assert_eq!(auto.calcular_precio(), Ok(Dinero::desde_unidades(7500, Moneda::Ars)));
+
+    // las condiciones se siguen evaluando sobre el auto: el de 1995 tiene su descuento
+    let viejo = Auto::new("AC300CC".to_string(), "Marca3".to_string(), "ModeloC".to_string(), 1995, Dinero::desde_unidades(10000, Moneda::Ars), Color::Azul)
+        .con_uso(435_000, EstadoAuto::Bueno)
+        .con_depreciacion(modelo, 2024);
+    assert_eq!(viejo.calcular_precio(), Ok(Dinero::desde_unidades(2400, Moneda::Ars)));
+
+    // la venta cobra el precio depreciado
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 1);
+    concesionario.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), String::new())).unwrap();
+    assert!(concesionario.agregar_auto(auto));
+    let venta = concesionario.vender("AA100AA", 30111222, "Carla", Fecha::new(5, 3, 2024)).unwrap();
+    assert_eq!(venta.precio, Dinero::desde_unidades(7500, Moneda::Ars));
+}
+
+#[cfg(test)]
+fn patentes(autos: Vec<&Auto>) -> Vec<&str> {
+    autos.iter().map(|auto| auto.patente.as_str()).collect()
+}
+
+#[test]
+fn test_patentes_unicas() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 10);
+    let autos = [
+        ("AA001AA", "Ford", "Focus", 2018, 15000, Color::Azul),
+        ("AA002AA", "Ford", "Fiesta", 2012, 8000, Color::Rojo),
+        ("AA003AA", "BMW", "Serie 3", 2020, 40000, Color::Negro),
+        ("AA004AA", "Fiat", "Cronos", 2021, 12000, Color::Azul),
+        ("AA005AA", "ford", "Focus", 2015, 11000, Color::Blanco),
+        ("AA006AA", "Fiat", "Uno", 1998, 3000, Color::Rojo),
+    ];
+    for (patente, marca, modelo, anio, precio, color) in autos {
+        let auto = Auto::new(patente.to_string(), marca.to_string(), modelo.to_string(), anio, Dinero::desde_unidades(precio, Moneda::Ars), color);
+        assert!(concesionario.agregar_auto(auto));
+    }
+    let repetido = Auto::new("AA001AA".to_string(), "Otra".to_string(), "Otro".to_string(), 2000, Dinero::desde_unidades(1, Moneda::Ars), Color::Verde);
+    assert!(!concesionario.agregar_auto(repetido));
+    assert_eq!(concesionario.buscar_por_patente("AA003AA").map(|auto| auto.modelo.as_str()), Some("Serie 3"));
 
-    let auto3 = Auto::new("Marca3".to_string(), "ModeloC".to_string(), 1999, 10000.00, Color::Azul);
-    assert_eq!(auto3.calcular_precio(), 12000.00);
+    let eliminado = concesionario.eliminar_por_patente("AA002AA").unwrap();
+    assert_eq!(eliminado.modelo, "Fiesta");
+    assert!(concesionario.buscar_por_patente("AA002AA").is_none());
+    // las posiciones de los demas siguen siendo validas
+    assert_eq!(concesionario.buscar_por_patente("AA006AA").map(|auto| auto.modelo.as_str()), Some("Uno"));
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().con_color(Color::Rojo))), vec!["AA006AA"]);
+    assert!(concesionario.eliminar_por_patente("AA002AA").is_none());
+}
+
+#[test]
+fn test_consultas_combinadas() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 10);
+    let autos = [
+        ("AA001AA", "Ford", "Focus", 2018, 15000, Color::Azul),
+        ("AA002AA", "Ford", "Fiesta", 2012, 8000, Color::Rojo),
+        ("AA003AA", "BMW", "Serie 3", 2020, 40000, Color::Negro),
+        ("AA004AA", "Fiat", "Cronos", 2021, 12000, Color::Azul),
+        ("AA005AA", "ford", "Focus", 2015, 11000, Color::Blanco),
+        ("AA006AA", "Fiat", "Uno", 1998, 3000, Color::Rojo),
+    ];
+    for (patente, marca, modelo, anio, precio, color) in autos {
+        let auto = Auto::new(patente.to_string(), marca.to_string(), modelo.to_string(), anio, Dinero::desde_unidades(precio, Moneda::Ars), color);
+        assert!(concesionario.agregar_auto(auto));
+    }
+    assert_eq!(concesionario.consultar(&ConsultaAutos::new()).len(), 6);
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().con_marca("FORD"))), vec!["AA001AA", "AA002AA", "AA005AA"]);
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().con_marca("ford").con_modelo("focus"))), vec!["AA001AA", "AA005AA"]);
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().con_color(Color::Azul).anio_entre(2019, 2025))), vec!["AA004AA"]);
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().anio_entre(2010, 2018).precio_hasta(Dinero::desde_unidades(11000, Moneda::Ars)))), vec!["AA002AA", "AA005AA"]);
+    assert_eq!(patentes(concesionario.consultar(&ConsultaAutos::new().precio_desde(Dinero::desde_unidades(12000, Moneda::Ars)))), vec!["AA001AA", "AA003AA", "AA004AA"]);
+    assert!(concesionario.consultar(&ConsultaAutos::new().precio_hasta(Dinero::desde_unidades(100000, Moneda::Usd))).is_empty());
+    assert!(concesionario.consultar(&ConsultaAutos::new().anio_entre(2020, 2010)).is_empty());
+    assert!(concesionario.consultar(&ConsultaAutos::new().con_marca("Renault")).is_empty());
+}
+
+#[test]
+fn test_consultas_ordenadas_y_paginadas() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 10);
+    let autos = [
+        ("AA001AA", "Ford", "Focus", 2018, 15000, Color::Azul),
+        ("AA002AA", "Ford", "Fiesta", 2012, 8000, Color::Rojo),
+        ("AA003AA", "BMW", "Serie 3", 2020, 40000, Color::Negro),
+        ("AA004AA", "Fiat", "Cronos", 2021, 12000, Color::Azul),
+        ("AA005AA", "ford", "Focus", 2015, 11000, Color::Blanco),
+        ("AA006AA", "Fiat", "Uno", 1998, 3000, Color::Rojo),
+    ];
+    for (patente, marca, modelo, anio, precio, color) in autos {
+        let auto = Auto::new(patente.to_string(), marca.to_string(), modelo.to_string(), anio, Dinero::desde_unidades(precio, Moneda::Ars), color);
+        assert!(concesionario.agregar_auto(auto));
+    }
+    let por_precio = ConsultaAutos::new().ordenar_por(CampoOrden::Precio, Direccion::Descendente);
+    assert_eq!(patentes(concesionario.consultar(&por_precio)), vec!["AA003AA", "AA001AA", "AA004AA", "AA005AA", "AA002AA", "AA006AA"]);
+    assert_eq!(patentes(concesionario.consultar(&por_precio.clone().paginar(2, 4))), vec!["AA002AA", "AA006AA"]);
+    assert!(concesionario.consultar(&por_precio.clone().paginar(3, 4)).is_empty());
+    assert!(concesionario.consultar(&por_precio.clone().paginar(usize::MAX, 2)).is_empty());
+
+    let por_marca = ConsultaAutos::new().ordenar_por(CampoOrden::Marca, Direccion::Ascendente).anio_entre(2000, 2025);
+    assert_eq!(patentes(concesionario.consultar(&por_marca)), vec!["AA003AA", "AA004AA", "AA001AA", "AA002AA", "AA005AA"]);
+    let por_anio = ConsultaAutos::new().con_marca("fiat").ordenar_por(CampoOrden::Anio, Direccion::Ascendente);
+    assert_eq!(patentes(concesionario.consultar(&por_anio)), vec!["AA006AA", "AA004AA"]);
+
+    // los precios en dolares no se mezclan con los montos en pesos
+    let importado = Auto::new("AA007AA".to_string(), "BMW".to_string(), "X1".to_string(), 2022, Dinero::desde_unidades(30, Moneda::Usd), Color::Verde);
+    assert!(concesionario.agregar_auto(importado));
+    assert_eq!(patentes(concesionario.consultar(&por_precio)), vec!["AA007AA", "AA003AA", "AA001AA", "AA004AA", "AA005AA", "AA002AA", "AA006AA"]);
+}
+
+#[test]
+fn test_clientes_y_pruebas_de_manejo() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 10);
+    assert!(concesionario.agregar_auto(Auto::new("AA001AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2018, Dinero::desde_unidades(15000, Moneda::Ars), Color::Azul)));
+    concesionario.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), "221-555-0001".to_string())).unwrap();
+    concesionario.registrar_cliente(Cliente::new(28999888, "Luis".to_string(), "221-555-0002".to_string())).unwrap();
+    assert_eq!(concesionario.registrar_cliente(Cliente::new(30111222, "Otra Ana".to_string(), String::new())), Err(ErrorOperacion::ClienteDuplicado));
+
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA001AA", 30111222, Fecha::new(10, 3, 2024)), Ok(()));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA001AA", 28999888, Fecha::new(10, 3, 2024)), Err(ErrorOperacion::TurnoOcupado));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA001AA", 28999888, Fecha::new(11, 3, 2024)), Ok(()));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("ZZ000ZZ", 28999888, Fecha::new(11, 3, 2024)), Err(ErrorOperacion::AutoInexistente));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA001AA", 1, Fecha::new(12, 3, 2024)), Err(ErrorOperacion::ClienteInexistente));
+    assert_eq!(concesionario.pruebas_de_manejo_del(&Fecha::new(11, 3, 2024)).len(), 1);
+}
+
+#[test]
+fn test_reservas_con_vencimiento() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 10);
+    assert!(concesionario.agregar_auto(Auto::new("AA003AA".to_string(), "BMW".to_string(), "Serie 3".to_string(), 2020, Dinero::desde_unidades(40000, Moneda::Ars), Color::Negro)));
+    concesionario.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), "221-555-0001".to_string())).unwrap();
+    concesionario.registrar_cliente(Cliente::new(28999888, "Luis".to_string(), "221-555-0002".to_string())).unwrap();
+    let sena = Dinero::desde_unidades(1000, Moneda::Ars);
+    assert_eq!(concesionario.reservar("AA003AA", 30111222, Dinero::desde_unidades(1000, Moneda::Usd), Fecha::new(1, 3, 2024), 7).err(), Some(ErrorOperacion::SenaInvalida));
+    assert_eq!(concesionario.reservar("AA003AA", 30111222, Dinero::cero(Moneda::Ars), Fecha::new(1, 3, 2024), 7).err(), Some(ErrorOperacion::SenaInvalida));
+    // la sena no puede pasarse del precio final del auto
+    assert_eq!(concesionario.buscar_por_patente("AA003AA").unwrap().calcular_precio(), Ok(Dinero::desde_unidades(42000, Moneda::Ars)));
+    assert_eq!(concesionario.reservar("AA003AA", 30111222, Dinero::new(4_200_001, Moneda::Ars), Fecha::new(1, 3, 2024), 7).err(), Some(ErrorOperacion::SenaInvalida));
+
+    let reserva = concesionario.reservar("AA003AA", 30111222, sena, Fecha::new(28, 2, 2024), 7).unwrap();
+    assert_eq!(reserva.vencimiento, Fecha::new(6, 3, 2024));
+    // el mismo cliente tampoco puede pisar su reserva con otra sena
+    assert_eq!(concesionario.reservar("AA003AA", 30111222, Dinero::desde_unidades(500, Moneda::Ars), Fecha::new(1, 3, 2024), 30).err(), Some(ErrorOperacion::YaReservado));
+    assert_eq!(concesionario.reserva_vigente("AA003AA", &Fecha::new(1, 3, 2024)).unwrap().sena, sena);
+
+    // mientras esta vigente nadie mas puede reservarlo, probarlo ni comprarlo
+    assert_eq!(concesionario.reservar("AA003AA", 28999888, sena, Fecha::new(6, 3, 2024), 7).err(), Some(ErrorOperacion::AutoReservado));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA003AA", 28999888, Fecha::new(2, 3, 2024)), Err(ErrorOperacion::AutoReservado));
+    assert_eq!(concesionario.vender("AA003AA", 28999888, "Carla", Fecha::new(3, 3, 2024)).err(), Some(ErrorOperacion::AutoReservado));
+    assert_eq!(concesionario.agendar_prueba_de_manejo("AA003AA", 30111222, Fecha::new(2, 3, 2024)), Ok(()));
+
+    assert!(concesionario.liberar_reservas_vencidas(&Fecha::new(6, 3, 2024)).is_empty());
+    let liberadas = concesionario.liberar_reservas_vencidas(&Fecha::new(7, 3, 2024));
+    assert_eq!(liberadas.len(), 1);
+    assert_eq!(liberadas[0].sena, sena);
+    assert!(concesionario.reservar("AA003AA", 28999888, sena, Fecha::new(7, 3, 2024), 7).is_ok());
+    assert!(concesionario.cancelar_reserva("AA003AA").is_ok());
+    assert_eq!(concesionario.cancelar_reserva("AA003AA"), Err(ErrorOperacion::ReservaInexistente));
+}
+
+#[test]
+fn test_ventas() {
+    let mut concesionario = ConcesionarioAuto::new("Motosport".to_string(), "Algun lado".to_string(), 1);
+    concesionario.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), String::new())).unwrap();
+    let auto = Auto::new("AB123CD".to_string(), "BMW".to_string(), "Serie 1".to_string(), 2019, Dinero::desde_unidades(10000, Moneda::Ars), Color::Rojo);
+    assert!(concesionario.agregar_auto(auto));
+    concesionario.reservar("AB123CD", 30111222, Dinero::desde_unidades(2000, Moneda::Ars), Fecha::new(1, 3, 2024), 10).unwrap();
+    concesionario.agendar_prueba_de_manejo("AB123CD", 30111222, Fecha::new(2, 3, 2024)).unwrap();
+
+    let venta = concesionario.vender("AB123CD", 30111222, "Carla", Fecha::new(5, 3, 2024)).unwrap();
+    // el precio cobrado es el de calcular_precio: +25 % por color y +15 % por marca
+    assert_eq!(venta.precio, Dinero::desde_unidades(14000, Moneda::Ars));
+    assert_eq!(venta.saldo(), Ok(Dinero::desde_unidades(12000, Moneda::Ars)));
+    assert_eq!(venta.vendedor, "Carla");
+    assert_eq!(venta.auto.patente, "AB123CD");
+
+    // el auto sale del inventario junto con su reserva y sus turnos, y se libera el lugar
+    assert!(concesionario.buscar_por_patente("AB123CD").is_none());
+    assert!(concesionario.reservas.is_empty());
+    assert!(concesionario.pruebas_de_manejo.is_empty());
+    let otro = Auto::new("AC456DE".to_string(), "Fiat".to_string(), "Uno".to_string(), 2010, Dinero::desde_unidades(3000, Moneda::Ars), Color::Blanco);
+    assert!(concesionario.agregar_auto(otro));
+    assert_eq!(concesionario.vender("AB123CD", 30111222, "Carla", Fecha::new(6, 3, 2024)).err(), Some(ErrorOperacion::AutoInexistente));
+
+    let venta = concesionario.vender("AC456DE", 30111222, "Pablo", Fecha::new(6, 3, 2024)).unwrap();
+    assert_eq!(venta.sena, None);
+    assert_eq!(venta.saldo(), Ok(Dinero::desde_unidades(2700, Moneda::Ars)));
+    assert_eq!(concesionario.ventas_de_cliente(30111222).len(), 2);
+    assert!(concesionario.ventas_de_cliente(28999888).is_empty());
+}
+
+#[cfg(test)]
+const REGLAS_DE_PRUEBA: &str = "
+# recargos de temporada
+nombre=Importado caro; recargo=12.5; precio_desde=ARS 20000
+nombre=Clasico; descuento=8; anio_hasta=1990; color!=rojo, negro
+nombre=Ford moderno; recargo=3; marca=ford; anio_desde=2020; color=azul,blanco
+";
+
+#[test]
+fn test_reglas_por_defecto_explicadas() {
+    let auto = Auto::new("AD400DD".to_string(), "BMW".to_string(), "Serie 3".to_string(), 1998, Dinero::desde_unidades(10000, Moneda::Ars), Color::Verde);
+    let explicado = auto.calcular_precio_con(&ReglasDePrecio::por_defecto()).unwrap();
+    let nombres: Vec<&str> = explicado.aplicadas.iter().map(|(nombre, _)| nombre.as_str()).collect();
+    assert_eq!(nombres, vec!["Color sobrio", "Marca BMW", "Anterior al 2000"]);
+    assert_eq!(explicado.aplicadas[0].1, Dinero::desde_unidades(-1000, Moneda::Ars));
+    assert_eq!(explicado.precio_final, Dinero::desde_unidades(10000, Moneda::Ars));
+    assert_eq!(explicado.to_string().lines().nth(2), Some("Marca BMW                              ARS 1500.00"));
+}
+
+#[test]
+fn test_reglas_desde_texto() {
+    let reglas = ReglasDePrecio::desde_texto(REGLAS_DE_PRUEBA).unwrap();
+    assert_eq!(reglas.reglas.len(), 3);
+    assert_eq!(reglas.reglas[1], ReglaAuto::new("Clasico", AjusteAuto::Descuento(Porcentaje::new(8)))
+        .con_condicion(Condicion::AnioHasta(1990))
+        .con_condicion(Condicion::ColorFueraDe(vec![Color::Rojo, Color::Negro])));
+
+    let ford = Auto::new("AE500EE".to_string(), "Ford".to_string(), "Focus".to_string(), 2021, Dinero::desde_unidades(30000, Moneda::Ars), Color::Azul);
+    let explicado = ford.calcular_precio_con(&reglas).unwrap();
+    assert_eq!(explicado.aplicadas.len(), 2);
+    assert_eq!(explicado.precio_final, Dinero::desde_unidades(34650, Moneda::Ars));
+
+    // fuera del rango de precio, de anio y en otra moneda no aplica ninguna
+    let clasico = Auto::new("AE500EE".to_string(), "Ford".to_string(), "Falcon".to_string(), 1985, Dinero::desde_unidades(30000, Moneda::Usd), Color::Rojo);
+    let explicado = clasico.calcular_precio_con(&reglas).unwrap();
+    assert!(explicado.aplicadas.is_empty());
+    assert_eq!(explicado.precio_final, Dinero::desde_unidades(30000, Moneda::Usd));
+}
+
+#[test]
+fn test_reglas_invalidas() {
+    let error = |linea: usize, error: ErrorLinea| Err(ErrorReglas::Linea { linea, error });
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; recargo=5\nnombre=B; recargo=5; pais=AR"), error(2, ErrorLinea::CampoDesconocido("pais".to_string())));
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; recargo=5; color=violeta"), error(1, ErrorLinea::ValorInvalido("violeta".to_string())));
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; recargo=5.125"), error(1, ErrorLinea::ValorInvalido("5.125".to_string())));
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; precio_desde=1000; recargo=1"), error(1, ErrorLinea::ValorInvalido("1000".to_string())));
+    assert_eq!(ReglasDePrecio::desde_texto("recargo=5"), error(1, ErrorLinea::SinNombre));
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; marca=BMW"), error(1, ErrorLinea::SinAjuste));
+    assert_eq!(ReglasDePrecio::desde_texto("nombre=A; recargo=5; descuento=5"), error(1, ErrorLinea::AjusteRepetido));
+}
+
+#[test]
+fn test_reglas_desde_archivo() {
+    let ruta = std::env::temp_dir().join(format!("reglas_autos_{}.txt", std::process::id()));
+    fs::write(&ruta, REGLAS_DE_PRUEBA).unwrap();
+    let reglas = ReglasDePrecio::desde_archivo(&ruta);
+    fs::remove_file(&ruta).unwrap();
+    assert_eq!(reglas, ReglasDePrecio::desde_texto(REGLAS_DE_PRUEBA));
+    assert_eq!(ReglasDePrecio::desde_archivo(&ruta), Err(ErrorReglas::Archivo(io::ErrorKind::NotFound)));
 }
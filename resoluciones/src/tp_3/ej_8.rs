@@ -1,74 +1,312 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 
-enum Genero {
+use super::comun::{normalizar, Direccion};
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
+
+const PUNTUACION_MINIMA: u8 = 1;
+const PUNTUACION_MAXIMA: u8 = 5;
+
+// el orden de las variantes es el que se usa al ordenar por genero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Genero {
     Rock,
     Pop,
     Rap,
     Jazz,
     Otros,
 }
-impl Genero {
-    fn es_igual(&self, otro_genero:&Genero) -> bool {
-        match (self, otro_genero) {
-            (Genero::Jazz, Genero::Jazz) => true,
-            (Genero::Pop, Genero::Pop) => true,
-            (Genero::Rap, Genero::Rap) => true,
-            (Genero::Rock, Genero::Rock) => true,
-            (Genero::Otros, Genero::Otros) => true,
-            _ => false,
-        }
+
+impl ConNombre for Genero {
+    const VARIANTES: &'static [(Genero, &'static str)] = &[
+        (Genero::Rock, "rock"),
+        (Genero::Pop, "pop"),
+        (Genero::Rap, "rap"),
+        (Genero::Jazz, "jazz"),
+        (Genero::Otros, "otros"),
+    ];
+}
+
+impl fmt::Display for Genero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for Genero {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Genero::desde_nombre(texto)
     }
 }
 
 
-struct Cancion {
+// la puntuacion que se quiso cargar, fuera de 1 a 5
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PuntuacionInvalida(pub u8);
+
+#[derive(Debug, Clone)]
+pub struct Cancion {
     titulo: String,
     artista: String,
     genero: Genero,
+    duracion: u32, // en segundos
+    album: Option<String>,
+    anio: Option<u32>,
+    pista: Option<u32>,
+    puntuacion: Option<u8>, // de 1 a 5 estrellas
+    ubicacion: Option<String>, // ruta o URL del archivo de audio
+}
+
+// los metadatos se completan de a poco: una cancion sigue siendo la misma
+// aunque tenga o no duracion, album o puntuacion
+impl PartialEq for Cancion {
+    fn eq(&self, otra: &Cancion) -> bool {
+        self.titulo == otra.titulo &&
+        self.artista == otra.artista &&
+        self.genero == otra.genero
+    }
 }
 
 impl Cancion {
-    fn new(titulo:String, artista:String, genero:Genero) -> Cancion {
-        Cancion {titulo, artista, genero}
+    pub fn new(titulo:String, artista:String, genero:Genero) -> Cancion {
+        Cancion {titulo, artista, genero, duracion: 0, album: None, anio: None, pista: None, puntuacion: None, ubicacion: None}
+    }
+
+    pub fn con_duracion(mut self, segundos: u32) -> Cancion {
+        self.duracion = segundos;
+        self
+    }
+
+    pub fn con_album(mut self, album: String) -> Cancion {
+        self.album = Some(album);
+        self
+    }
+
+    pub fn con_anio(mut self, anio: u32) -> Cancion {
+        self.anio = Some(anio);
+        self
+    }
+
+    pub fn con_pista(mut self, pista: u32) -> Cancion {
+        self.pista = Some(pista);
+        self
+    }
+
+    pub fn con_puntuacion(mut self, estrellas: u8) -> Result<Cancion, PuntuacionInvalida> {
+        if !(PUNTUACION_MINIMA..=PUNTUACION_MAXIMA).contains(&estrellas) {
+            return Err(PuntuacionInvalida(estrellas));
+        }
+        self.puntuacion = Some(estrellas);
+        Ok(self)
+    }
+
+    pub fn con_ubicacion(mut self, ubicacion: String) -> Cancion {
+        self.ubicacion = Some(ubicacion);
+        self
+    }
+
+    pub fn duracion(&self) -> u32 {
+        self.duracion
+    }
+
+    pub fn album(&self) -> Option<&str> {
+        self.album.as_deref()
+    }
+
+    pub fn anio(&self) -> Option<u32> {
+        self.anio
+    }
+
+    pub fn pista(&self) -> Option<u32> {
+        self.pista
+    }
+
+    pub fn puntuacion(&self) -> Option<u8> {
+        self.puntuacion
+    }
+
+    pub fn ubicacion(&self) -> Option<&str> {
+        self.ubicacion.as_deref()
+    }
+
+    pub fn titulo(&self) -> &str {
+        &self.titulo
+    }
+
+    pub fn artista(&self) -> &str {
+        &self.artista
+    }
+
+    pub fn genero(&self) -> Genero {
+        self.genero
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CampoCancion {
+    Titulo,
+    Artista,
+    Genero,
+    Duracion,
+    Album,
+    Anio,
+    Pista,
+    Puntuacion,
+}
+
+// "m:ss", o "h:mm:ss" desde la hora
+pub fn formatear_duracion(segundos: u32) -> String {
+    let (horas, minutos, segundos) = (segundos / 3600, segundos % 3600 / 60, segundos % 60);
+    if horas > 0 {
+        format!("{}:{:02}:{:02}", horas, minutos, segundos)
+    } else {
+        format!("{}:{:02}", minutos, segundos)
+    }
+}
+
+// los datos que faltan van siempre al final, sea cual sea la direccion
+fn comparar_opcionales<T: Ord>(a: Option<T>, b: Option<T>, direccion: Direccion) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if direccion == Direccion::Descendente => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn comparar_canciones(a: &Cancion, b: &Cancion, campo: CampoCancion, direccion: Direccion) -> Ordering {
+    match campo {
+        CampoCancion::Titulo => comparar_opcionales(Some(a.titulo.to_lowercase()), Some(b.titulo.to_lowercase()), direccion),
+        CampoCancion::Artista => comparar_opcionales(Some(a.artista.to_lowercase()), Some(b.artista.to_lowercase()), direccion),
+        CampoCancion::Genero => comparar_opcionales(Some(a.genero), Some(b.genero), direccion),
+        CampoCancion::Duracion => comparar_opcionales(Some(a.duracion), Some(b.duracion), direccion),
+        CampoCancion::Album => comparar_opcionales(a.album.as_ref().map(|album| album.to_lowercase()), b.album.as_ref().map(|album| album.to_lowercase()), direccion),
+        CampoCancion::Anio => comparar_opcionales(a.anio, b.anio, direccion),
+        CampoCancion::Pista => comparar_opcionales(a.pista, b.pista, direccion),
+        CampoCancion::Puntuacion => comparar_opcionales(a.puntuacion, b.puntuacion, direccion),
+    }
+}
+
+// de mejor a peor; el orden de las variantes es el del ranking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Coincidencia {
+    Exacta,
+    Prefijo,
+    Contiene,
+    Aproximada(usize), // con la distancia de edicion
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultadoBusqueda<'a> {
+    cancion: &'a Cancion,
+    campo: CampoCancion, // donde se encontro la mejor coincidencia
+    coincidencia: Coincidencia,
+}
+
+impl<'a> ResultadoBusqueda<'a> {
+    pub fn cancion(&self) -> &'a Cancion {
+        self.cancion
     }
 
-    fn es_igual(&self, otra_cancion:&Cancion) -> bool {
-        self.titulo == otra_cancion.titulo &&
-        self.artista == otra_cancion.artista &&
-        self.genero_comparable() == otra_cancion.genero_comparable()
+    pub fn campo(&self) -> CampoCancion {
+        self.campo
     }
 
-    fn genero_comparable(&self) -> &str {
-        match self.genero {
-            Genero::Jazz => "jazz",
-            Genero::Pop => "pop",
-            Genero::Rap => "rap",
-            Genero::Rock => "rock",
-            Genero::Otros => "otros"
+    pub fn coincidencia(&self) -> Coincidencia {
+        self.coincidencia
+    }
+}
+
+// Levenshtein por caracteres, con dos filas
+fn distancia_edicion(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut anterior: Vec<usize> = (0..=b.len()).collect();
+    let mut actual = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        actual[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let sustitucion = anterior[j] + usize::from(ca != *cb);
+            actual[j + 1] = sustitucion.min(anterior[j + 1] + 1).min(actual[j] + 1);
         }
+        std::mem::swap(&mut anterior, &mut actual);
+    }
+    anterior[b.len()]
+}
+
+// errores de tipeo que se aceptan segun el largo de lo que se busca
+fn tolerancia(consulta: &str) -> usize {
+    match consulta.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+// los dos textos ya normalizados; el aproximado compara la consulta con cada
+// tramo del campo que tiene la misma cantidad de palabras
+fn coincidencia_en(campo: &str, consulta: &str) -> Option<Coincidencia> {
+    if campo == consulta {
+        return Some(Coincidencia::Exacta);
+    }
+    if campo.starts_with(consulta) {
+        return Some(Coincidencia::Prefijo);
+    }
+    if campo.contains(consulta) {
+        return Some(Coincidencia::Contiene);
+    }
+    let palabras: Vec<&str> = campo.split(' ').collect();
+    let largo = consulta.split(' ').count().min(palabras.len());
+    let distancia = palabras.windows(largo)
+        .map(|tramo| distancia_edicion(&tramo.join(" "), consulta))
+        .min()?;
+    (distancia <= tolerancia(consulta)).then_some(Coincidencia::Aproximada(distancia))
+}
+
+// ante la misma coincidencia pesa mas el titulo que el artista y que el album
+fn prioridad(campo: CampoCancion) -> usize {
+    match campo {
+        CampoCancion::Titulo => 0,
+        CampoCancion::Artista => 1,
+        _ => 2,
     }
 }
 
-struct Playlist {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playlist {
     lista_canciones: Vec<Cancion>,
     nombre: String,
 }
 
 impl Playlist {
-    fn new(nombre: String) -> Self {
+    pub fn new(nombre: String) -> Self {
         Playlist{lista_canciones: Vec::new(), nombre}}
 
-    fn agregar_cancion(&mut self, cancion: Cancion) {
+    pub fn nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn canciones(&self) -> &[Cancion] {
+        &self.lista_canciones
+    }
+
+    pub fn agregar_cancion(&mut self, cancion: Cancion) {
         self.lista_canciones.push(cancion);
     }
 
     fn eliminar_cancion(&mut self, cancion:&Cancion) {
-        if let Some(pos) = self.lista_canciones.iter().position(|c| c.es_igual(&cancion)) {
+        if let Some(pos) = self.lista_canciones.iter().position(|c| c == cancion) {
             self.lista_canciones.remove(pos);
         } 
     }
 
     fn mover_cancion(&mut self, cancion:&Cancion, nueva_pos: usize) {
-        if let Some(pos_actual) = self.lista_canciones.iter().position(|c| c.es_igual(&cancion)) {
+        if let Some(pos_actual) = self.lista_canciones.iter().position(|c| c == cancion) {
             if nueva_pos < self.lista_canciones.len() {
                 let cancion = self.lista_canciones.remove(pos_actual);
                 self.lista_canciones.insert(nueva_pos, cancion);
@@ -82,7 +320,7 @@ impl Playlist {
         
 
     fn obtener_por_genero(&self, genero:Genero) -> Vec<&Cancion> {
-        self.lista_canciones.iter().filter(|elem| elem.genero.es_igual(&genero)).collect()
+        self.lista_canciones.iter().filter(|elem| elem.genero == genero).collect()
     }
 
     fn obtener_por_artista(&self, artista: String) -> Vec<&Cancion> {
@@ -97,9 +335,60 @@ impl Playlist {
         self.lista_canciones.clear();
     }
 
+    pub fn duracion_total(&self) -> u64 {
+        self.lista_canciones.iter().map(|cancion| u64::from(cancion.duracion)).sum()
+    }
+
+    // solo cuentan las canciones puntuadas
+    pub fn puntuacion_promedio(&self) -> Option<f64> {
+        let puntuaciones: Vec<u8> = self.lista_canciones.iter().filter_map(|cancion| cancion.puntuacion).collect();
+        if puntuaciones.is_empty() {
+            return None;
+        }
+        let suma: u32 = puntuaciones.iter().map(|&estrellas| estrellas as u32).sum();
+        Some(suma as f64 / puntuaciones.len() as f64)
+    }
+
+    // el orden es estable: las canciones con el mismo valor quedan como estaban
+    pub fn ordenar_por(&mut self, campo: CampoCancion, direccion: Direccion) {
+        self.lista_canciones.sort_by(|a, b| comparar_canciones(a, b, campo, direccion));
+    }
+
+    // busca en titulo, artista y album sin importar mayusculas ni tildes y
+    // tolerando errores de tipeo; los resultados van de la mejor coincidencia
+    // a la peor y, si empatan, en el orden de la playlist
+    // una consulta sin letras ni numeros no encuentra nada, aunque haya un
+    // titulo escrito solo con esos signos
+    pub fn buscar(&self, consulta: &str) -> Vec<ResultadoBusqueda<'_>> {
+        if !consulta.chars().any(char::is_alphanumeric) {
+            return Vec::new();
+        }
+        let consulta = normalizar(consulta);
+        let mut resultados: Vec<ResultadoBusqueda> = self.lista_canciones.iter().filter_map(|cancion| {
+            let campos = [
+                (CampoCancion::Titulo, Some(cancion.titulo.as_str())),
+                (CampoCancion::Artista, Some(cancion.artista.as_str())),
+                (CampoCancion::Album, cancion.album.as_deref()),
+            ];
+            campos.into_iter()
+                .filter_map(|(campo, texto)| Some((campo, coincidencia_en(&normalizar(texto?), &consulta)?)))
+                .min_by_key(|&(campo, coincidencia)| (coincidencia, prioridad(campo)))
+                .map(|(campo, coincidencia)| ResultadoBusqueda { cancion, campo, coincidencia })
+        }).collect();
+        resultados.sort_by_key(|resultado| (resultado.coincidencia, prioridad(resultado.campo)));
+        resultados
+    }
+
 }
 
 
+#[test]
+fn test_genero_texto_ida_y_vuelta() {
+    comprobar_variantes::<Genero>();
+    assert_eq!("JAZZ".parse::<Genero>(), Ok(Genero::Jazz));
+    assert_eq!("cumbia".parse::<Genero>(), Err(NombreDesconocido("cumbia".to_string())));
+}
+
 #[test]
 fn test_agregar_cancion() {
     let mut playlist = Playlist::new(String::from("Mi Playlist"));
@@ -116,6 +405,11 @@ fn test_eliminar_cancion() {
     playlist.agregar_cancion(cancion);
     playlist.eliminar_cancion(&Cancion::new("Titulo1".to_string(), "Artista1".to_string(), Genero::Rock));
        assert_eq!(playlist.lista_canciones.len(), 0);
+
+    // la guardada tiene duracion y la que se busca no
+    playlist.agregar_cancion(Cancion::new("Titulo2".to_string(), "Artista2".to_string(), Genero::Rap).con_duracion(180).con_puntuacion(4).unwrap());
+    playlist.eliminar_cancion(&Cancion::new("Titulo2".to_string(), "Artista2".to_string(), Genero::Rap));
+    assert!(playlist.lista_canciones.is_empty());
 }
 
 #[test]
@@ -135,7 +429,7 @@ fn test_buscar_cancion_por_nombre() {
     let cancion = Cancion::new("Titulo1".to_string(), "Artista1".to_string(), Genero::Rock);
     playlist.agregar_cancion(cancion);
     let cancion_a_encontrar = Cancion::new("Titulo1".to_string(), "Artista1".to_string(), Genero::Rock);
-    assert!(playlist.buscar_cancion_por_nombre("Titulo1".to_string()).unwrap().es_igual(&cancion_a_encontrar));
+    assert_eq!(playlist.buscar_cancion_por_nombre("Titulo1".to_string()), Some(&cancion_a_encontrar));
 }
 
 #[test]
@@ -181,4 +475,149 @@ fn test_vaciar_playlist() {
     assert_eq!(playlist.lista_canciones.len(), 1) ;
     playlist.vaciar_playlist();
     assert_eq!(playlist.lista_canciones.len(), 0);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn titulos(playlist: &Playlist) -> Vec<&str> {
+    playlist.canciones().iter().map(|cancion| cancion.titulo()).collect()
+}
+
+#[test]
+fn test_metadatos_de_cancion() {
+    let cancion = Cancion::new("Zamba".to_string(), "Mercedes".to_string(), Genero::Otros);
+    assert_eq!(cancion.duracion(), 0);
+    assert_eq!(cancion.album(), None);
+    assert_eq!(cancion.clone().con_puntuacion(0), Err(PuntuacionInvalida(0)));
+    assert_eq!(cancion.clone().con_puntuacion(6), Err(PuntuacionInvalida(6)));
+    let cancion = cancion.con_duracion(245).con_album("Cantora".to_string()).con_pista(3).con_puntuacion(1).unwrap();
+    assert_eq!(cancion.album(), Some("Cantora"));
+    assert_eq!(cancion.pista(), Some(3));
+    assert_eq!(cancion.puntuacion(), Some(1));
+    assert_eq!(formatear_duracion(cancion.duracion()), "4:05");
+    assert_eq!(formatear_duracion(4155), "1:09:15");
+}
+
+#[test]
+fn test_totales_de_playlist() {
+    let mut playlist = Playlist::new("Discos".to_string());
+    playlist.agregar_cancion(Cancion::new("Zamba".to_string(), "Mercedes".to_string(), Genero::Otros)
+        .con_duracion(245).con_album("Cantora".to_string()).con_anio(2009).con_pista(3).con_puntuacion(4).unwrap());
+    playlist.agregar_cancion(Cancion::new("alfonsina".to_string(), "Mercedes".to_string(), Genero::Otros)
+        .con_duracion(310).con_album("Cantora".to_string()).con_anio(2009).con_pista(1));
+    playlist.agregar_cancion(Cancion::new("Blues".to_string(), "Pappo".to_string(), Genero::Rock)
+        .con_duracion(3600).con_anio(1978).con_puntuacion(5).unwrap());
+    assert_eq!(playlist.duracion_total(), 4155);
+    let mut larga = Playlist::new("Larga".to_string());
+    larga.agregar_cancion(Cancion::new("Uno".to_string(), "A".to_string(), Genero::Otros).con_duracion(u32::MAX));
+    larga.agregar_cancion(Cancion::new("Dos".to_string(), "A".to_string(), Genero::Otros).con_duracion(u32::MAX));
+    assert_eq!(larga.duracion_total(), 2 * u32::MAX as u64);
+    // la cancion sin puntuar no cuenta
+    assert_eq!(playlist.puntuacion_promedio(), Some(4.5));
+    assert_eq!(Playlist::new("Vacia".to_string()).puntuacion_promedio(), None);
+}
+
+#[test]
+fn test_ordenar_playlist() {
+    let mut playlist = Playlist::new("Discos".to_string());
+    playlist.agregar_cancion(Cancion::new("Zamba".to_string(), "Mercedes".to_string(), Genero::Otros)
+        .con_duracion(245).con_album("Cantora".to_string()).con_anio(2009).con_pista(3).con_puntuacion(4).unwrap());
+    playlist.agregar_cancion(Cancion::new("alfonsina".to_string(), "Mercedes".to_string(), Genero::Otros)
+        .con_duracion(310).con_album("Cantora".to_string()).con_anio(2009).con_pista(1));
+    playlist.agregar_cancion(Cancion::new("Blues".to_string(), "Pappo".to_string(), Genero::Rock)
+        .con_duracion(3600).con_anio(1978).con_puntuacion(5).unwrap());
+    playlist.ordenar_por(CampoCancion::Titulo, Direccion::Ascendente);
+    assert_eq!(titulos(&playlist), vec!["alfonsina", "Blues", "Zamba"]);
+    playlist.ordenar_por(CampoCancion::Duracion, Direccion::Descendente);
+    assert_eq!(titulos(&playlist), vec!["Blues", "alfonsina", "Zamba"]);
+    playlist.ordenar_por(CampoCancion::Genero, Direccion::Ascendente);
+    assert_eq!(titulos(&playlist), vec!["Blues", "alfonsina", "Zamba"]);
+    // sin album o sin pista va al final en las dos direcciones
+    playlist.ordenar_por(CampoCancion::Pista, Direccion::Ascendente);
+    assert_eq!(titulos(&playlist), vec!["alfonsina", "Zamba", "Blues"]);
+    playlist.ordenar_por(CampoCancion::Album, Direccion::Descendente);
+    assert_eq!(titulos(&playlist), vec!["alfonsina", "Zamba", "Blues"]);
+    playlist.ordenar_por(CampoCancion::Puntuacion, Direccion::Descendente);
+    assert_eq!(titulos(&playlist), vec!["Blues", "Zamba", "alfonsina"]);
+    playlist.ordenar_por(CampoCancion::Anio, Direccion::Ascendente);
+    assert_eq!(titulos(&playlist), vec!["Blues", "Zamba", "alfonsina"]);
+    playlist.ordenar_por(CampoCancion::Artista, Direccion::Descendente);
+    assert_eq!(titulos(&playlist), vec!["Blues", "Zamba", "alfonsina"]);
+}
+
+#[cfg(test)]
+fn encontrados<'a>(resultados: &[ResultadoBusqueda<'a>]) -> Vec<&'a str> {
+    resultados.iter().map(|resultado| resultado.cancion().titulo()).collect()
+}
+
+#[test]
+fn test_distancia_edicion() {
+    assert_eq!(distancia_edicion("zamba", "zamba"), 0);
+    assert_eq!(distancia_edicion("zamva", "zamba"), 1);
+    assert_eq!(distancia_edicion("", "sumo"), 4);
+    assert_eq!(distancia_edicion("kitten", "sitting"), 3);
+}
+
+#[test]
+fn test_buscar_sin_mayusculas_ni_tildes() {
+    let mut playlist = Playlist::new("Busqueda".to_string());
+    playlist.agregar_cancion(Cancion::new("Canción para mi muerte".to_string(), "Sui Generis".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Zamba de mi esperanza".to_string(), "Mercedes Sosa".to_string(), Genero::Otros)
+        .con_album("Cantora".to_string()));
+    playlist.agregar_cancion(Cancion::new("Mañana en el Abasto".to_string(), "Sumo".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Sosa".to_string(), "Otro".to_string(), Genero::Pop));
+    let resultados = playlist.buscar("cancion");
+    assert_eq!(encontrados(&resultados), vec!["Canción para mi muerte"]);
+    assert_eq!(resultados[0].coincidencia(), Coincidencia::Prefijo);
+    assert_eq!(encontrados(&playlist.buscar("MANANA")), vec!["Mañana en el Abasto"]);
+    assert_eq!(encontrados(&playlist.buscar("abasto")), vec!["Mañana en el Abasto"]);
+}
+
+#[test]
+fn test_buscar_sin_letras_ni_numeros() {
+    let mut playlist = Playlist::new("Signos".to_string());
+    playlist.agregar_cancion(Cancion::new("¡!".to_string(), "Alguien".to_string(), Genero::Otros));
+    playlist.agregar_cancion(Cancion::new("Hola!".to_string(), "Alguien".to_string(), Genero::Otros));
+    assert!(playlist.buscar("  ¡! ").is_empty());
+    assert!(playlist.buscar("¡!").is_empty());
+    assert!(playlist.buscar("").is_empty());
+    assert_eq!(encontrados(&playlist.buscar("¡hola!")), vec!["Hola!"]);
+}
+
+#[test]
+fn test_buscar_con_errores_de_tipeo() {
+    let mut playlist = Playlist::new("Busqueda".to_string());
+    playlist.agregar_cancion(Cancion::new("Canción para mi muerte".to_string(), "Sui Generis".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Zamba de mi esperanza".to_string(), "Mercedes Sosa".to_string(), Genero::Otros)
+        .con_album("Cantora".to_string()));
+    playlist.agregar_cancion(Cancion::new("Mañana en el Abasto".to_string(), "Sumo".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Sosa".to_string(), "Otro".to_string(), Genero::Pop));
+    let resultados = playlist.buscar("zamva");
+    assert_eq!(encontrados(&resultados), vec!["Zamba de mi esperanza"]);
+    assert_eq!(resultados[0].coincidencia(), Coincidencia::Aproximada(1));
+    assert_eq!(encontrados(&playlist.buscar("mi esperansa")), vec!["Zamba de mi esperanza"]);
+    assert_eq!(encontrados(&playlist.buscar("sumi")), vec!["Canción para mi muerte", "Mañana en el Abasto"]);
+    // las consultas cortas tienen que coincidir tal cual
+    assert!(playlist.buscar("sux").is_empty());
+}
+
+#[test]
+fn test_buscar_en_artista_y_album_con_ranking() {
+    let mut playlist = Playlist::new("Busqueda".to_string());
+    playlist.agregar_cancion(Cancion::new("Canción para mi muerte".to_string(), "Sui Generis".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Zamba de mi esperanza".to_string(), "Mercedes Sosa".to_string(), Genero::Otros)
+        .con_album("Cantora".to_string()));
+    playlist.agregar_cancion(Cancion::new("Mañana en el Abasto".to_string(), "Sumo".to_string(), Genero::Rock));
+    playlist.agregar_cancion(Cancion::new("Sosa".to_string(), "Otro".to_string(), Genero::Pop));
+    let resultados = playlist.buscar("sosa");
+    // el titulo exacto gana a la coincidencia en el artista
+    assert_eq!(encontrados(&resultados), vec!["Sosa", "Zamba de mi esperanza"]);
+    assert_eq!(resultados[0].campo(), CampoCancion::Titulo);
+    assert_eq!((resultados[1].campo(), resultados[1].coincidencia()), (CampoCancion::Artista, Coincidencia::Contiene));
+
+    let resultados = playlist.buscar("cantora");
+    assert_eq!((resultados[0].campo(), resultados[0].coincidencia()), (CampoCancion::Album, Coincidencia::Exacta));
+
+    // con la misma coincidencia en el mismo campo queda el orden de la playlist
+    let resultados = playlist.buscar("su");
+    assert_eq!(encontrados(&resultados), vec!["Canción para mi muerte", "Mañana en el Abasto"]);
+}
@@ -1,12 +1,43 @@
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 use super::ej_3::Fecha;
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum TipoAnimal {
     Perro,
     Gato,
     Caballo,
     Otros,
 }
+
+impl ConNombre for TipoAnimal {
+    const VARIANTES: &'static [(TipoAnimal, &'static str)] = &[
+        (TipoAnimal::Perro, "perro"),
+        (TipoAnimal::Gato, "gato"),
+        (TipoAnimal::Caballo, "caballo"),
+        (TipoAnimal::Otros, "otros"),
+    ];
+}
+
+impl fmt::Display for TipoAnimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for TipoAnimal {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        TipoAnimal::desde_nombre(texto)
+    }
+}
+
+#[derive(Debug, PartialEq)]
 struct Mascota {
     nombre: String,
     edad: u32,
@@ -17,24 +48,9 @@ impl Mascota {
     fn new(nombre:String, edad:u32, tipo:TipoAnimal, dueño:Dueño) -> Mascota {
         Mascota {nombre, edad, tipo, dueño}
     }
-
-    fn es_igual(&self, otra_mascota:&Mascota) -> bool {
-        self.nombre == otra_mascota.nombre &&
-        self.edad == otra_mascota.edad &&
-        self.tipo_comparable() == otra_mascota.tipo_comparable() &&
-        self.dueño.es_igual(&otra_mascota.dueño)
-    }
-
-    fn tipo_comparable(&self) -> &str {
-        match self.tipo {
-            TipoAnimal::Caballo => "caballo",
-            TipoAnimal::Gato => "gato",
-            TipoAnimal::Perro => "perro",
-            TipoAnimal::Otros => "otros",
-        }
-    }
 }
 
+#[derive(Debug, PartialEq)]
 struct Dueño {
     nombre: String,
     direccion: String,
@@ -44,12 +60,6 @@ impl Dueño {
     fn new(nombre:String, direccion:String, telefono:u32) -> Dueño {
         Dueño{nombre, direccion, telefono}
     }
-
-    fn es_igual(&self,  otro_dueño:&Dueño) -> bool {
-        self.nombre == otro_dueño.nombre &&
-        self.direccion == otro_dueño.direccion &&
-        self.telefono == otro_dueño.telefono
-    }
 }
 
 struct Atencion {
@@ -94,7 +104,7 @@ impl Veterinaria {
     }
 
     fn eliminar_mascota(&mut self, mascota:Mascota) {
-        if let Some(pos) = self.cola_atencion.iter().position(|m| m.es_igual(&mascota)) {
+        if let Some(pos) = self.cola_atencion.iter().position(|m| *m == mascota) {
             self.cola_atencion.remove(pos);
         }
     }
@@ -108,7 +118,7 @@ impl Veterinaria {
     }
 
     fn modificar_diagnostico(&mut self, mascota:&Mascota, nuevo_diagnostico: String) -> bool {
-        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota.es_igual(mascota)) {
+        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota == *mascota) {
             self.registro_atenciones[pos].diagnostico = nuevo_diagnostico;
             true
         } else {
@@ -117,7 +127,7 @@ impl Veterinaria {
     }
 
     fn modificar_fecha(&mut self, mascota:&Mascota, nueva_fecha:Option<Fecha>) -> bool {
-        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota.es_igual(mascota)) {
+        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota == *mascota) {
             self.registro_atenciones[pos].prox_visita = nueva_fecha;
             true
         } else {
@@ -126,7 +136,7 @@ impl Veterinaria {
     }
 
     fn eliminar_atencion(&mut self, mascota:&Mascota) -> Option<Atencion> {
-        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota.es_igual(mascota)) {
+        if let Some(pos) = self.registro_atenciones.iter().position(|a| a.mascota == *mascota) {
             Some(self.registro_atenciones.remove(pos))
         } else {
             None
@@ -135,6 +145,13 @@ impl Veterinaria {
 }
 
 
+#[test]
+fn test_tipo_animal_texto_ida_y_vuelta() {
+    comprobar_variantes::<TipoAnimal>();
+    assert_eq!("Gato".parse::<TipoAnimal>(), Ok(TipoAnimal::Gato));
+    assert_eq!("loro".parse::<TipoAnimal>(), Err(NombreDesconocido("loro".to_string())));
+}
+
 #[test]
 fn test_agregar_mascota() {
     let mut vet = Veterinaria::new("Vet1".to_string(), "Dirección 123".to_string(), 1);
@@ -146,7 +163,7 @@ fn test_agregar_mascota() {
     assert_eq!(vet.cola_atencion.len(), 1);
     let dueño_agregado = Dueño::new("Juan".to_string(), "Calle Falsa 123".to_string(), 5551234);
     let mascota_agregada = Mascota::new("Fido".to_string(), 3, TipoAnimal::Perro, dueño_agregado);
-    assert!(vet.cola_atencion.front().unwrap().es_igual(&mascota_agregada));
+    assert_eq!(vet.cola_atencion.front().unwrap(), &mascota_agregada);
 }
  
 #[test]
@@ -163,7 +180,7 @@ fn test_agregar_mascota_prioritaria() {
     assert_eq!(vet.cola_atencion.len(), 2);
     let dueño_prioritario = Dueño::new("Maria".to_string(), "Calle Verdadera 456".to_string(), 5555678);
     let mascota_prioritaria = Mascota::new("Luna".to_string(), 2, TipoAnimal::Gato, dueño_prioritario);
-    assert!(vet.cola_atencion.front().unwrap().es_igual(&mascota_prioritaria));
+    assert_eq!(vet.cola_atencion.front().unwrap(), &mascota_prioritaria);
 }
 
 
@@ -178,7 +195,7 @@ fn test_atender_proxima() {
 
     let dueño_mascota_a_atender = Dueño::new("Juan".to_string(), "Calle Falsa 123".to_string(), 5551234);
     let mascota_a_atender = Mascota::new("Fido".to_string(), 3, TipoAnimal::Perro, dueño_mascota_a_atender);
-    assert!(atendida.es_igual(&mascota_a_atender));
+    assert_eq!(atendida, mascota_a_atender);
     assert_eq!(vet.cola_atencion.len(), 0);
 }
 
@@ -209,7 +226,7 @@ fn test_registrar_atencion() {
     assert_eq!(vet.registro_atenciones.len(), 1);
     let dueño_mascota_atendida = Dueño::new("Juan".to_string(), "Calle Falsa 123".to_string(), 5551234);
     let mascota_atendida = Mascota::new("Fido".to_string(), 3, TipoAnimal::Perro, dueño_mascota_atendida);
-    assert!(vet.registro_atenciones[0].mascota.es_igual(&mascota_atendida));
+    assert_eq!(vet.registro_atenciones[0].mascota, mascota_atendida);
 }
 
 #[test]
@@ -275,5 +292,5 @@ fn test_eliminar_atencion() {
 
     assert!(eliminada.is_some());
     assert_eq!(vet.registro_atenciones.len(), 0);
-    assert!(eliminada.unwrap().mascota.es_igual(&mascota_a_eliminar));
+    assert_eq!(eliminada.unwrap().mascota, mascota_a_eliminar);
 }
\ No newline at end of file
@@ -0,0 +1,347 @@
+// Planes de cuotas para financiar un auto: sistema frances (cuota fija) y
+// aleman (amortizacion fija), con la tasa nominal anual dividida en 12 meses.
+// Los montos van en centavos; solo la cuota francesa y el CFT usan f64.
+
+use std::fmt::Write;
+
+use super::ej_7::Auto;
+use crate::dinero::{Dinero, ErrorDinero, Porcentaje};
+#[cfg(test)]
+use super::ej_7::Color;
+#[cfg(test)]
+use crate::dinero::{pesos, Moneda};
+
+const ITERACIONES_CFT: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SistemaAmortizacion {
+    Frances, // cuota constante, el capital crece y el interes baja
+    Aleman,  // capital constante, la cuota baja mes a mes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFinanciacion {
+    SinCuotas,
+    TasaNegativa,
+    AnticipoInvalido, // negativo o mayor al precio
+    CargoNegativo,    // gastos de otorgamiento o cargo mensual
+    Dinero(ErrorDinero),
+}
+
+impl From<ErrorDinero> for ErrorFinanciacion {
+    fn from(error: ErrorDinero) -> Self {
+        ErrorFinanciacion::Dinero(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Financiacion {
+    precio: Dinero,
+    anticipo: Dinero,
+    cuotas: u32,
+    tna: Porcentaje,
+    gastos_otorgamiento: Dinero, // se descuentan del monto que recibe el cliente
+    cargo_mensual: Dinero,       // seguro, gastos administrativos, etc. en cada cuota
+}
+
+impl Financiacion {
+    pub fn new(precio: Dinero, anticipo: Dinero, cuotas: u32, tna: Porcentaje) -> Result<Financiacion, ErrorFinanciacion> {
+        if cuotas == 0 {
+            return Err(ErrorFinanciacion::SinCuotas);
+        }
+        if tna.centesimas() < 0 {
+            return Err(ErrorFinanciacion::TasaNegativa);
+        }
+        if anticipo.es_negativo() || precio.restar(&anticipo)?.es_negativo() {
+            return Err(ErrorFinanciacion::AnticipoInvalido);
+        }
+        let cero = Dinero::cero(precio.moneda());
+        Ok(Financiacion { precio, anticipo, cuotas, tna, gastos_otorgamiento: cero, cargo_mensual: cero })
+    }
+
+    // financia el precio de lista del auto, con las reglas por defecto
+    pub fn para_auto(auto: &Auto, anticipo: Dinero, cuotas: u32, tna: Porcentaje) -> Result<Financiacion, ErrorFinanciacion> {
+        Financiacion::new(auto.calcular_precio()?, anticipo, cuotas, tna)
+    }
+
+    pub fn con_gastos_otorgamiento(mut self, gastos: Dinero) -> Result<Financiacion, ErrorFinanciacion> {
+        self.gastos_otorgamiento = self.validar_cargo(gastos)?;
+        Ok(self)
+    }
+
+    pub fn con_cargo_mensual(mut self, cargo: Dinero) -> Result<Financiacion, ErrorFinanciacion> {
+        self.cargo_mensual = self.validar_cargo(cargo)?;
+        Ok(self)
+    }
+
+    fn validar_cargo(&self, cargo: Dinero) -> Result<Dinero, ErrorFinanciacion> {
+        if cargo.moneda() != self.precio.moneda() {
+            return Err(ErrorFinanciacion::Dinero(ErrorDinero::MonedasDistintas));
+        }
+        if cargo.es_negativo() {
+            return Err(ErrorFinanciacion::CargoNegativo);
+        }
+        Ok(cargo)
+    }
+
+    pub fn capital_financiado(&self) -> Dinero {
+        // validado en new: es la misma moneda y no desborda
+        Dinero::new(self.precio.centavos() - self.anticipo.centavos(), self.precio.moneda())
+    }
+
+    pub fn plan(&self, sistema: SistemaAmortizacion) -> Result<PlanDeCuotas, ErrorFinanciacion> {
+        let capital = self.capital_financiado();
+        let moneda = capital.moneda();
+        let mut saldo = capital.centavos();
+        let cuota_francesa = self.cuota_francesa(saldo)?;
+        let amortizacion_alemana = saldo / self.cuotas as i64;
+        let mut cuotas = Vec::new();
+        for numero in 1..=self.cuotas {
+            let interes = interes_mensual(saldo, self.tna)?;
+            // la ultima cuota absorbe los centavos que quedaron por redondeo
+            let amortizacion = if numero == self.cuotas {
+                saldo
+            } else {
+                match sistema {
+                    SistemaAmortizacion::Frances => (cuota_francesa - interes).clamp(0, saldo),
+                    SistemaAmortizacion::Aleman => amortizacion_alemana,
+                }
+            };
+            saldo -= amortizacion;
+            let capital = Dinero::new(amortizacion, moneda);
+            let interes = Dinero::new(interes, moneda);
+            let total = capital.sumar(&interes)?.sumar(&self.cargo_mensual)?;
+            cuotas.push(Cuota { numero, capital, interes, cargos: self.cargo_mensual, total, saldo: Dinero::new(saldo, moneda) });
+        }
+        Ok(PlanDeCuotas {
+            capital,
+            neto_recibido: capital.restar(&self.gastos_otorgamiento)?,
+            cuotas,
+        })
+    }
+
+    // P * i / (1 - (1 + i)^-n), redondeada al centavo
+    fn cuota_francesa(&self, capital: i64) -> Result<i64, ErrorDinero> {
+        let tasa = self.tna.centesimas() as f64 / 10_000.0 / 12.0;
+        if tasa == 0.0 {
+            return Ok(capital / self.cuotas as i64);
+        }
+        // con mas cuotas de las que entran en un i32 el descuento ya es cero
+        let descuento = i32::try_from(self.cuotas).map_or(0.0, |cuotas| (1.0 + tasa).powi(-cuotas));
+        let cuota = (capital as f64 * tasa / (1.0 - descuento)).round();
+        if !cuota.is_finite() || cuota >= i64::MAX as f64 {
+            return Err(ErrorDinero::Desbordamiento);
+        }
+        Ok(cuota as i64)
+    }
+}
+
+// saldo * tna / 12, redondeado al centavo con la mitad hacia arriba
+fn interes_mensual(saldo: i64, tna: Porcentaje) -> Result<i64, ErrorDinero> {
+    let producto = saldo as i128 * tna.centesimas() as i128;
+    i64::try_from((producto + 60_000) / 120_000).map_err(|_| ErrorDinero::Desbordamiento)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cuota {
+    numero: u32,
+    capital: Dinero,
+    interes: Dinero,
+    cargos: Dinero,
+    total: Dinero,
+    saldo: Dinero, // lo que queda por amortizar despues de pagarla
+}
+
+impl Cuota {
+    pub fn capital(&self) -> Dinero {
+        self.capital
+    }
+
+    pub fn interes(&self) -> Dinero {
+        self.interes
+    }
+
+    pub fn total(&self) -> Dinero {
+        self.total
+    }
+
+    pub fn saldo(&self) -> Dinero {
+        self.saldo
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PlanDeCuotas {
+    capital: Dinero,
+    neto_recibido: Dinero, // capital menos gastos de otorgamiento
+    cuotas: Vec<Cuota>,
+}
+
+impl PlanDeCuotas {
+    pub fn capital(&self) -> Dinero {
+        self.capital
+    }
+
+    pub fn cuotas(&self) -> &[Cuota] {
+        &self.cuotas
+    }
+
+    pub fn total_intereses(&self) -> Result<Dinero, ErrorFinanciacion> {
+        let cero = Dinero::cero(self.capital.moneda());
+        Ok(self.cuotas.iter().try_fold(cero, |suma, cuota| suma.sumar(&cuota.interes))?)
+    }
+
+    pub fn total_a_pagar(&self) -> Result<Dinero, ErrorFinanciacion> {
+        let cero = Dinero::cero(self.capital.moneda());
+        Ok(self.cuotas.iter().try_fold(cero, |suma, cuota| suma.sumar(&cuota.total))?)
+    }
+
+    // costo financiero total: la tasa efectiva anual, en porcentaje, que iguala
+    // lo que el cliente recibe con lo que paga. La tasa mensual se busca por
+    // biseccion porque con gastos no hay formula cerrada.
+    pub fn cft(&self) -> Option<f64> {
+        let neto = self.neto_recibido.centavos() as f64;
+        let pagos: Vec<f64> = self.cuotas.iter().map(|cuota| cuota.total.centavos() as f64).collect();
+        // cada pago se descuenta un mes mas que el anterior
+        let valor_actual = |tasa: f64| -> f64 {
+            pagos.iter().fold((0.0, 1.0), |(suma, factor), pago| {
+                let factor = factor / (1.0 + tasa);
+                (suma + pago * factor, factor)
+            }).0
+        };
+        if neto <= 0.0 {
+            return None;
+        }
+        if valor_actual(0.0) <= neto {
+            return Some(0.0);
+        }
+        let (mut baja, mut alta) = (0.0, 1.0);
+        if valor_actual(alta) > neto {
+            return None;
+        }
+        for _ in 0..ITERACIONES_CFT {
+            let media = (baja + alta) / 2.0;
+            if valor_actual(media) > neto {
+                baja = media;
+            } else {
+                alta = media;
+            }
+        }
+        let mensual = (baja + alta) / 2.0;
+        Some(((1.0 + mensual).powi(12) - 1.0) * 100.0)
+    }
+
+    pub fn a_csv(&self) -> String {
+        let mut csv = String::from("cuota,capital,interes,cargos,total,saldo\n");
+        for cuota in &self.cuotas {
+            let _ = writeln!(csv, "{},{},{},{},{},{}", cuota.numero, monto(cuota.capital), monto(cuota.interes),
+                monto(cuota.cargos), monto(cuota.total), monto(cuota.saldo));
+        }
+        csv
+    }
+}
+
+// el monto sin la moneda, para que la planilla lo tome como numero
+fn monto(dinero: Dinero) -> String {
+    let signo = if dinero.es_negativo() { "-" } else { "" };
+    let absoluto = dinero.centavos().unsigned_abs();
+    format!("{}{}.{:02}", signo, absoluto / 100, absoluto % 100)
+}
+
+
+#[test]
+fn test_sistema_frances() {
+    // 60 % anual son 5 % por mes
+    let financiacion = Financiacion::new(pesos(130000), pesos(30000), 12, Porcentaje::new(60)).unwrap();
+    let plan = financiacion.plan(SistemaAmortizacion::Frances).unwrap();
+    let cuotas = plan.cuotas();
+    assert_eq!(cuotas.len(), 12);
+    assert_eq!(cuotas[0].interes(), pesos(5000));
+    assert_eq!(cuotas[0].total(), Dinero::new(1128254, Moneda::Ars));
+    assert_eq!(cuotas[0].capital(), Dinero::new(628254, Moneda::Ars));
+    assert!(cuotas[..11].iter().all(|cuota| cuota.total() == cuotas[0].total()));
+    assert!(cuotas[1].interes().centavos() < cuotas[0].interes().centavos());
+    assert!(cuotas[11].saldo().es_cero());
+    let capital: i64 = cuotas.iter().map(|cuota| cuota.capital().centavos()).sum();
+    assert_eq!(capital, pesos(100000).centavos());
+    assert_eq!(plan.total_a_pagar().unwrap().centavos(), plan.capital().centavos() + plan.total_intereses().unwrap().centavos());
+}
+
+#[test]
+fn test_sistema_aleman() {
+    let financiacion = Financiacion::new(pesos(100000), pesos(0), 4, Porcentaje::new(12)).unwrap();
+    let plan = financiacion.plan(SistemaAmortizacion::Aleman).unwrap();
+    let totales: Vec<Dinero> = plan.cuotas().iter().map(|cuota| cuota.total()).collect();
+    assert_eq!(totales, vec![pesos(26000), pesos(25750), pesos(25500), pesos(25250)]);
+    assert_eq!(plan.total_intereses(), Ok(pesos(2500)));
+
+    // los centavos que no se pueden repartir quedan en la ultima cuota
+    let plan = Financiacion::new(pesos(100), pesos(0), 3, Porcentaje::new(0)).unwrap().plan(SistemaAmortizacion::Aleman).unwrap();
+    let capitales: Vec<i64> = plan.cuotas().iter().map(|cuota| cuota.capital().centavos()).collect();
+    assert_eq!(capitales, vec![3333, 3333, 3334]);
+}
+
+#[test]
+fn test_cft() {
+    let financiacion = Financiacion::new(pesos(100000), pesos(0), 12, Porcentaje::new(60)).unwrap();
+    // sin gastos el CFT es la tasa efectiva anual: 1,05^12 - 1
+    let cft = financiacion.plan(SistemaAmortizacion::Frances).unwrap().cft().unwrap();
+    assert!((cft - 79.5856).abs() < 0.01);
+    let cft_aleman = financiacion.plan(SistemaAmortizacion::Aleman).unwrap().cft().unwrap();
+    assert!((cft_aleman - 79.5856).abs() < 0.01);
+
+    let con_gastos = financiacion.con_gastos_otorgamiento(pesos(2000)).unwrap().con_cargo_mensual(pesos(500)).unwrap();
+    let plan = con_gastos.plan(SistemaAmortizacion::Frances).unwrap();
+    assert_eq!(plan.cuotas()[0].total(), Dinero::new(1178254, Moneda::Ars));
+    assert!(plan.cft().unwrap() > cft + 10.0);
+
+    let sin_interes = Financiacion::new(pesos(1200), pesos(0), 12, Porcentaje::new(0)).unwrap();
+    assert_eq!(sin_interes.plan(SistemaAmortizacion::Frances).unwrap().cft(), Some(0.0));
+}
+
+#[test]
+fn test_financiacion_invalida() {
+    assert_eq!(Financiacion::new(pesos(100), pesos(0), 0, Porcentaje::new(10)), Err(ErrorFinanciacion::SinCuotas));
+    assert_eq!(Financiacion::new(pesos(100), pesos(0), 6, Porcentaje::new(-1)), Err(ErrorFinanciacion::TasaNegativa));
+    assert_eq!(Financiacion::new(pesos(100), pesos(150), 6, Porcentaje::new(10)), Err(ErrorFinanciacion::AnticipoInvalido));
+    assert_eq!(Financiacion::new(pesos(100), Dinero::desde_unidades(10, Moneda::Usd), 6, Porcentaje::new(10)),
+        Err(ErrorFinanciacion::Dinero(ErrorDinero::MonedasDistintas)));
+    let financiacion = Financiacion::new(pesos(100), pesos(0), 6, Porcentaje::new(10)).unwrap();
+    assert_eq!(financiacion.con_cargo_mensual(Dinero::desde_unidades(1, Moneda::Usd)),
+        Err(ErrorFinanciacion::Dinero(ErrorDinero::MonedasDistintas)));
+    assert_eq!(financiacion.con_gastos_otorgamiento(Dinero::desde_unidades(5, Moneda::Eur)),
+        Err(ErrorFinanciacion::Dinero(ErrorDinero::MonedasDistintas)));
+    assert_eq!(financiacion.con_cargo_mensual(pesos(-1)), Err(ErrorFinanciacion::CargoNegativo));
+    assert_eq!(financiacion.con_gastos_otorgamiento(pesos(-10)), Err(ErrorFinanciacion::CargoNegativo));
+    assert_eq!(financiacion.con_cargo_mensual(pesos(0)), Ok(financiacion));
+}
+
+#[test]
+fn test_financiacion_desbordada() {
+    let usura = Financiacion::new(Dinero::new(i64::MAX / 2, Moneda::Ars), pesos(0), 2, Porcentaje::con_decimales(i64::MAX)).unwrap();
+    assert_eq!(usura.plan(SistemaAmortizacion::Aleman), Err(ErrorFinanciacion::Dinero(ErrorDinero::Desbordamiento)));
+    assert_eq!(usura.plan(SistemaAmortizacion::Frances), Err(ErrorFinanciacion::Dinero(ErrorDinero::Desbordamiento)));
+
+    // cada cuota entra en un i64 pero la suma no
+    let con_cargo = Financiacion::new(pesos(300), pesos(0), 3, Porcentaje::new(0)).unwrap()
+        .con_cargo_mensual(Dinero::new(i64::MAX / 2, Moneda::Ars)).unwrap();
+    let plan = con_cargo.plan(SistemaAmortizacion::Frances).unwrap();
+    assert_eq!(plan.total_a_pagar(), Err(ErrorFinanciacion::Dinero(ErrorDinero::Desbordamiento)));
+    assert_eq!(plan.total_intereses(), Ok(pesos(0)));
+
+    // tantas cuotas que no entran en un i32
+    let eterna = Financiacion::new(pesos(100), pesos(0), u32::MAX, Porcentaje::new(12)).unwrap();
+    assert_eq!(eterna.cuota_francesa(10_000), Ok(100));
+}
+
+#[test]
+fn test_financiar_auto_y_exportar() {
+    // Rojo: 10000 + 25 %
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2020, pesos(10000), Color::Rojo);
+    let financiacion = Financiacion::para_auto(&auto, pesos(4500), 2, Porcentaje::new(24)).unwrap();
+    assert_eq!(financiacion.capital_financiado(), pesos(8000));
+    let csv = financiacion.plan(SistemaAmortizacion::Aleman).unwrap().a_csv();
+    assert_eq!(csv, "cuota,capital,interes,cargos,total,saldo\n\
+                     1,4000.00,160.00,0.00,4160.00,4000.00\n\
+                     2,4000.00,80.00,0.00,4080.00,0.00\n");
+}
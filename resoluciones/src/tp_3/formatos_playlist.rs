@@ -0,0 +1,359 @@
+// Lectura y escritura de playlists en M3U extendido y PLS. Los dos formatos
+// guardan el titulo como "Artista - Titulo" y la duracion en segundos, con -1
+// para la duracion desconocida (que en Cancion es 0). Al leer se toleran las
+// variantes que aparecen en archivos reales: BOM, fin de linea de Windows,
+// lineas en blanco, claves en cualquier mayuscula y entradas desordenadas.
+//
+// Ningun formato guarda anio, pista ni puntuacion, y PLS tampoco guarda genero
+// ni album: esos datos se pierden al exportar. En M3U el album y el genero van
+// en #EXTALB y #EXTGENRE, y el artista en #EXTART cuando "Artista - Titulo" no
+// alcanza para separarlos (por ejemplo un titulo "A - B" sin artista).
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::ej_8::{Cancion, Genero, Playlist};
+use crate::variantes::ConNombre;
+
+const DURACION_DESCONOCIDA: i64 = -1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorPls {
+    SinSeccionPlaylist,
+}
+
+// lo que las directivas de M3U agregan a la siguiente ubicacion
+#[derive(Debug, Clone, Default)]
+struct Metadatos {
+    artista: Option<String>,
+    album: Option<String>,
+    genero: Option<Genero>,
+}
+
+// las canciones sin ubicacion no se pueden reproducir desde el archivo y se omiten
+pub fn a_m3u(playlist: &Playlist) -> String {
+    let mut m3u = String::from("#EXTM3U\n");
+    let _ = writeln!(m3u, "#PLAYLIST:{}", playlist.nombre());
+    for cancion in playlist.canciones() {
+        if let Some(ubicacion) = cancion.ubicacion() {
+            let titulo = titulo_exportado(cancion);
+            let _ = writeln!(m3u, "#EXTINF:{},{}", duracion_exportada(cancion), titulo);
+            if separar_titulo(&titulo) != (cancion.artista(), cancion.titulo()) {
+                let _ = writeln!(m3u, "#EXTART:{}", cancion.artista());
+            }
+            if let Some(album) = cancion.album() {
+                let _ = writeln!(m3u, "#EXTALB:{}", album);
+            }
+            if cancion.genero() != Genero::Otros {
+                let _ = writeln!(m3u, "#EXTGENRE:{}", cancion.genero());
+            }
+            let _ = writeln!(m3u, "{}", ubicacion);
+        }
+    }
+    m3u
+}
+
+// tambien acepta M3U simple, sin #EXTM3U: solo ubicaciones, una por linea
+pub fn desde_m3u(nombre: String, texto: &str) -> Playlist {
+    let mut nombre = nombre;
+    let mut canciones = Vec::new();
+    let mut pendiente: Option<(u32, String)> = None; // el #EXTINF que espera su ubicacion
+    let mut metadatos = Metadatos::default();
+    for linea in lineas(texto) {
+        if let Some(resto) = linea.strip_prefix("#EXTINF:") {
+            let (cabecera, titulo) = separar_extinf(resto);
+            let duracion = cabecera.split_whitespace().next().unwrap_or("");
+            pendiente = Some((leer_duracion(duracion), titulo.trim().to_string()));
+        } else if let Some(resto) = linea.strip_prefix("#EXTART:") {
+            metadatos.artista = Some(resto.trim().to_string());
+        } else if let Some(resto) = linea.strip_prefix("#EXTALB:") {
+            metadatos.album = Some(resto.trim().to_string()).filter(|album| !album.is_empty());
+        } else if let Some(resto) = linea.strip_prefix("#EXTGENRE:") {
+            metadatos.genero = Genero::desde_nombre(resto).ok();
+        } else if let Some(resto) = linea.strip_prefix("#PLAYLIST:") {
+            if !resto.trim().is_empty() {
+                nombre = resto.trim().to_string();
+            }
+        } else if !linea.starts_with('#') {
+            let (duracion, titulo) = pendiente.take().unwrap_or_default();
+            canciones.push(crear_cancion(&titulo, duracion, linea, std::mem::take(&mut metadatos)));
+        }
+    }
+    let mut playlist = Playlist::new(nombre);
+    for cancion in canciones {
+        playlist.agregar_cancion(cancion);
+    }
+    playlist
+}
+
+pub fn a_pls(playlist: &Playlist) -> String {
+    let mut pls = String::from("[playlist]\n");
+    let mut numero = 0;
+    for cancion in playlist.canciones() {
+        if let Some(ubicacion) = cancion.ubicacion() {
+            numero += 1;
+            let _ = writeln!(pls, "File{}={}", numero, ubicacion);
+            let _ = writeln!(pls, "Title{}={}", numero, titulo_exportado(cancion));
+            let _ = writeln!(pls, "Length{}={}", numero, duracion_exportada(cancion));
+        }
+    }
+    let _ = writeln!(pls, "NumberOfEntries={}", numero);
+    let _ = writeln!(pls, "Version=2");
+    pls
+}
+
+// las entradas se ordenan por su numero y no por la posicion en el archivo;
+// NumberOfEntries no se tiene en cuenta porque muchas veces esta mal
+pub fn desde_pls(nombre: String, texto: &str) -> Result<Playlist, ErrorPls> {
+    let mut en_playlist = false;
+    let mut vista_seccion = false;
+    let mut entradas: BTreeMap<u32, (Option<String>, String, u32)> = BTreeMap::new();
+    for linea in lineas(texto) {
+        if linea.starts_with('[') {
+            en_playlist = linea.eq_ignore_ascii_case("[playlist]");
+            vista_seccion |= en_playlist;
+            continue;
+        }
+        let Some((clave, valor)) = linea.split_once('=').filter(|_| en_playlist) else {
+            continue;
+        };
+        let clave = clave.trim().to_lowercase();
+        let valor = valor.trim();
+        let campo = ["file", "title", "length"].into_iter()
+            .find_map(|campo| clave.strip_prefix(campo).and_then(|numero| numero.parse::<u32>().ok()).map(|numero| (campo, numero)));
+        if let Some((campo, numero)) = campo {
+            let entrada = entradas.entry(numero).or_default();
+            match campo {
+                "file" => entrada.0 = Some(valor.to_string()),
+                "title" => entrada.1 = valor.to_string(),
+                _ => entrada.2 = leer_duracion(valor),
+            }
+        }
+    }
+    if !vista_seccion {
+        return Err(ErrorPls::SinSeccionPlaylist);
+    }
+    let mut playlist = Playlist::new(nombre);
+    // un titulo o duracion sin FileN no tiene nada que reproducir
+    for (ubicacion, titulo, duracion) in entradas.into_values() {
+        if let Some(ubicacion) = ubicacion.filter(|ubicacion| !ubicacion.is_empty()) {
+            playlist.agregar_cancion(crear_cancion(&titulo, duracion, &ubicacion, Metadatos::default()));
+        }
+    }
+    Ok(playlist)
+}
+
+// lineas sin BOM, sin espacios en los bordes y sin las vacias
+fn lineas(texto: &str) -> impl Iterator<Item = &str> {
+    texto.trim_start_matches('\u{feff}').lines().map(str::trim).filter(|linea| !linea.is_empty())
+}
+
+fn duracion_exportada(cancion: &Cancion) -> i64 {
+    if cancion.duracion() == 0 {
+        DURACION_DESCONOCIDA
+    } else {
+        cancion.duracion() as i64
+    }
+}
+
+fn titulo_exportado(cancion: &Cancion) -> String {
+    if cancion.artista().is_empty() {
+        cancion.titulo().to_string()
+    } else {
+        format!("{} - {}", cancion.artista(), cancion.titulo())
+    }
+}
+
+// "duracion atributos,titulo": algunos programas agregan atributos clave="valor"
+// y sus valores pueden tener comas, asi que se corta en la primera coma fuera
+// de comillas
+fn separar_extinf(resto: &str) -> (&str, &str) {
+    let mut entre_comillas = false;
+    for (i, c) in resto.char_indices() {
+        match c {
+            '"' => entre_comillas = !entre_comillas,
+            ',' if !entre_comillas => return (&resto[..i], &resto[i + 1..]),
+            _ => {}
+        }
+    }
+    (resto, "")
+}
+
+// negativa o ilegible es desconocida; con decimales se redondea
+fn leer_duracion(texto: &str) -> u32 {
+    match texto.trim().parse::<f64>() {
+        Ok(segundos) if segundos.is_finite() && segundos > 0.0 => segundos.round() as u32,
+        _ => 0,
+    }
+}
+
+// "Artista - Titulo", o solo el titulo si no hay separador
+fn separar_titulo(titulo: &str) -> (&str, &str) {
+    match titulo.split_once(" - ") {
+        Some((artista, titulo)) => (artista.trim(), titulo.trim()),
+        None => ("", titulo),
+    }
+}
+
+// con el artista explicito solo se le quita el prefijo "Artista - " al titulo;
+// sin titulo se usa el nombre del archivo sin la extension
+fn crear_cancion(titulo: &str, duracion: u32, ubicacion: &str, metadatos: Metadatos) -> Cancion {
+    let (artista, titulo) = match &metadatos.artista {
+        Some(artista) if artista.is_empty() => ("", titulo),
+        Some(artista) => {
+            let sin_artista = titulo.strip_prefix(artista.as_str()).and_then(|resto| resto.strip_prefix(" - "));
+            (artista.as_str(), sin_artista.unwrap_or(titulo).trim())
+        }
+        None => separar_titulo(titulo),
+    };
+    let titulo = if titulo.is_empty() { nombre_de_archivo(ubicacion) } else { titulo };
+    let mut cancion = Cancion::new(titulo.to_string(), artista.to_string(), metadatos.genero.unwrap_or(Genero::Otros))
+        .con_duracion(duracion)
+        .con_ubicacion(ubicacion.to_string());
+    if let Some(album) = metadatos.album {
+        cancion = cancion.con_album(album);
+    }
+    cancion
+}
+
+fn nombre_de_archivo(ubicacion: &str) -> &str {
+    let archivo = ubicacion.rsplit(['/', '\\']).next().unwrap_or(ubicacion);
+    match archivo.rsplit_once('.') {
+        Some((nombre, _)) if !nombre.is_empty() => nombre,
+        _ => archivo,
+    }
+}
+
+
+// lo que una cancion guarda en el archivo ademas de titulo, artista y genero
+#[cfg(test)]
+fn detalle(playlist: &Playlist) -> Vec<(u32, Option<&str>, Option<&str>)> {
+    playlist.canciones().iter().map(|cancion| (cancion.duracion(), cancion.album(), cancion.ubicacion())).collect()
+}
+
+#[test]
+fn test_m3u_ida_y_vuelta() {
+    let mut playlist = Playlist::new("Viaje".to_string());
+    playlist.agregar_cancion(Cancion::new("Zamba de mi esperanza".to_string(), "Mercedes Sosa".to_string(), Genero::Otros)
+        .con_duracion(245).con_ubicacion("musica/zamba.mp3".to_string()));
+    playlist.agregar_cancion(Cancion::new("Radio".to_string(), "".to_string(), Genero::Otros)
+        .con_ubicacion("http://radio.example/stream".to_string()));
+    playlist.agregar_cancion(Cancion::new("Demo - version 2".to_string(), "Banda".to_string(), Genero::Otros)
+        .con_duracion(61).con_ubicacion("C:\\Musica\\demo.flac".to_string()));
+    let m3u = a_m3u(&playlist);
+    assert!(m3u.starts_with("#EXTM3U\n#PLAYLIST:Viaje\n#EXTINF:245,Mercedes Sosa - Zamba de mi esperanza\nmusica/zamba.mp3\n"));
+    assert!(m3u.contains("#EXTINF:-1,Radio\nhttp://radio.example/stream\n"));
+    let leida = desde_m3u("Otra".to_string(), &m3u);
+    assert_eq!(leida, playlist);
+    assert_eq!(detalle(&leida), detalle(&playlist));
+}
+
+#[test]
+fn test_pls_ida_y_vuelta() {
+    let mut playlist = Playlist::new("Viaje".to_string());
+    playlist.agregar_cancion(Cancion::new("Zamba de mi esperanza".to_string(), "Mercedes Sosa".to_string(), Genero::Otros)
+        .con_duracion(245).con_ubicacion("musica/zamba.mp3".to_string()));
+    playlist.agregar_cancion(Cancion::new("Radio".to_string(), "".to_string(), Genero::Otros)
+        .con_ubicacion("http://radio.example/stream".to_string()));
+    playlist.agregar_cancion(Cancion::new("Demo - version 2".to_string(), "Banda".to_string(), Genero::Otros)
+        .con_duracion(61).con_ubicacion("C:\\Musica\\demo.flac".to_string()));
+    let pls = a_pls(&playlist);
+    assert!(pls.starts_with("[playlist]\nFile1=musica/zamba.mp3\nTitle1=Mercedes Sosa - Zamba de mi esperanza\nLength1=245\n"));
+    assert!(pls.ends_with("NumberOfEntries=3\nVersion=2\n"));
+    let leida = desde_pls("Viaje".to_string(), &pls).unwrap();
+    assert_eq!(leida, playlist);
+    assert_eq!(detalle(&leida), detalle(&playlist));
+}
+
+#[test]
+fn test_m3u_tolerante() {
+    let texto = "\u{feff}#EXTM3U\r\n\r\n\
+                 #EXTINF:183.6 tvg-id=\"x\" group-title=\"Rock\",Pappo - Blues local\r\n\
+                 /musica/blues.mp3\r\n\
+                 # un comentario cualquiera\r\n\
+                 /musica/sin datos.ogg\r\n\
+                 #EXTINF:abc,\r\n\
+                 ../otra/tema_final.mp3\r\n";
+    let playlist = desde_m3u("Mezcla".to_string(), texto);
+    assert_eq!(playlist.nombre(), "Mezcla");
+    let canciones = playlist.canciones();
+    assert_eq!(canciones.len(), 3);
+    assert_eq!((canciones[0].artista(), canciones[0].titulo(), canciones[0].duracion()), ("Pappo", "Blues local", 184));
+    assert_eq!((canciones[1].titulo(), canciones[1].duracion()), ("sin datos", 0));
+    assert_eq!((canciones[2].titulo(), canciones[2].ubicacion()), ("tema_final", Some("../otra/tema_final.mp3")));
+
+    // un #EXTINF sin ubicacion se descarta y no afecta a la siguiente entrada
+    let playlist = desde_m3u("Corta".to_string(), "#EXTINF:10,A - B\n#EXTINF:20,C - D\nd.mp3\n");
+    assert_eq!(playlist.canciones().len(), 1);
+    assert_eq!(playlist.canciones()[0].titulo(), "D");
+}
+
+#[test]
+fn test_pls_tolerante() {
+    let texto = "; exportado por otro programa\r\n\
+                 [Playlist]\r\n\
+                 numberofentries=9\r\n\
+                 Title2=Segundo\r\n\
+                 File2=b.mp3\r\n\
+                 FILE1 = a.mp3\r\n\
+                 Length1=-1\r\n\
+                 Title3=Sin archivo\r\n\
+                 [otra]\r\n\
+                 File4=no_es_de_la_playlist.mp3\r\n";
+    let playlist = desde_pls("Lista".to_string(), texto).unwrap();
+    let titulos: Vec<&str> = playlist.canciones().iter().map(|cancion| cancion.titulo()).collect();
+    assert_eq!(titulos, vec!["a", "Segundo"]);
+    assert_eq!(playlist.canciones()[0].duracion(), 0);
+    assert_eq!(desde_pls("Nada".to_string(), "File1=a.mp3\n"), Err(ErrorPls::SinSeccionPlaylist));
+}
+
+#[test]
+fn test_exportar_omite_canciones_sin_ubicacion() {
+    let mut playlist = Playlist::new("Mixta".to_string());
+    playlist.agregar_cancion(Cancion::new("Solo metadatos".to_string(), "Nadie".to_string(), Genero::Pop));
+    playlist.agregar_cancion(Cancion::new("Tema".to_string(), "Alguien".to_string(), Genero::Otros).con_ubicacion("tema.mp3".to_string()));
+    assert_eq!(a_m3u(&playlist), "#EXTM3U\n#PLAYLIST:Mixta\n#EXTINF:-1,Alguien - Tema\ntema.mp3\n");
+    assert_eq!(a_pls(&playlist), "[playlist]\nFile1=tema.mp3\nTitle1=Alguien - Tema\nLength1=-1\nNumberOfEntries=1\nVersion=2\n");
+}
+
+#[test]
+fn test_m3u_conserva_album_genero_y_artista() {
+    let mut playlist = Playlist::new("Discos".to_string());
+    playlist.agregar_cancion(Cancion::new("Yo caminaba".to_string(), "Pappo".to_string(), Genero::Rock)
+        .con_duracion(201).con_album("Pappo's Blues Volumen 3".to_string()).con_ubicacion("pappo/03.mp3".to_string()));
+    playlist.agregar_cancion(Cancion::new("Lado A - Lado B".to_string(), "".to_string(), Genero::Jazz)
+        .con_ubicacion("sin_artista.mp3".to_string()));
+    playlist.agregar_cancion(Cancion::new("Tema".to_string(), "Uno - Dos".to_string(), Genero::Otros)
+        .con_ubicacion("dos.mp3".to_string()));
+    let m3u = a_m3u(&playlist);
+    assert!(m3u.contains("#EXTINF:201,Pappo - Yo caminaba\n#EXTALB:Pappo's Blues Volumen 3\n#EXTGENRE:rock\npappo/03.mp3\n"));
+    assert!(m3u.contains("#EXTINF:-1,Lado A - Lado B\n#EXTART:\n#EXTGENRE:jazz\nsin_artista.mp3\n"));
+    assert!(m3u.contains("#EXTINF:-1,Uno - Dos - Tema\n#EXTART:Uno - Dos\ndos.mp3\n"));
+    let leida = desde_m3u("Discos".to_string(), &m3u);
+    assert_eq!(leida, playlist);
+    assert_eq!(detalle(&leida), detalle(&playlist));
+
+    // anio, pista y puntuacion no se guardan
+    let mut con_anio = Playlist::new("Discos".to_string());
+    con_anio.agregar_cancion(Cancion::new("Zamba".to_string(), "Mercedes".to_string(), Genero::Otros)
+        .con_anio(2009).con_pista(3).con_ubicacion("zamba.mp3".to_string()));
+    let leida = desde_m3u("Discos".to_string(), &a_m3u(&con_anio));
+    assert_eq!((leida.canciones()[0].anio(), leida.canciones()[0].pista()), (None, None));
+
+    // PLS solo guarda titulo y duracion: el titulo sin artista vuelve partido
+    let pls = desde_pls("Discos".to_string(), &a_pls(&playlist)).unwrap();
+    assert_eq!((pls.canciones()[1].artista(), pls.canciones()[1].titulo()), ("Lado A", "Lado B"));
+    assert_eq!(pls.canciones()[0].genero(), Genero::Otros);
+}
+
+#[test]
+fn test_m3u_atributos_con_comas() {
+    let texto = "#EXTM3U\n#EXTINF:120 group-title=\"Rock, Pop\" tvg-name=\"a,b\",Soda Stereo - Persiana americana\nsoda.mp3\n";
+    let playlist = desde_m3u("Radio".to_string(), texto);
+    let cancion = &playlist.canciones()[0];
+    assert_eq!((cancion.artista(), cancion.titulo(), cancion.duracion()), ("Soda Stereo", "Persiana americana", 120));
+
+    // una coma en el titulo no es parte de los atributos
+    let playlist = desde_m3u("Radio".to_string(), "#EXTINF:60,Charly - Hoy, ayer\nc.mp3\n");
+    assert_eq!(playlist.canciones()[0].titulo(), "Hoy, ayer");
+}
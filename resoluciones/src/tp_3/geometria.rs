@@ -0,0 +1,416 @@
+// Figuras ubicadas en el plano. Todas son poligonos convexos, asi que las
+// transformaciones, la pertenencia de puntos y las intersecciones se resuelven
+// una sola vez en el trait Figura a partir de los vertices. Las comparaciones
+// usan una tolerancia proporcional al tamano de las figuras, asi el resultado
+// no cambia si todo se expresa en milimetros o en kilometros.
+
+const TOLERANCIA: f64 = 1e-9;
+
+// una figura sin area (vertices alineados o escalada por 0) contendria
+// cualquier punto y se intersectaria con todo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFigura {
+    Degenerada,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Punto {
+    x: f64,
+    y: f64,
+}
+
+impl Punto {
+    pub fn new(x: f64, y: f64) -> Punto {
+        Punto { x, y }
+    }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn distancia(&self, otro: &Punto) -> f64 {
+        ((self.x - otro.x).powi(2) + (self.y - otro.y).powi(2)).sqrt()
+    }
+}
+
+// producto vectorial de (b - a) x (c - a): positivo si c queda a la izquierda de a->b
+fn producto_cruz(a: &Punto, b: &Punto, c: &Punto) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn area_con_signo(vertices: &[Punto]) -> f64 {
+    let n = vertices.len();
+    let mut suma = 0.0;
+    for i in 0..n {
+        let (p, q) = (&vertices[i], &vertices[(i + 1) % n]);
+        suma += p.x * q.y - q.x * p.y;
+    }
+    suma / 2.0
+}
+
+fn caja_de(vertices: &[Punto]) -> (Punto, Punto) {
+    let mut min = Punto::new(f64::INFINITY, f64::INFINITY);
+    let mut max = Punto::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for v in vertices {
+        min = Punto::new(min.x.min(v.x), min.y.min(v.y));
+        max = Punto::new(max.x.max(v.x), max.y.max(v.y));
+    }
+    (min, max)
+}
+
+// la diagonal de la caja contenedora
+fn tamano(vertices: &[Punto]) -> f64 {
+    let (min, max) = caja_de(vertices);
+    min.distancia(&max)
+}
+
+// un producto vectorial contra el lado a->b vale el largo del lado por la
+// distancia a la recta, asi que la tolerancia escala con los dos
+fn tolerancia(a: &Punto, b: &Punto, tamano: f64) -> f64 {
+    TOLERANCIA * a.distancia(b) * tamano
+}
+
+fn validar(vertices: &[Punto]) -> Result<(), ErrorFigura> {
+    let area = area_con_signo(vertices).abs();
+    if !area.is_finite() || area <= TOLERANCIA * tamano(vertices).powi(2) {
+        return Err(ErrorFigura::Degenerada);
+    }
+    Ok(())
+}
+
+fn en_sentido_antihorario(vertices: &[Punto]) -> Vec<Punto> {
+    let mut resultado = vertices.to_vec();
+    if area_con_signo(&resultado) < 0.0 {
+        resultado.reverse();
+    }
+    resultado
+}
+
+fn proyectar(vertices: &[Punto], eje: (f64, f64)) -> (f64, f64) {
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    for v in vertices {
+        let proyeccion = v.x * eje.0 + v.y * eje.1;
+        min = min.min(proyeccion);
+        max = max.max(proyeccion);
+    }
+    (min, max)
+}
+
+fn interseccion_de_rectas(p: &Punto, q: &Punto, a: &Punto, b: &Punto) -> Punto {
+    let cruz_p = producto_cruz(a, b, p);
+    let cruz_q = producto_cruz(a, b, q);
+    let t = cruz_p / (cruz_p - cruz_q);
+    Punto::new(p.x + (q.x - p.x) * t, p.y + (q.y - p.y) * t)
+}
+
+// recorte de Sutherland-Hodgman: sirve porque el poligono recortador es convexo
+fn recortar(sujeto: &[Punto], recortador: &[Punto]) -> Vec<Punto> {
+    let mut resultado = en_sentido_antihorario(sujeto);
+    let recortador = en_sentido_antihorario(recortador);
+    let tamano = tamano(&recortador);
+    for i in 0..recortador.len() {
+        if resultado.is_empty() {
+            break;
+        }
+        let a = &recortador[i];
+        let b = &recortador[(i + 1) % recortador.len()];
+        let tolerancia = tolerancia(a, b, tamano);
+        let entrada = resultado;
+        resultado = Vec::new();
+        for j in 0..entrada.len() {
+            let actual = &entrada[j];
+            let anterior = &entrada[(j + entrada.len() - 1) % entrada.len()];
+            let actual_adentro = producto_cruz(a, b, actual) >= -tolerancia;
+            let anterior_adentro = producto_cruz(a, b, anterior) >= -tolerancia;
+            if actual_adentro {
+                if !anterior_adentro {
+                    resultado.push(interseccion_de_rectas(anterior, actual, a, b));
+                }
+                resultado.push(*actual);
+            } else if anterior_adentro {
+                resultado.push(interseccion_de_rectas(anterior, actual, a, b));
+            }
+        }
+    }
+    resultado
+}
+
+pub trait Figura {
+    fn vertices(&self) -> &[Punto];
+
+    fn vertices_mut(&mut self) -> &mut [Punto];
+
+    fn trasladar(&mut self, dx: f64, dy: f64) {
+        for v in self.vertices_mut() {
+            v.x += dx;
+            v.y += dy;
+        }
+    }
+
+    // rota en sentido antihorario alrededor de centro
+    fn rotar(&mut self, grados: f64, centro: Punto) {
+        let (seno, coseno) = grados.to_radians().sin_cos();
+        for v in self.vertices_mut() {
+            let (dx, dy) = (v.x - centro.x, v.y - centro.y);
+            v.x = centro.x + dx * coseno - dy * seno;
+            v.y = centro.y + dx * seno + dy * coseno;
+        }
+    }
+
+    // si la figura quedaria sin area no se modifica
+    fn escalar(&mut self, factor: f64, centro: Punto) -> Result<(), ErrorFigura> {
+        let escalados: Vec<Punto> = self.vertices().iter()
+            .map(|v| Punto::new(centro.x + (v.x - centro.x) * factor, centro.y + (v.y - centro.y) * factor))
+            .collect();
+        validar(&escalados)?;
+        self.vertices_mut().copy_from_slice(&escalados);
+        Ok(())
+    }
+
+    fn centro(&self) -> Punto {
+        let vertices = self.vertices();
+        let n = vertices.len() as f64;
+        let x = vertices.iter().map(|v| v.x).sum::<f64>() / n;
+        let y = vertices.iter().map(|v| v.y).sum::<f64>() / n;
+        Punto::new(x, y)
+    }
+
+    // esquinas inferior izquierda y superior derecha de la caja alineada a los ejes
+    fn caja_contenedora(&self) -> (Punto, Punto) {
+        caja_de(self.vertices())
+    }
+
+    fn area(&self) -> f64 {
+        area_con_signo(self.vertices()).abs()
+    }
+
+    fn perimetro(&self) -> f64 {
+        let vertices = self.vertices();
+        let n = vertices.len();
+        (0..n).map(|i| vertices[i].distancia(&vertices[(i + 1) % n])).sum()
+    }
+
+    // los puntos sobre el borde se consideran dentro de la figura
+    fn contiene_punto(&self, punto: &Punto) -> bool {
+        let vertices = en_sentido_antihorario(self.vertices());
+        let tamano = tamano(&vertices);
+        let n = vertices.len();
+        (0..n).all(|i| {
+            let (a, b) = (&vertices[i], &vertices[(i + 1) % n]);
+            producto_cruz(a, b, punto) >= -tolerancia(a, b, tamano)
+        })
+    }
+
+    // teorema del eje separador: dos poligonos convexos no se tocan si y solo si
+    // existe un eje, normal a alguno de sus lados, donde sus proyecciones no se solapan
+    fn se_intersecta_con(&self, otra: &dyn Figura) -> bool {
+        let (propios, ajenos) = (self.vertices(), otra.vertices());
+        let tamano = tamano(propios).max(tamano(ajenos));
+        for vertices in [propios, ajenos] {
+            let n = vertices.len();
+            for i in 0..n {
+                let (p, q) = (&vertices[i], &vertices[(i + 1) % n]);
+                let eje = (q.y - p.y, p.x - q.x);
+                let tolerancia = tolerancia(p, q, tamano);
+                let (min_a, max_a) = proyectar(propios, eje);
+                let (min_b, max_b) = proyectar(ajenos, eje);
+                if max_a < min_b - tolerancia || max_b < min_a - tolerancia {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn area_interseccion(&self, otra: &dyn Figura) -> f64 {
+        let comun = recortar(self.vertices(), otra.vertices());
+        if comun.len() < 3 {
+            return 0.0;
+        }
+        area_con_signo(&comun).abs()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RectanguloPlano {
+    vertices: [Punto; 4],
+}
+
+impl RectanguloPlano {
+    // origen es la esquina inferior izquierda; la longitud se mide sobre el eje x
+    pub fn new(origen: Punto, longitud: f64, ancho: f64) -> Result<RectanguloPlano, ErrorFigura> {
+        let vertices = [
+            origen,
+            Punto::new(origen.x + longitud, origen.y),
+            Punto::new(origen.x + longitud, origen.y + ancho),
+            Punto::new(origen.x, origen.y + ancho),
+        ];
+        validar(&vertices)?;
+        Ok(RectanguloPlano { vertices })
+    }
+}
+
+impl Figura for RectanguloPlano {
+    fn vertices(&self) -> &[Punto] {
+        &self.vertices
+    }
+
+    fn vertices_mut(&mut self) -> &mut [Punto] {
+        &mut self.vertices
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrianguloPlano {
+    vertices: [Punto; 3],
+}
+
+impl TrianguloPlano {
+    pub fn new(a: Punto, b: Punto, c: Punto) -> Result<TrianguloPlano, ErrorFigura> {
+        let vertices = [a, b, c];
+        validar(&vertices)?;
+        Ok(TrianguloPlano { vertices })
+    }
+}
+
+impl Figura for TrianguloPlano {
+    fn vertices(&self) -> &[Punto] {
+        &self.vertices
+    }
+
+    fn vertices_mut(&mut self) -> &mut [Punto] {
+        &mut self.vertices
+    }
+}
+
+
+#[cfg(test)]
+fn puntos_cercanos(p: &Punto, q: &Punto) -> bool {
+    p.distancia(q) < 0.000001
+}
+
+#[test]
+fn test_trasladar_rotar_escalar() {
+    let mut rectangulo = RectanguloPlano::new(Punto::new(0.0, 0.0), 4.0, 2.0).unwrap();
+    rectangulo.trasladar(1.0, 1.0);
+    assert_eq!(rectangulo.vertices()[0], Punto::new(1.0, 1.0));
+    assert_eq!(rectangulo.vertices()[2], Punto::new(5.0, 3.0));
+
+    rectangulo.rotar(90.0, Punto::new(1.0, 1.0));
+    assert!(puntos_cercanos(&rectangulo.vertices()[1], &Punto::new(1.0, 5.0)));
+    assert!(puntos_cercanos(&rectangulo.vertices()[2], &Punto::new(-1.0, 5.0)));
+    assert!((rectangulo.area() - 8.0).abs() < 0.000001);
+
+    let centro = rectangulo.centro();
+    rectangulo.escalar(2.0, centro).unwrap();
+    assert!((rectangulo.area() - 32.0).abs() < 0.000001);
+    assert!((rectangulo.perimetro() - 24.0).abs() < 0.000001);
+    assert!(puntos_cercanos(&rectangulo.centro(), &centro));
+}
+
+#[test]
+fn test_contiene_punto() {
+    let triangulo = TrianguloPlano::new(Punto::new(0.0, 0.0), Punto::new(4.0, 0.0), Punto::new(0.0, 4.0)).unwrap();
+    assert!(triangulo.contiene_punto(&Punto::new(1.0, 1.0)));
+    assert!(triangulo.contiene_punto(&Punto::new(2.0, 2.0))); // sobre la hipotenusa
+    assert!(!triangulo.contiene_punto(&Punto::new(3.0, 3.0)));
+
+    // el resultado no depende del orden en que se dieron los vertices
+    let invertido = TrianguloPlano::new(Punto::new(0.0, 4.0), Punto::new(4.0, 0.0), Punto::new(0.0, 0.0)).unwrap();
+    assert!(invertido.contiene_punto(&Punto::new(1.0, 1.0)));
+
+    let mut rectangulo = RectanguloPlano::new(Punto::new(0.0, 0.0), 2.0, 2.0).unwrap();
+    rectangulo.rotar(45.0, Punto::new(1.0, 1.0));
+    assert!(rectangulo.contiene_punto(&Punto::new(1.0, 2.3)));
+    assert!(!rectangulo.contiene_punto(&Punto::new(0.1, 0.1)));
+}
+
+#[test]
+fn test_interseccion_rectangulos() {
+    let a = RectanguloPlano::new(Punto::new(0.0, 0.0), 4.0, 4.0).unwrap();
+    let b = RectanguloPlano::new(Punto::new(2.0, 2.0), 4.0, 4.0).unwrap();
+    let c = RectanguloPlano::new(Punto::new(10.0, 0.0), 1.0, 1.0).unwrap();
+    let contiguo = RectanguloPlano::new(Punto::new(4.0, 0.0), 1.0, 1.0).unwrap();
+
+    assert!(a.se_intersecta_con(&b));
+    assert!((a.area_interseccion(&b) - 4.0).abs() < 0.000001);
+    assert!(!a.se_intersecta_con(&c));
+    assert_eq!(a.area_interseccion(&c), 0.0);
+    // comparten un lado: se tocan pero no se superponen
+    assert!(a.se_intersecta_con(&contiguo));
+    assert!(a.area_interseccion(&contiguo).abs() < 0.000001);
+}
+
+#[test]
+fn test_interseccion_rectangulo_rotado() {
+    let a = RectanguloPlano::new(Punto::new(0.0, 0.0), 2.0, 2.0).unwrap();
+    let mut b = RectanguloPlano::new(Punto::new(0.0, 0.0), 2.0, 2.0).unwrap();
+    b.rotar(45.0, Punto::new(1.0, 1.0));
+    // un cuadrado y el mismo rotado 45 grados comparten un octogono regular
+    let esperado = 8.0 * (2.0_f64.sqrt() - 1.0);
+    assert!((a.area_interseccion(&b) - esperado).abs() < 0.000001);
+
+    // las cajas contenedoras se solapan pero las figuras no
+    let mut rombo = RectanguloPlano::new(Punto::new(0.0, 0.0), 1.0, 1.0).unwrap();
+    rombo.rotar(45.0, Punto::new(0.0, 0.0));
+    rombo.trasladar(2.6, 2.6 - 0.5_f64.sqrt());
+    assert!(!a.se_intersecta_con(&rombo));
+}
+
+#[test]
+fn test_interseccion_triangulos() {
+    let a = TrianguloPlano::new(Punto::new(0.0, 0.0), Punto::new(4.0, 0.0), Punto::new(0.0, 4.0)).unwrap();
+    let b = TrianguloPlano::new(Punto::new(4.0, 4.0), Punto::new(0.0, 4.0), Punto::new(4.0, 0.0)).unwrap();
+    let c = TrianguloPlano::new(Punto::new(1.0, 1.0), Punto::new(5.0, 1.0), Punto::new(1.0, 5.0)).unwrap();
+    let d = TrianguloPlano::new(Punto::new(3.0, 3.0), Punto::new(6.0, 3.0), Punto::new(3.0, 6.0)).unwrap();
+
+    assert!(a.se_intersecta_con(&b));
+    assert!(a.area_interseccion(&b).abs() < 0.000001);
+    assert!(a.se_intersecta_con(&c));
+    assert!((a.area_interseccion(&c) - 2.0).abs() < 0.000001);
+    assert!(!a.se_intersecta_con(&d));
+
+    let rectangulo = RectanguloPlano::new(Punto::new(0.0, 0.0), 2.0, 2.0).unwrap();
+    assert!((a.area_interseccion(&rectangulo) - 4.0).abs() < 0.000001);
+}
+
+#[test]
+fn test_figuras_sin_area() {
+    let alineados = TrianguloPlano::new(Punto::new(0.0, 0.0), Punto::new(1.0, 1.0), Punto::new(3.0, 3.0));
+    assert_eq!(alineados, Err(ErrorFigura::Degenerada));
+    assert_eq!(RectanguloPlano::new(Punto::new(0.0, 0.0), 5.0, 0.0), Err(ErrorFigura::Degenerada));
+    assert_eq!(RectanguloPlano::new(Punto::new(0.0, 0.0), f64::NAN, 1.0), Err(ErrorFigura::Degenerada));
+
+    let mut rectangulo = RectanguloPlano::new(Punto::new(0.0, 0.0), 2.0, 2.0).unwrap();
+    assert_eq!(rectangulo.escalar(0.0, Punto::new(1.0, 1.0)), Err(ErrorFigura::Degenerada));
+    assert_eq!(rectangulo.area(), 4.0);
+    assert!(!rectangulo.contiene_punto(&Punto::new(5.0, 5.0)));
+}
+
+#[test]
+fn test_tolerancia_proporcional_al_tamano() {
+    // en coordenadas muy chicas un punto afuera sigue estando afuera
+    let chico = TrianguloPlano::new(Punto::new(0.0, 0.0), Punto::new(1e-6, 0.0), Punto::new(0.0, 1e-6)).unwrap();
+    assert!(chico.contiene_punto(&Punto::new(2e-7, 2e-7)));
+    assert!(!chico.contiene_punto(&Punto::new(6e-7, 6e-7)));
+    let otro = TrianguloPlano::new(Punto::new(1.1e-6, 0.0), Punto::new(2e-6, 0.0), Punto::new(1.1e-6, 1e-6)).unwrap();
+    assert!(!chico.se_intersecta_con(&otro));
+    // lejos del origen la distancia entre figuras chicas tampoco se pierde
+    let mut lejano = otro.clone();
+    lejano.trasladar(1e6, 1e6);
+    let mut cerca_del_lejano = chico.clone();
+    cerca_del_lejano.trasladar(1e6, 1e6);
+    assert!(!cerca_del_lejano.se_intersecta_con(&lejano));
+
+    // y en coordenadas enormes el error de redondeo no deja afuera al borde
+    let grande = RectanguloPlano::new(Punto::new(0.0, 0.0), 3e9, 3e9).unwrap();
+    let mut rotado = grande.clone();
+    rotado.rotar(90.0, Punto::new(1.5e9, 1.5e9));
+    assert!((grande.area_interseccion(&rotado) - grande.area()).abs() / grande.area() < 1e-9);
+    assert!(rotado.contiene_punto(&Punto::new(3e9, 3e9)));
+}
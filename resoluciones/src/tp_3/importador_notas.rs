@@ -0,0 +1,343 @@
+// Importacion de notas desde CSV con filas legajo,nombre,materia,nota[,fecha].
+// Las filas mal formadas se informan con su numero de linea y no cortan la
+// importacion. Cada fila ocupa una sola linea: no hay saltos de linea entre comillas.
+// Solo una fila con fecha identifica a un examen: dos filas iguales sin fecha
+// pueden ser dos examenes distintos (por ejemplo dos trabajos practicos con 70),
+// asi que nunca se descartan como duplicadas.
+
+use std::collections::BTreeMap;
+
+use super::ej_3::{ErrorFecha, Fecha};
+use super::ej_6::{Curso, ErrorCurso, Estudiante, Examen};
+#[cfg(test)]
+use super::ej_6::{ErrorEscala, Escala};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFila {
+    CantidadDeCampos(usize),
+    ComillasSinCerrar,
+    LegajoInvalido,
+    CampoVacio,
+    NotaInvalida,
+    Fecha(ErrorFecha),
+    NombreDistinto, // el legajo ya existe con otro nombre
+    Curso(ErrorCurso),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorImportacion {
+    linea: usize,
+    error: ErrorFila,
+}
+
+impl ErrorImportacion {
+    pub fn linea(&self) -> usize {
+        self.linea
+    }
+
+    pub fn error(&self) -> ErrorFila {
+        self.error
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ResumenImportacion {
+    agregados: usize,
+    duplicados: usize, // examenes con fecha que ya estaban cargados y se ignoraron
+    ambiguas: Vec<usize>, // lineas sin fecha iguales a un examen que ya estaba
+    errores: Vec<ErrorImportacion>,
+}
+
+impl ResumenImportacion {
+    pub fn agregados(&self) -> usize {
+        self.agregados
+    }
+
+    pub fn duplicados(&self) -> usize {
+        self.duplicados
+    }
+
+    // estas filas no se cargan: pueden ser el mismo examen importado otra vez o
+    // uno nuevo con la misma nota, y sin fecha no hay forma de saberlo
+    pub fn ambiguas(&self) -> &[usize] {
+        &self.ambiguas
+    }
+
+    pub fn errores(&self) -> &[ErrorImportacion] {
+        &self.errores
+    }
+
+    fn registrar_error(&mut self, linea: usize, error: ErrorFila) {
+        self.errores.push(ErrorImportacion { linea, error });
+    }
+
+    // los errores de formato se detectan antes que los de agrupacion
+    fn ordenar_errores(&mut self) {
+        self.errores.sort_by_key(|error| error.linea);
+    }
+}
+
+struct FilaNota {
+    linea: usize,
+    legajo: u32,
+    nombre: String,
+    examen: Examen,
+}
+
+// agrupa las filas en estudiantes, ordenados por legajo
+pub fn importar(texto: &str) -> (Vec<Estudiante>, ResumenImportacion) {
+    let mut resumen = ResumenImportacion::default();
+    let mut estudiantes: BTreeMap<u32, Estudiante> = BTreeMap::new();
+    for fila in leer_filas(texto, &mut resumen) {
+        let estudiante = estudiantes.entry(fila.legajo)
+            .or_insert_with(|| Estudiante::new(fila.nombre.clone(), fila.legajo, Vec::new()));
+        if estudiante.nombre() != fila.nombre {
+            resumen.registrar_error(fila.linea, ErrorFila::NombreDistinto);
+        } else if fila.examen.fecha().is_some() && estudiante.calificaciones().contains(&fila.examen) {
+            resumen.duplicados += 1;
+        } else {
+            estudiante.agregar_examen(fila.examen);
+            resumen.agregados += 1;
+        }
+    }
+    resumen.ordenar_errores();
+    (estudiantes.into_values().collect(), resumen)
+}
+
+// suma las notas a un curso existente: crea los estudiantes y materias que
+// falten y no vuelve a cargar examenes que ya estan
+pub fn fusionar_en(texto: &str, curso: &mut Curso) -> ResumenImportacion {
+    let mut resumen = ResumenImportacion::default();
+    // las filas ya procesadas y si se cargaron: lo cargado en esta importacion
+    // no cuenta como "ya estaba"
+    let mut procesadas: Vec<(u32, Examen, bool)> = Vec::new();
+    for fila in leer_filas(texto, &mut resumen) {
+        if let Err(error) = fusionar_fila(&fila, curso, &mut procesadas, &mut resumen) {
+            resumen.registrar_error(fila.linea, error);
+        }
+    }
+    resumen.ordenar_errores();
+    resumen
+}
+
+fn fusionar_fila(fila: &FilaNota, curso: &mut Curso, procesadas: &mut Vec<(u32, Examen, bool)>, resumen: &mut ResumenImportacion) -> Result<(), ErrorFila> {
+    // se valida antes de tocar el curso para no dejar inscripciones a medias
+    curso.escala().validar(fila.examen.nota()).map_err(|error| ErrorFila::Curso(ErrorCurso::NotaInvalida(error)))?;
+    match curso.obtener_estudiante(fila.legajo) {
+        Some(estudiante) if estudiante.nombre() != fila.nombre => return Err(ErrorFila::NombreDistinto),
+        Some(estudiante) if fila.examen.fecha().is_some() && estudiante.calificaciones().contains(&fila.examen) => {
+            resumen.duplicados += 1;
+            return Ok(());
+        }
+        Some(estudiante) => {
+            // cada examen igual que ya estaba puede corresponder a una sola fila
+            // del archivo; las filas que sobran son examenes nuevos
+            let iguales: Vec<bool> = procesadas.iter()
+                .filter(|(legajo, examen, _)| *legajo == fila.legajo && *examen == fila.examen)
+                .map(|(_, _, cargada)| *cargada)
+                .collect();
+            let cargadas_ahora = iguales.iter().filter(|cargada| **cargada).count();
+            let ya_estaban = estudiante.calificaciones().iter().filter(|examen| **examen == fila.examen).count() - cargadas_ahora;
+            if iguales.len() < ya_estaban {
+                resumen.ambiguas.push(fila.linea);
+                procesadas.push((fila.legajo, fila.examen.clone(), false));
+                return Ok(());
+            }
+        }
+        None => curso.inscribir_estudiante(Estudiante::new(fila.nombre.clone(), fila.legajo, Vec::new())).map_err(ErrorFila::Curso)?,
+    }
+    curso.agregar_materia(fila.examen.materia());
+    curso.inscribir_en_materia(fila.legajo, fila.examen.materia()).map_err(ErrorFila::Curso)?;
+    curso.cargar_examen(fila.legajo, fila.examen.clone()).map_err(ErrorFila::Curso)?;
+    procesadas.push((fila.legajo, fila.examen.clone(), true));
+    resumen.agregados += 1;
+    Ok(())
+}
+
+fn leer_filas(texto: &str, resumen: &mut ResumenImportacion) -> Vec<FilaNota> {
+    let mut filas = Vec::new();
+    for (i, linea) in texto.lines().enumerate() {
+        let numero = i + 1;
+        if linea.trim().is_empty() || (numero == 1 && es_encabezado(linea)) {
+            continue;
+        }
+        match leer_fila(numero, linea) {
+            Ok(fila) => filas.push(fila),
+            Err(error) => resumen.registrar_error(numero, error),
+        }
+    }
+    filas
+}
+
+fn es_encabezado(linea: &str) -> bool {
+    let primero = linea.split(',').next().unwrap_or("").trim().to_lowercase();
+    primero == "student_id" || primero == "legajo"
+}
+
+fn leer_fila(linea: usize, texto: &str) -> Result<FilaNota, ErrorFila> {
+    let campos = separar_campos(texto)?;
+    if campos.len() != 4 && campos.len() != 5 {
+        return Err(ErrorFila::CantidadDeCampos(campos.len()));
+    }
+    let legajo = campos[0].parse().map_err(|_| ErrorFila::LegajoInvalido)?;
+    if campos[1].is_empty() || campos[2].is_empty() {
+        return Err(ErrorFila::CampoVacio);
+    }
+    let nota: f64 = campos[3].parse().map_err(|_| ErrorFila::NotaInvalida)?;
+    if !nota.is_finite() {
+        return Err(ErrorFila::NotaInvalida);
+    }
+    let mut examen = Examen::new(campos[2].clone(), nota);
+    if let Some(fecha) = campos.get(4).filter(|fecha| !fecha.is_empty()) {
+        examen = examen.con_fecha(fecha.parse::<Fecha>().map_err(ErrorFila::Fecha)?);
+    }
+    Ok(FilaNota { linea, legajo, nombre: campos[1].clone(), examen })
+}
+
+// separa por comas respetando los campos entre comillas; "" dentro de comillas es una comilla
+fn separar_campos(linea: &str) -> Result<Vec<String>, ErrorFila> {
+    let mut campos = Vec::new();
+    let mut actual = String::new();
+    let mut entre_comillas = false;
+    let mut caracteres = linea.chars().peekable();
+    while let Some(c) = caracteres.next() {
+        match c {
+            '"' if entre_comillas => {
+                if caracteres.peek() == Some(&'"') {
+                    caracteres.next();
+                    actual.push('"');
+                } else {
+                    entre_comillas = false;
+                }
+            }
+            '"' if actual.trim().is_empty() => {
+                actual.clear();
+                entre_comillas = true;
+            }
+            ',' if !entre_comillas => {
+                campos.push(actual.trim().to_string());
+                actual.clear();
+            }
+            _ => actual.push(c),
+        }
+    }
+    if entre_comillas {
+        return Err(ErrorFila::ComillasSinCerrar);
+    }
+    campos.push(actual.trim().to_string());
+    Ok(campos)
+}
+
+
+#[test]
+fn test_separar_campos() {
+    assert_eq!(separar_campos("1, Juan ,Historia,70"), Ok(vec!["1".to_string(), "Juan".to_string(), "Historia".to_string(), "70".to_string()]));
+    assert_eq!(separar_campos("2,\"Perez, Ana\",\"Dijo \"\"hola\"\"\",80"),
+        Ok(vec!["2".to_string(), "Perez, Ana".to_string(), "Dijo \"hola\"".to_string(), "80".to_string()]));
+    assert_eq!(separar_campos("3,\"Sin cerrar,Historia,80"), Err(ErrorFila::ComillasSinCerrar));
+}
+
+#[test]
+fn test_importar_agrupa_por_estudiante() {
+    let csv = "student_id,nombre,materia,nota,fecha\n\
+               2,\"Perez, Ana\",Historia,75,10/04/2024\n\
+               1,Juan,Matemáticas,60\n\
+               \n\
+               2,\"Perez, Ana\",Matemáticas,90,05/06/2024\n\
+               2,\"Perez, Ana\",Historia,75,10/04/2024\n";
+    let (estudiantes, resumen) = importar(csv);
+    assert_eq!(estudiantes.len(), 2);
+    assert_eq!(estudiantes[0].id(), 1);
+    assert_eq!(estudiantes[1].nombre(), "Perez, Ana");
+    assert_eq!(estudiantes[1].calificaciones().len(), 2);
+    assert_eq!(estudiantes[1].calificaciones()[0].fecha(), Some(&Fecha::new(10, 4, 2024)));
+    assert_eq!(resumen.agregados(), 3);
+    assert_eq!(resumen.duplicados(), 1);
+    assert!(resumen.errores().is_empty());
+}
+
+#[test]
+fn test_importar_informa_filas_mal_formadas() {
+    let csv = "1,Juan,Historia,70\n\
+               x,Juan,Historia,70\n\
+               1,Juan,Historia\n\
+               1,Juan,Historia,setenta\n\
+               1,Juan,Historia,70,31/02/2024\n\
+               1,Pedro,Historia,80\n\
+               1,,Historia,80\n\
+               1,Juan,\"Historia,80\n\
+               1,Juan,Matemáticas,NaN\n\
+               1,Juan,Matemáticas,85,01/07/2024\n";
+    let (estudiantes, resumen) = importar(csv);
+    assert_eq!(estudiantes.len(), 1);
+    assert_eq!(estudiantes[0].calificaciones().len(), 2);
+    let errores: Vec<(usize, ErrorFila)> = resumen.errores().iter().map(|e| (e.linea(), e.error())).collect();
+    assert_eq!(errores, vec![
+        (2, ErrorFila::LegajoInvalido),
+        (3, ErrorFila::CantidadDeCampos(3)),
+        (4, ErrorFila::NotaInvalida),
+        (5, ErrorFila::Fecha(ErrorFecha::FechaInexistente)),
+        (6, ErrorFila::NombreDistinto),
+        (7, ErrorFila::CampoVacio),
+        (8, ErrorFila::ComillasSinCerrar),
+        (9, ErrorFila::NotaInvalida),
+    ]);
+}
+
+#[test]
+fn test_fusionar_en_curso() {
+    let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal());
+    curso.agregar_materia("Historia");
+    curso.inscribir_estudiante(Estudiante::new("Juan".to_string(), 1, Vec::new())).unwrap();
+    curso.inscribir_en_materia(1, "Historia").unwrap();
+    curso.cargar_nota(1, "Historia", 70.0).unwrap();
+
+    let csv = "1,Juan,Historia,70\n\
+               1,Juan,Química,55,02/05/2024\n\
+               2,Ana,Historia,88\n\
+               3,Pedro,Historia,120\n\
+               2,Anita,Historia,90\n";
+    let resumen = fusionar_en(csv, &mut curso);
+    assert_eq!(resumen.agregados(), 2);
+    // el 70 de Historia no tiene fecha: puede ser el mismo examen u otro
+    assert_eq!(resumen.duplicados(), 0);
+    assert_eq!(resumen.ambiguas(), &[1]);
+    let errores: Vec<(usize, ErrorFila)> = resumen.errores().iter().map(|e| (e.linea(), e.error())).collect();
+    assert_eq!(errores, vec![
+        (4, ErrorFila::Curso(ErrorCurso::NotaInvalida(ErrorEscala::NotaFueraDeRango))),
+        (5, ErrorFila::NombreDistinto),
+    ]);
+    assert_eq!(curso.obtener_estudiante(1).unwrap().calificaciones().len(), 2);
+    assert_eq!(curso.obtener_estudiante(2).unwrap().nombre(), "Ana");
+    // la fila invalida no dejo al estudiante cargado a medias
+    assert!(curso.obtener_estudiante(3).is_none());
+
+    // volver a importar el mismo archivo no duplica nada
+    let resumen = fusionar_en(csv, &mut curso);
+    assert_eq!(resumen.agregados(), 0);
+    assert_eq!(resumen.duplicados(), 1);
+    assert_eq!(resumen.ambiguas(), &[1, 3]);
+}
+
+#[test]
+fn test_filas_iguales_sin_fecha() {
+    // dos trabajos practicos con la misma nota son dos examenes
+    let csv = "1,Juan,Historia,70
+               1,Juan,Historia,70
+";
+    let (estudiantes, resumen) = importar(csv);
+    assert_eq!(estudiantes[0].calificaciones().len(), 2);
+    assert_eq!(resumen.agregados(), 2);
+    assert_eq!(resumen.duplicados(), 0);
+
+    let mut curso = Curso::new("Seminario".to_string(), Escala::centesimal());
+    curso.agregar_materia("Historia");
+    curso.inscribir_estudiante(Estudiante::new("Juan".to_string(), 1, Vec::new())).unwrap();
+    curso.inscribir_en_materia(1, "Historia").unwrap();
+    curso.cargar_nota(1, "Historia", 70.0).unwrap();
+    let resumen = fusionar_en(csv, &mut curso);
+    // la primera puede ser la que ya estaba; la segunda seguro es otra
+    assert_eq!(resumen.ambiguas(), &[1]);
+    assert_eq!(resumen.agregados(), 1);
+    assert_eq!(curso.obtener_estudiante(1).unwrap().calificaciones().len(), 2);
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unidad {
+    Milimetro,
+    Centimetro,
+    Metro,
+    Kilometro,
+}
+
+impl Unidad {
+    pub fn en_milimetros(&self) -> u64 {
+        match self {
+            Unidad::Milimetro => 1,
+            Unidad::Centimetro => 10,
+            Unidad::Metro => 1_000,
+            Unidad::Kilometro => 1_000_000,
+        }
+    }
+
+    fn simbolo(&self) -> &str {
+        match self {
+            Unidad::Milimetro => "mm",
+            Unidad::Centimetro => "cm",
+            Unidad::Metro => "m",
+            Unidad::Kilometro => "km",
+        }
+    }
+
+    // cuantas unidades de destino hay en una unidad de self (puede ser fraccionario)
+    pub fn factor_a(&self, destino: Unidad) -> f64 {
+        self.en_milimetros() as f64 / destino.en_milimetros() as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorMedida {
+    Desbordamiento,
+    ConversionInexacta, // por ejemplo 1500 mm a metros
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Medida {
+    valor: u64,
+    unidad: Unidad,
+}
+
+impl Medida {
+    pub fn new(valor: u64, unidad: Unidad) -> Medida {
+        Medida { valor, unidad }
+    }
+
+    pub fn valor(&self) -> u64 {
+        self.valor
+    }
+
+    pub fn unidad(&self) -> Unidad {
+        self.unidad
+    }
+
+    pub fn convertir_a(&self, unidad: Unidad) -> Result<Medida, ErrorMedida> {
+        let valor = convertir_valor(self.valor, self.unidad.en_milimetros(), unidad.en_milimetros())?;
+        Ok(Medida::new(valor, unidad))
+    }
+
+    pub fn a_f64_en(&self, unidad: Unidad) -> f64 {
+        self.valor as f64 * self.unidad.factor_a(unidad)
+    }
+
+    pub fn sumar(&self, otra: &Medida) -> Result<Medida, ErrorMedida> {
+        let unidad = self.unidad.min(otra.unidad);
+        let a = self.convertir_a(unidad)?;
+        let b = otra.convertir_a(unidad)?;
+        let valor = a.valor.checked_add(b.valor).ok_or(ErrorMedida::Desbordamiento)?;
+        Ok(Medida::new(valor, unidad))
+    }
+
+    pub fn multiplicar_por(&self, factor: u64) -> Result<Medida, ErrorMedida> {
+        let valor = self.valor.checked_mul(factor).ok_or(ErrorMedida::Desbordamiento)?;
+        Ok(Medida::new(valor, self.unidad))
+    }
+
+    pub fn multiplicar(&self, otra: &Medida) -> Result<Superficie, ErrorMedida> {
+        let unidad = self.unidad.min(otra.unidad);
+        let a = self.convertir_a(unidad)?;
+        let b = otra.convertir_a(unidad)?;
+        let valor = a.valor.checked_mul(b.valor).ok_or(ErrorMedida::Desbordamiento)?;
+        Ok(Superficie::new(valor, unidad))
+    }
+}
+
+impl fmt::Display for Medida {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.valor, self.unidad.simbolo())
+    }
+}
+
+// superficie expresada en unidades cuadradas de la unidad indicada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Superficie {
+    valor: u64,
+    unidad: Unidad,
+}
+
+impl Superficie {
+    pub fn new(valor: u64, unidad: Unidad) -> Superficie {
+        Superficie { valor, unidad }
+    }
+
+    pub fn convertir_a(&self, unidad: Unidad) -> Result<Superficie, ErrorMedida> {
+        let origen = self.unidad.en_milimetros().pow(2);
+        let destino = unidad.en_milimetros().pow(2);
+        Ok(Superficie::new(convertir_valor(self.valor, origen, destino)?, unidad))
+    }
+}
+
+impl fmt::Display for Superficie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}²", self.valor, self.unidad.simbolo())
+    }
+}
+
+fn convertir_valor(valor: u64, origen: u64, destino: u64) -> Result<u64, ErrorMedida> {
+    if origen >= destino {
+        valor.checked_mul(origen / destino).ok_or(ErrorMedida::Desbordamiento)
+    } else if valor.is_multiple_of(destino / origen) {
+        Ok(valor / (destino / origen))
+    } else {
+        Err(ErrorMedida::ConversionInexacta)
+    }
+}
+
+
+#[test]
+fn test_convertir_medidas() {
+    let medida = Medida::new(3, Unidad::Kilometro);
+    assert_eq!(medida.convertir_a(Unidad::Metro), Ok(Medida::new(3_000, Unidad::Metro)));
+    assert_eq!(medida.convertir_a(Unidad::Milimetro), Ok(Medida::new(3_000_000, Unidad::Milimetro)));
+    assert_eq!(Medida::new(2_500, Unidad::Centimetro).convertir_a(Unidad::Metro), Ok(Medida::new(25, Unidad::Metro)));
+    assert_eq!(Medida::new(1_500, Unidad::Milimetro).convertir_a(Unidad::Metro), Err(ErrorMedida::ConversionInexacta));
+    assert_eq!(Medida::new(u64::MAX, Unidad::Metro).convertir_a(Unidad::Centimetro), Err(ErrorMedida::Desbordamiento));
+    assert_eq!(Medida::new(1_500, Unidad::Milimetro).a_f64_en(Unidad::Metro), 1.5);
+}
+
+#[test]
+fn test_operaciones_medidas() {
+    let a = Medida::new(2, Unidad::Metro);
+    let b = Medida::new(50, Unidad::Centimetro);
+    assert_eq!(a.sumar(&b), Ok(Medida::new(250, Unidad::Centimetro)));
+    assert_eq!(a.multiplicar(&b), Ok(Superficie::new(10_000, Unidad::Centimetro)));
+    assert_eq!(a.multiplicar_por(3), Ok(Medida::new(6, Unidad::Metro)));
+    assert_eq!(Medida::new(u64::MAX, Unidad::Metro).sumar(&a), Err(ErrorMedida::Desbordamiento));
+    assert_eq!(Medida::new(u64::MAX, Unidad::Metro).multiplicar_por(2), Err(ErrorMedida::Desbordamiento));
+    assert_eq!(Medida::new(1 << 32, Unidad::Metro).multiplicar(&Medida::new(1 << 32, Unidad::Metro)), Err(ErrorMedida::Desbordamiento));
+}
+
+#[test]
+fn test_superficies() {
+    let superficie = Superficie::new(3, Unidad::Metro);
+    assert_eq!(superficie.convertir_a(Unidad::Centimetro), Ok(Superficie::new(30_000, Unidad::Centimetro)));
+    assert_eq!(Superficie::new(20_000, Unidad::Centimetro).convertir_a(Unidad::Metro), Ok(Superficie::new(2, Unidad::Metro)));
+    assert_eq!(Superficie::new(5_000, Unidad::Centimetro).convertir_a(Unidad::Metro), Err(ErrorMedida::ConversionInexacta));
+    assert_eq!(superficie.to_string(), "3 m²");
+    assert_eq!(Medida::new(7, Unidad::Kilometro).to_string(), "7 km");
+}
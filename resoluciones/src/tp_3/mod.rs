@@ -9,4 +9,17 @@ pub mod ej_8;
 pub mod ej_9;
 pub mod ej_10;
 
+pub mod analitico;
+pub mod biblioteca_musical;
+pub mod comun;
+pub mod financiacion;
+pub mod formatos_playlist;
+pub mod geometria;
+pub mod importador_notas;
+pub mod medidas;
+pub mod motor_precios;
+pub mod red_concesionarios;
+pub mod reproductor;
+pub mod valuacion;
+
 pub mod alfredo_moracho_e2_v2;
\ No newline at end of file
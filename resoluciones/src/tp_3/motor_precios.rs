@@ -0,0 +1,274 @@
+// Motor de reglas de precio: impuestos, descuentos y topes se cargan como datos
+// y se aplican en el orden en que fueron agregados. Cada regla deja registrado
+// cuanto movio el precio, asi el resultado se puede mostrar detallado.
+
+use std::fmt;
+
+use crate::dinero::{Dinero, ErrorDinero, Porcentaje, Redondeo};
+#[cfg(test)]
+use crate::dinero::{pesos, Moneda};
+
+// sobre que monto se calcula un ajuste porcentual
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseDeCalculo {
+    PrecioBruto,
+    Subtotal, // lo acumulado despues de las reglas anteriores
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoAjuste {
+    Impuesto(Porcentaje),
+    Descuento(Porcentaje),
+    RecargoFijo(Dinero),
+    DescuentoFijo(Dinero),
+    PrecioMaximo(Dinero),
+    PrecioMinimo(Dinero),
+}
+
+// un tope negativo daria vuelta el signo del ajuste
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopeNegativo(pub Dinero);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regla {
+    nombre: String,
+    ajuste: TipoAjuste,
+    base: BaseDeCalculo,
+    tope: Option<Dinero>, // lo maximo que la regla puede mover el precio
+}
+
+impl Regla {
+    pub fn new(nombre: &str, ajuste: TipoAjuste, base: BaseDeCalculo) -> Regla {
+        Regla { nombre: nombre.to_string(), ajuste, base, tope: None }
+    }
+
+    pub fn con_tope(mut self, tope: Dinero) -> Result<Regla, TopeNegativo> {
+        if tope.es_negativo() {
+            return Err(TopeNegativo(tope));
+        }
+        self.tope = Some(tope);
+        Ok(self)
+    }
+
+    pub fn iva_general() -> Regla {
+        Regla::new("IVA 21%", TipoAjuste::Impuesto(Porcentaje::new(21)), BaseDeCalculo::Subtotal)
+    }
+
+    pub fn iva_reducido() -> Regla {
+        Regla::new("IVA 10.5%", TipoAjuste::Impuesto(Porcentaje::con_decimales(1050)), BaseDeCalculo::Subtotal)
+    }
+
+    pub fn impuesto_interno(porcentaje: Porcentaje) -> Regla {
+        let nombre = format!("Impuesto interno {}", porcentaje);
+        Regla::new(&nombre, TipoAjuste::Impuesto(porcentaje), BaseDeCalculo::Subtotal)
+    }
+
+    fn calcular_monto(&self, precio_bruto: &Dinero, subtotal: &Dinero, redondeo: Redondeo) -> Result<Dinero, ErrorDinero> {
+        let base = match self.base {
+            BaseDeCalculo::PrecioBruto => precio_bruto,
+            BaseDeCalculo::Subtotal => subtotal,
+        };
+        let monto = match self.ajuste {
+            TipoAjuste::Impuesto(porcentaje) => base.porcentaje(porcentaje, redondeo)?,
+            TipoAjuste::Descuento(porcentaje) => base.porcentaje(porcentaje, redondeo)?.negar()?,
+            TipoAjuste::RecargoFijo(monto) => monto,
+            TipoAjuste::DescuentoFijo(monto) => monto.negar()?,
+            TipoAjuste::PrecioMaximo(maximo) => {
+                let diferencia = maximo.restar(subtotal)?;
+                if diferencia.es_negativo() { diferencia } else { Dinero::cero(subtotal.moneda()) }
+            }
+            TipoAjuste::PrecioMinimo(minimo) => {
+                let diferencia = minimo.restar(subtotal)?;
+                if diferencia.es_negativo() { Dinero::cero(subtotal.moneda()) } else { diferencia }
+            }
+        };
+        match self.tope {
+            Some(tope) => limitar(monto, tope),
+            None => Ok(monto),
+        }
+    }
+}
+
+fn limitar(monto: Dinero, tope: Dinero) -> Result<Dinero, ErrorDinero> {
+    let absoluto = if monto.es_negativo() { monto.negar()? } else { monto };
+    if tope.restar(&absoluto)?.es_negativo() {
+        if monto.es_negativo() { tope.negar() } else { Ok(tope) }
+    } else {
+        Ok(monto)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ajuste {
+    nombre: String,
+    monto: Dinero, // negativo para descuentos
+    subtotal: Dinero,
+}
+
+impl Ajuste {
+    pub fn nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn monto(&self) -> Dinero {
+        self.monto
+    }
+
+    pub fn subtotal(&self) -> Dinero {
+        self.subtotal
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Desglose {
+    precio_bruto: Dinero,
+    ajustes: Vec<Ajuste>,
+    precio_final: Dinero,
+}
+
+impl Desglose {
+    pub fn precio_bruto(&self) -> Dinero {
+        self.precio_bruto
+    }
+
+    pub fn ajustes(&self) -> &[Ajuste] {
+        &self.ajustes
+    }
+
+    pub fn precio_final(&self) -> Dinero {
+        self.precio_final
+    }
+}
+
+impl fmt::Display for Desglose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ajustes = self.ajustes.iter().map(|ajuste| (ajuste.nombre.as_str(), ajuste.monto));
+        let renglones = [("Precio bruto", self.precio_bruto)].into_iter()
+            .chain(ajustes)
+            .chain([("Precio final", self.precio_final)]);
+        escribir_renglones(f, renglones)
+    }
+}
+
+// un renglon por monto, con la etiqueta a la izquierda y el monto alineado a
+// la derecha; lo usan todos los desgloses de precios para que se vean iguales
+pub fn escribir_renglones<'a>(f: &mut fmt::Formatter, renglones: impl IntoIterator<Item = (&'a str, Dinero)>) -> fmt::Result {
+    for (numero, (etiqueta, monto)) in renglones.into_iter().enumerate() {
+        if numero > 0 {
+            writeln!(f)?;
+        }
+        write!(f, "{:<30}{:>20}", etiqueta, monto.to_string())?;
+    }
+    Ok(())
+}
+
+pub struct MotorDePrecios {
+    reglas: Vec<Regla>,
+    redondeo: Redondeo,
+}
+
+impl MotorDePrecios {
+    pub fn new(redondeo: Redondeo) -> MotorDePrecios {
+        MotorDePrecios { reglas: Vec::new(), redondeo }
+    }
+
+    pub fn con_reglas(reglas: Vec<Regla>, redondeo: Redondeo) -> MotorDePrecios {
+        MotorDePrecios { reglas, redondeo }
+    }
+
+    pub fn agregar_regla(&mut self, regla: Regla) {
+        self.reglas.push(regla);
+    }
+
+    pub fn aplicar(&self, precio_bruto: Dinero) -> Result<Desglose, ErrorDinero> {
+        let mut subtotal = precio_bruto;
+        let mut ajustes = Vec::new();
+        for regla in &self.reglas {
+            let monto = regla.calcular_monto(&precio_bruto, &subtotal, self.redondeo)?;
+            subtotal = subtotal.sumar(&monto)?;
+            ajustes.push(Ajuste { nombre: regla.nombre.clone(), monto, subtotal });
+        }
+        Ok(Desglose { precio_bruto, ajustes, precio_final: subtotal })
+    }
+}
+
+
+#[test]
+fn test_iva_y_desglose() {
+    let mut motor = MotorDePrecios::new(Redondeo::MitadHaciaArriba);
+    motor.agregar_regla(Regla::new("Descuento 10%", TipoAjuste::Descuento(Porcentaje::new(10)), BaseDeCalculo::Subtotal));
+    motor.agregar_regla(Regla::iva_general());
+    let desglose = motor.aplicar(pesos(1000)).unwrap();
+
+    assert_eq!(desglose.ajustes().len(), 2);
+    assert_eq!(desglose.ajustes()[0].monto(), pesos(-100));
+    assert_eq!(desglose.ajustes()[0].subtotal(), pesos(900));
+    assert_eq!(desglose.ajustes()[1].nombre(), "IVA 21%");
+    assert_eq!(desglose.ajustes()[1].monto(), pesos(189));
+    assert_eq!(desglose.precio_final(), pesos(1089));
+}
+
+#[test]
+fn test_orden_de_las_reglas() {
+    let descuento = Regla::new("Descuento fijo", TipoAjuste::DescuentoFijo(pesos(10)), BaseDeCalculo::Subtotal);
+
+    // descuento antes del impuesto: (100 - 10) * 1,21
+    let antes = MotorDePrecios::con_reglas(vec![descuento.clone(), Regla::iva_general()], Redondeo::Bancario);
+    assert_eq!(antes.aplicar(pesos(100)).unwrap().precio_final(), Dinero::new(10890, Moneda::Ars));
+
+    // descuento despues del impuesto: 100 * 1,21 - 10
+    let despues = MotorDePrecios::con_reglas(vec![Regla::iva_general(), descuento], Redondeo::Bancario);
+    assert_eq!(despues.aplicar(pesos(100)).unwrap().precio_final(), pesos(111));
+}
+
+#[test]
+fn test_base_precio_bruto() {
+    // ambos porcentajes sobre el precio bruto: el impuesto no se calcula sobre el precio ya descontado
+    let motor = MotorDePrecios::con_reglas(vec![
+        Regla::new("Descuento", TipoAjuste::Descuento(Porcentaje::new(20)), BaseDeCalculo::PrecioBruto),
+        Regla::new("Impuesto", TipoAjuste::Impuesto(Porcentaje::new(10)), BaseDeCalculo::PrecioBruto),
+    ], Redondeo::Bancario);
+    assert_eq!(motor.aplicar(pesos(100)).unwrap().precio_final(), pesos(90));
+}
+
+#[test]
+fn test_topes() {
+    let motor = MotorDePrecios::con_reglas(vec![
+        Regla::new("Descuento 50% hasta $200", TipoAjuste::Descuento(Porcentaje::new(50)), BaseDeCalculo::Subtotal).con_tope(pesos(200)).unwrap(),
+        Regla::iva_reducido(),
+        Regla::impuesto_interno(Porcentaje::new(8)),
+        Regla::new("Precio maximo", TipoAjuste::PrecioMaximo(pesos(1000)), BaseDeCalculo::Subtotal),
+    ], Redondeo::Bancario);
+    let desglose = motor.aplicar(pesos(1000)).unwrap();
+    assert_eq!(desglose.ajustes()[0].monto(), pesos(-200));
+    assert_eq!(desglose.ajustes()[1].monto(), pesos(84));
+    assert_eq!(desglose.ajustes()[2].nombre(), "Impuesto interno 8.00%");
+    // 884 * 1,08 = 954,72: no llega al precio maximo
+    assert!(desglose.ajustes()[3].monto().es_cero());
+    assert_eq!(desglose.precio_final(), Dinero::new(95472, Moneda::Ars));
+
+    let motor = MotorDePrecios::con_reglas(vec![
+        Regla::iva_general(),
+        Regla::new("Precio maximo", TipoAjuste::PrecioMaximo(pesos(1000)), BaseDeCalculo::Subtotal),
+        Regla::new("Precio minimo", TipoAjuste::PrecioMinimo(pesos(1500)), BaseDeCalculo::Subtotal),
+    ], Redondeo::Bancario);
+    let desglose = motor.aplicar(pesos(1000)).unwrap();
+    assert_eq!(desglose.ajustes()[1].monto(), pesos(-210));
+    assert_eq!(desglose.ajustes()[2].monto(), pesos(500));
+    assert_eq!(desglose.precio_final(), pesos(1500));
+}
+
+#[test]
+fn test_reglas_con_otra_moneda() {
+    let dolares = Dinero::desde_unidades(5, Moneda::Usd);
+    let motor = MotorDePrecios::con_reglas(vec![Regla::new("Envio", TipoAjuste::RecargoFijo(dolares), BaseDeCalculo::Subtotal)], Redondeo::Bancario);
+    assert_eq!(motor.aplicar(pesos(100)), Err(ErrorDinero::MonedasDistintas));
+}
+
+#[test]
+fn test_tope_negativo() {
+    let descuento = Regla::new("Descuento", TipoAjuste::Descuento(Porcentaje::new(50)), BaseDeCalculo::Subtotal);
+    assert_eq!(descuento.clone().con_tope(pesos(-200)), Err(TopeNegativo(pesos(-200))));
+    let sin_movimiento = MotorDePrecios::con_reglas(vec![descuento.con_tope(pesos(0)).unwrap()], Redondeo::Bancario);
+    assert_eq!(sin_movimiento.aplicar(pesos(100)).unwrap().precio_final(), pesos(100));
+}
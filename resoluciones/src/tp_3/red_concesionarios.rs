@@ -0,0 +1,360 @@
+// Red de sucursales: cada una sigue siendo un ConcesionarioAuto con su propio
+// cap_max, y la red se encarga de que una patente este en una sola sucursal.
+// Los clientes se registran una sola vez en la red y operan en cualquier sucursal.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::ej_3::Fecha;
+use super::ej_7::{Auto, Cliente, ConcesionarioAuto, ConsultaAutos, ErrorOperacion, Reserva, Venta};
+use crate::dinero::{Dinero, ErrorDinero, Moneda};
+#[cfg(test)]
+use super::comun::Direccion;
+#[cfg(test)]
+use super::ej_7::{CampoOrden, Color};
+
+#[derive(Debug, PartialEq)]
+pub enum ErrorRed {
+    SucursalDuplicada,
+    SucursalInexistente,
+    PatenteDuplicada, // la patente ya esta en alguna sucursal de la red
+    AutoInexistente,
+    SinCapacidad,
+    AutoReservado,
+    AutoConPruebas, // tiene pruebas de manejo agendadas en su sucursal
+    MismaSucursal,
+    Operacion(ErrorOperacion),
+}
+
+pub struct RedConcesionarios {
+    nombre: String,
+    sucursales: BTreeMap<String, ConcesionarioAuto>, // por nombre de sucursal
+    clientes: BTreeMap<u32, Cliente>, // por dni
+}
+
+impl RedConcesionarios {
+    pub fn new(nombre: String) -> RedConcesionarios {
+        RedConcesionarios { nombre, sucursales: BTreeMap::new(), clientes: BTreeMap::new() }
+    }
+
+    pub fn agregar_sucursal(&mut self, sucursal: ConcesionarioAuto) -> Result<(), ErrorRed> {
+        if self.sucursales.contains_key(sucursal.nombre()) {
+            return Err(ErrorRed::SucursalDuplicada);
+        }
+        if sucursal.autos().iter().any(|auto| self.ubicar(auto.patente()).is_some()) {
+            return Err(ErrorRed::PatenteDuplicada);
+        }
+        self.sucursales.insert(sucursal.nombre().to_string(), sucursal);
+        Ok(())
+    }
+
+    pub fn sucursal(&self, nombre: &str) -> Option<&ConcesionarioAuto> {
+        self.sucursales.get(nombre)
+    }
+
+    pub fn agregar_auto(&mut self, sucursal: &str, auto: Auto) -> Result<(), ErrorRed> {
+        if self.ubicar(auto.patente()).is_some() {
+            return Err(ErrorRed::PatenteDuplicada);
+        }
+        let sucursal = self.sucursales.get_mut(sucursal).ok_or(ErrorRed::SucursalInexistente)?;
+        if !sucursal.agregar_auto(auto) {
+            return Err(ErrorRed::SinCapacidad);
+        }
+        Ok(())
+    }
+
+    // en que sucursal esta el auto
+    pub fn ubicar(&self, patente: &str) -> Option<(&str, &Auto)> {
+        self.sucursales.iter()
+            .find_map(|(nombre, sucursal)| sucursal.buscar_por_patente(patente).map(|auto| (nombre.as_str(), auto)))
+    }
+
+    pub fn registrar_cliente(&mut self, cliente: Cliente) -> Result<(), ErrorRed> {
+        if self.clientes.contains_key(&cliente.dni()) {
+            return Err(ErrorRed::Operacion(ErrorOperacion::ClienteDuplicado));
+        }
+        self.clientes.insert(cliente.dni(), cliente);
+        Ok(())
+    }
+
+    // reservas, pruebas de manejo y ventas se hacen en la sucursal donde esta el auto
+    pub fn reservar(&mut self, patente: &str, dni_cliente: u32, sena: Dinero, fecha: Fecha, dias_de_validez: u32) -> Result<&Reserva, ErrorRed> {
+        let (sucursal, cliente) = self.sucursal_del_auto(patente, dni_cliente)?;
+        sucursal.reservar_para(cliente, patente, sena, fecha, dias_de_validez).map_err(ErrorRed::Operacion)
+    }
+
+    pub fn agendar_prueba_de_manejo(&mut self, patente: &str, dni_cliente: u32, fecha: Fecha) -> Result<(), ErrorRed> {
+        let (sucursal, cliente) = self.sucursal_del_auto(patente, dni_cliente)?;
+        sucursal.agendar_prueba_para(cliente, patente, fecha).map_err(ErrorRed::Operacion)
+    }
+
+    pub fn vender(&mut self, patente: &str, dni_cliente: u32, vendedor: &str, fecha: Fecha) -> Result<&Venta, ErrorRed> {
+        let (sucursal, cliente) = self.sucursal_del_auto(patente, dni_cliente)?;
+        sucursal.vender_a(cliente, patente, vendedor, fecha).map_err(ErrorRed::Operacion)
+    }
+
+    fn sucursal_del_auto(&mut self, patente: &str, dni_cliente: u32) -> Result<(&mut ConcesionarioAuto, &Cliente), ErrorRed> {
+        let nombre = self.ubicar(patente).ok_or(ErrorRed::AutoInexistente)?.0.to_string();
+        let cliente = self.clientes.get(&dni_cliente).ok_or(ErrorRed::Operacion(ErrorOperacion::ClienteInexistente))?;
+        let sucursal = self.sucursales.get_mut(&nombre).ok_or(ErrorRed::SucursalInexistente)?;
+        Ok((sucursal, cliente))
+    }
+
+    // las reservas vencidas de todas las sucursales, para gestionar sus senas
+    pub fn liberar_reservas_vencidas(&mut self, fecha: &Fecha) -> Vec<(String, Reserva)> {
+        self.sucursales.iter_mut()
+            .flat_map(|(nombre, sucursal)| sucursal.liberar_reservas_vencidas(fecha).into_iter().map(move |reserva| (nombre.clone(), reserva)))
+            .collect()
+    }
+
+    // el orden y el paginado de la consulta se aplican sobre el resultado de toda la red
+    pub fn consultar(&self, consulta: &ConsultaAutos) -> Vec<(&str, &Auto)> {
+        let filtros = consulta.solo_filtros();
+        let encontrados: Vec<(&str, &Auto)> = self.sucursales.iter()
+            .flat_map(|(nombre, sucursal)| sucursal.consultar(&filtros).into_iter().map(move |auto| (nombre.as_str(), auto)))
+            .collect();
+        consulta.ordenar_y_paginar(encontrados, |(_, auto)| auto)
+    }
+
+    // un auto con una reserva vigente o con pruebas de manejo por delante se
+    // queda en la sucursal donde se agendaron. Una reserva ya vencida sigue en
+    // el origen para que se pueda gestionar su sena
+    pub fn transferir(&mut self, patente: &str, destino: &str, fecha: &Fecha) -> Result<(), ErrorRed> {
+        let origen = self.ubicar(patente).ok_or(ErrorRed::AutoInexistente)?.0.to_string();
+        let lugares_en_destino = self.sucursales.get(destino).ok_or(ErrorRed::SucursalInexistente)?.lugares_libres();
+        if origen == destino {
+            return Err(ErrorRed::MismaSucursal);
+        }
+        if lugares_en_destino == 0 {
+            return Err(ErrorRed::SinCapacidad);
+        }
+        let sucursal_origen = self.sucursales.get_mut(&origen).ok_or(ErrorRed::SucursalInexistente)?;
+        if sucursal_origen.reserva_vigente(patente, fecha).is_some() {
+            return Err(ErrorRed::AutoReservado);
+        }
+        if sucursal_origen.tiene_pruebas_pendientes(patente, fecha) {
+            return Err(ErrorRed::AutoConPruebas);
+        }
+        let auto = sucursal_origen.desvincular_auto(patente).ok_or(ErrorRed::AutoInexistente)?;
+        // ya se controlo el lugar, pero si el destino lo rechaza el auto vuelve al origen
+        if !self.sucursales.get_mut(destino).is_some_and(|sucursal| sucursal.agregar_auto(auto.clone())) {
+            if let Some(sucursal_origen) = self.sucursales.get_mut(&origen) {
+                sucursal_origen.agregar_auto(auto);
+            }
+            return Err(ErrorRed::SinCapacidad);
+        }
+        Ok(())
+    }
+
+    pub fn reporte_de_stock(&self) -> Result<ReporteStock, ErrorDinero> {
+        let mut por_sucursal = Vec::new();
+        let mut por_marca: BTreeMap<String, usize> = BTreeMap::new();
+        let mut valor_total: HashMap<Moneda, Dinero> = HashMap::new();
+        for (nombre, sucursal) in &self.sucursales {
+            por_sucursal.push(StockSucursal {
+                sucursal: nombre.clone(),
+                autos: sucursal.autos().len(),
+                capacidad: sucursal.cap_max(),
+            });
+            for auto in sucursal.autos() {
+                *por_marca.entry(auto.marca().to_string()).or_default() += 1;
+                let precio = auto.precio_bruto();
+                let acumulado = valor_total.entry(precio.moneda()).or_insert(Dinero::cero(precio.moneda()));
+                *acumulado = acumulado.sumar(&precio)?;
+            }
+        }
+        let mut valor_total: Vec<Dinero> = valor_total.into_values().collect();
+        valor_total.sort_by(|a, b| a.moneda().codigo().cmp(b.moneda().codigo()));
+        Ok(ReporteStock { red: self.nombre.clone(), por_sucursal, por_marca, valor_total })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StockSucursal {
+    sucursal: String,
+    autos: usize,
+    capacidad: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReporteStock {
+    red: String,
+    por_sucursal: Vec<StockSucursal>,
+    por_marca: BTreeMap<String, usize>,
+    valor_total: Vec<Dinero>, // precio bruto del stock, una entrada por moneda
+}
+
+impl ReporteStock {
+    pub fn total_autos(&self) -> usize {
+        self.por_sucursal.iter().map(|stock| stock.autos).sum()
+    }
+
+    pub fn capacidad_total(&self) -> usize {
+        self.por_sucursal.iter().map(|stock| stock.capacidad).sum()
+    }
+}
+
+impl fmt::Display for ReporteStock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Stock de {}", self.red)?;
+        writeln!(f, "{:<20}{:>8}{:>12}", "Sucursal", "Autos", "Capacidad")?;
+        for stock in &self.por_sucursal {
+            writeln!(f, "{:<20}{:>8}{:>12}", stock.sucursal, stock.autos, stock.capacidad)?;
+        }
+        writeln!(f, "{:<20}{:>8}{:>12}", "Total", self.total_autos(), self.capacidad_total())?;
+        for (marca, cantidad) in &self.por_marca {
+            writeln!(f, "{:<20}{:>8}", marca, cantidad)?;
+        }
+        for valor in &self.valor_total {
+            writeln!(f, "Valor del stock: {}", valor)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+fn auto(patente: &str, marca: &str, anio: u32, precio: i64) -> Auto {
+    Auto::new(patente.to_string(), marca.to_string(), "Modelo".to_string(), anio, Dinero::desde_unidades(precio, Moneda::Ars), Color::Blanco)
+}
+
+#[test]
+fn test_sucursales_y_patentes_unicas() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Centro", auto("AA002AA", "Fiat", 2012, 8000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.agregar_auto("Norte", auto("AA004AA", "BMW", 2021, 40000)).unwrap();
+    assert_eq!(red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), String::new(), 5)), Err(ErrorRed::SucursalDuplicada));
+    let mut con_repetido = ConcesionarioAuto::new("Sur".to_string(), String::new(), 5);
+    con_repetido.agregar_auto(auto("AA001AA", "Ford", 2018, 15000));
+    assert_eq!(red.agregar_sucursal(con_repetido), Err(ErrorRed::PatenteDuplicada));
+
+    assert_eq!(red.agregar_auto("Centro", auto("AA003AA", "Ford", 2020, 20000)), Err(ErrorRed::PatenteDuplicada));
+    assert_eq!(red.agregar_auto("Norte", auto("AA005AA", "Fiat", 2020, 9000)), Err(ErrorRed::SinCapacidad));
+    assert_eq!(red.agregar_auto("Oeste", auto("AA005AA", "Fiat", 2020, 9000)), Err(ErrorRed::SucursalInexistente));
+    assert_eq!(red.ubicar("AA004AA").map(|(sucursal, auto)| (sucursal, auto.marca())), Some(("Norte", "BMW")));
+    assert!(red.ubicar("ZZ999ZZ").is_none());
+}
+
+#[test]
+fn test_consulta_en_toda_la_red() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Centro", auto("AA002AA", "Fiat", 2012, 8000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.agregar_auto("Norte", auto("AA004AA", "BMW", 2021, 40000)).unwrap();
+    let fords = red.consultar(&ConsultaAutos::new().con_marca("ford"));
+    assert_eq!(fords.iter().map(|(sucursal, _)| *sucursal).collect::<Vec<_>>(), vec!["Centro", "Norte"]);
+
+    // el paginado es sobre el total, no sobre cada sucursal
+    let consulta = ConsultaAutos::new().ordenar_por(CampoOrden::Precio, Direccion::Descendente).paginar(1, 3);
+    let patentes: Vec<&str> = red.consultar(&consulta).iter().map(|(_, auto)| auto.patente()).collect();
+    assert_eq!(patentes, vec!["AA004AA", "AA003AA", "AA001AA"]);
+}
+
+#[test]
+fn test_transferencias() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Centro", auto("AA002AA", "Fiat", 2012, 8000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.agregar_auto("Norte", auto("AA004AA", "BMW", 2021, 40000)).unwrap();
+    let hoy = Fecha::new(1, 3, 2024);
+    assert_eq!(red.transferir("AA001AA", "Norte", &hoy), Err(ErrorRed::SinCapacidad));
+    assert_eq!(red.transferir("AA001AA", "Centro", &hoy), Err(ErrorRed::MismaSucursal));
+    assert_eq!(red.transferir("ZZ999ZZ", "Centro", &hoy), Err(ErrorRed::AutoInexistente));
+    assert_eq!(red.transferir("AA001AA", "Oeste", &hoy), Err(ErrorRed::SucursalInexistente));
+
+    assert_eq!(red.transferir("AA004AA", "Centro", &hoy), Ok(()));
+    assert_eq!(red.ubicar("AA004AA").map(|(sucursal, _)| sucursal), Some("Centro"));
+    assert_eq!(red.sucursal("Norte").unwrap().lugares_libres(), 1);
+    assert_eq!(red.sucursal("Centro").unwrap().lugares_libres(), 0);
+
+    red.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), String::new())).unwrap();
+    let sena = Dinero::desde_unidades(500, Moneda::Ars);
+    red.reservar("AA003AA", 30111222, sena, hoy.clone(), 5).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Sur".to_string(), String::new(), 5)).unwrap();
+    assert_eq!(red.transferir("AA003AA", "Sur", &hoy), Err(ErrorRed::AutoReservado));
+
+    // vencida la reserva el auto se puede llevar, y la sena queda registrada en el origen
+    let despues = Fecha::new(10, 3, 2024);
+    assert_eq!(red.transferir("AA003AA", "Sur", &despues), Ok(()));
+    assert_eq!(red.ubicar("AA003AA").map(|(sucursal, _)| sucursal), Some("Sur"));
+    let vencidas = red.liberar_reservas_vencidas(&despues);
+    assert_eq!(vencidas.iter().map(|(sucursal, reserva)| (sucursal.as_str(), reserva.sena())).collect::<Vec<_>>(), vec![("Norte", sena)]);
+    assert!(red.liberar_reservas_vencidas(&despues).is_empty());
+}
+
+#[test]
+fn test_transferir_no_pierde_pruebas_de_manejo() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Centro", auto("AA002AA", "Fiat", 2012, 8000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.agregar_auto("Norte", auto("AA004AA", "BMW", 2021, 40000)).unwrap();
+    red.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), String::new())).unwrap();
+    red.agendar_prueba_de_manejo("AA001AA", 30111222, Fecha::new(5, 3, 2024)).unwrap();
+    assert_eq!(red.agendar_prueba_de_manejo("ZZ999ZZ", 30111222, Fecha::new(5, 3, 2024)), Err(ErrorRed::AutoInexistente));
+    assert_eq!(red.agendar_prueba_de_manejo("AA003AA", 1, Fecha::new(5, 3, 2024)), Err(ErrorRed::Operacion(ErrorOperacion::ClienteInexistente)));
+    red.agregar_sucursal(ConcesionarioAuto::new("Sur".to_string(), String::new(), 5)).unwrap();
+
+    assert_eq!(red.transferir("AA001AA", "Sur", &Fecha::new(1, 3, 2024)), Err(ErrorRed::AutoConPruebas));
+    assert_eq!(red.ubicar("AA001AA").map(|(sucursal, _)| sucursal), Some("Centro"));
+    assert!(red.sucursal("Centro").unwrap().tiene_pruebas_pendientes("AA001AA", &Fecha::new(1, 3, 2024)));
+    // pasado el turno ya no hay nada que cuidar
+    assert_eq!(red.transferir("AA001AA", "Sur", &Fecha::new(6, 3, 2024)), Ok(()));
+}
+
+#[test]
+fn test_reporte_de_stock() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Centro", auto("AA002AA", "Fiat", 2012, 8000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.agregar_auto("Norte", auto("AA004AA", "BMW", 2021, 40000)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Sur".to_string(), String::new(), 4)).unwrap();
+    red.agregar_auto("Sur", Auto::new("AA009AA".to_string(), "Ford".to_string(), "Ranger".to_string(), 2022, Dinero::desde_unidades(30000, Moneda::Usd), Color::Negro)).unwrap();
+    let reporte = red.reporte_de_stock().unwrap();
+    assert_eq!(reporte.total_autos(), 5);
+    assert_eq!(reporte.capacidad_total(), 9);
+    assert_eq!(reporte.por_marca.get("Ford"), Some(&3));
+    assert_eq!(reporte.valor_total, vec![Dinero::desde_unidades(83000, Moneda::Ars), Dinero::desde_unidades(30000, Moneda::Usd)]);
+
+    let texto = reporte.to_string();
+    assert!(texto.starts_with("Stock de Grupo Motosport\n"));
+    assert!(texto.contains("Norte                      2           2\n"));
+    assert!(texto.contains("Total                      5           9\n"));
+    assert!(texto.ends_with("Valor del stock: ARS 83000.00\nValor del stock: USD 30000.00\n"));
+}
+
+#[test]
+fn test_clientes_de_la_red() {
+    let mut red = RedConcesionarios::new("Grupo Motosport".to_string());
+    red.agregar_sucursal(ConcesionarioAuto::new("Centro".to_string(), "Calle 7".to_string(), 3)).unwrap();
+    red.agregar_sucursal(ConcesionarioAuto::new("Norte".to_string(), "Calle 13".to_string(), 2)).unwrap();
+    red.agregar_auto("Centro", auto("AA001AA", "Ford", 2018, 15000)).unwrap();
+    red.agregar_auto("Norte", auto("AA003AA", "Ford", 2020, 20000)).unwrap();
+    red.registrar_cliente(Cliente::new(30111222, "Ana".to_string(), String::new())).unwrap();
+    assert_eq!(red.registrar_cliente(Cliente::new(30111222, "Otra Ana".to_string(), String::new())), Err(ErrorRed::Operacion(ErrorOperacion::ClienteDuplicado)));
+
+    // el mismo cliente opera en las dos sucursales sin registrarse en cada una
+    let sena = Dinero::desde_unidades(500, Moneda::Ars);
+    assert_eq!(red.agendar_prueba_de_manejo("AA001AA", 30111222, Fecha::new(2, 3, 2024)), Ok(()));
+    red.reservar("AA003AA", 30111222, sena, Fecha::new(1, 3, 2024), 5).unwrap();
+    let venta = red.vender("AA003AA", 30111222, "Carla", Fecha::new(3, 3, 2024)).unwrap();
+    // el precio final es 18000 y la sena ya estaba pagada
+    assert_eq!(venta.saldo(), Ok(Dinero::desde_unidades(17500, Moneda::Ars)));
+    assert!(red.ubicar("AA003AA").is_none());
+    assert_eq!(red.vender("AA001AA", 1, "Carla", Fecha::new(3, 3, 2024)).err(), Some(ErrorRed::Operacion(ErrorOperacion::ClienteInexistente)));
+}
@@ -0,0 +1,352 @@
+// Estado de reproduccion de una playlist: cancion actual, una cola aparte que
+// se escucha antes de seguir con la playlist, modo aleatorio reproducible con
+// una semilla, repeticion e historial de lo ultimo que sono.
+
+use std::collections::VecDeque;
+
+use super::ej_8::{Cancion, Playlist};
+#[cfg(test)]
+use super::ej_8::Genero;
+
+const LARGO_HISTORIAL: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModoRepeticion {
+    Ninguna,
+    Una,   // al terminar la cancion vuelve a sonar la misma
+    Todas, // al terminar la playlist vuelve a empezar
+}
+
+// xorshift64*: no sirve para criptografia pero con la misma semilla siempre
+// mezcla igual, que es lo que hace falta para poder repetir un orden
+struct GeneradorAleatorio {
+    estado: u64,
+}
+
+impl GeneradorAleatorio {
+    fn new(semilla: u64) -> GeneradorAleatorio {
+        // con estado cero la secuencia queda en cero para siempre
+        let estado = if semilla == 0 { 0x9E37_79B9_7F4A_7C15 } else { semilla };
+        GeneradorAleatorio { estado }
+    }
+
+    fn siguiente(&mut self) -> u64 {
+        self.estado ^= self.estado >> 12;
+        self.estado ^= self.estado << 25;
+        self.estado ^= self.estado >> 27;
+        self.estado.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // un entero en [0, limite)
+    fn hasta(&mut self, limite: usize) -> usize {
+        (self.siguiente() % limite as u64) as usize
+    }
+}
+
+pub struct Reproductor {
+    playlist: Playlist,
+    orden: Vec<usize>,       // indices de la playlist en el orden en que se escuchan
+    posicion: Option<usize>, // lugar en orden de la ultima cancion de la playlist que sono
+    actual: Option<Cancion>, // sin actual pero con posicion: se termino la playlist
+    desde_cola: bool,        // la cancion actual salio de la cola y no de la playlist
+    cola: VecDeque<Cancion>,
+    historial: VecDeque<Cancion>, // la mas reciente primero
+    repeticion: ModoRepeticion,
+    mezclado: bool,
+}
+
+impl Reproductor {
+    pub fn new(playlist: Playlist) -> Reproductor {
+        let orden = (0..playlist.canciones().len()).collect();
+        Reproductor {
+            playlist,
+            orden,
+            posicion: None,
+            actual: None,
+            desde_cola: false,
+            cola: VecDeque::new(),
+            historial: VecDeque::new(),
+            repeticion: ModoRepeticion::Ninguna,
+            mezclado: false,
+        }
+    }
+
+    pub fn actual(&self) -> Option<&Cancion> {
+        self.actual.as_ref()
+    }
+
+    pub fn cola(&self) -> Vec<&Cancion> {
+        self.cola.iter().collect()
+    }
+
+    pub fn historial(&self) -> Vec<&Cancion> {
+        self.historial.iter().collect()
+    }
+
+    pub fn esta_mezclado(&self) -> bool {
+        self.mezclado
+    }
+
+    pub fn cambiar_repeticion(&mut self, modo: ModoRepeticion) {
+        self.repeticion = modo;
+    }
+
+    pub fn encolar(&mut self, cancion: Cancion) {
+        self.cola.push_back(cancion);
+    }
+
+    // Fisher-Yates sobre el orden; la cancion de la playlist que esta sonando
+    // pasa al principio para que el resto se escuche sin repetirla. Si la
+    // playlist ya habia terminado, la mezcla vuelve a empezar de cero
+    pub fn mezclar(&mut self, semilla: u64) {
+        let mut generador = GeneradorAleatorio::new(semilla);
+        let en_curso = self.posicion.filter(|_| self.actual.is_some()).map(|posicion| self.orden[posicion]);
+        self.posicion = None;
+        self.orden = (0..self.playlist.canciones().len()).collect();
+        for i in (1..self.orden.len()).rev() {
+            let j = generador.hasta(i + 1);
+            self.orden.swap(i, j);
+        }
+        if let Some(indice) = en_curso {
+            let lugar = self.orden.iter().position(|&otro| otro == indice).unwrap_or(0);
+            self.orden.swap(0, lugar);
+            self.posicion = Some(0);
+        }
+        self.mezclado = true;
+    }
+
+    // vuelve al orden de la playlist sin cortar la cancion actual
+    pub fn desmezclar(&mut self) {
+        self.posicion = self.posicion.map(|posicion| self.orden[posicion]);
+        self.orden = (0..self.playlist.canciones().len()).collect();
+        self.mezclado = false;
+    }
+
+    pub fn reproducir(&mut self) -> Option<&Cancion> {
+        if self.actual.is_none() {
+            return self.siguiente();
+        }
+        self.actual.as_ref()
+    }
+
+    // pasa de cancion a pedido del usuario: primero la cola y despues la playlist
+    pub fn siguiente(&mut self) -> Option<&Cancion> {
+        self.dejar_actual();
+        if let Some(cancion) = self.cola.pop_front() {
+            self.actual = Some(cancion);
+            self.desde_cola = true;
+            return self.actual.as_ref();
+        }
+        let mut proxima = self.posicion.map_or(0, |posicion| posicion + 1);
+        if proxima >= self.orden.len() {
+            // sin repeticion se queda parado al final, sin volver a empezar
+            if self.repeticion != ModoRepeticion::Todas || self.orden.is_empty() {
+                return None;
+            }
+            proxima = 0;
+        }
+        self.sonar(proxima)
+    }
+
+    // la cancion actual llego al final: con repeticion de una vuelve a sonar
+    pub fn terminar(&mut self) -> Option<&Cancion> {
+        if self.repeticion == ModoRepeticion::Una {
+            if let Some(cancion) = self.actual.clone() {
+                self.agregar_al_historial(cancion);
+                return self.actual.as_ref();
+            }
+        }
+        self.siguiente()
+    }
+
+    // si lo que suena vino de la cola, o si la playlist ya termino, anterior
+    // vuelve a la ultima de la playlist
+    pub fn anterior(&mut self) -> Option<&Cancion> {
+        let posicion = self.posicion?;
+        let previa = if self.desde_cola || self.actual.is_none() {
+            posicion
+        } else if posicion > 0 {
+            posicion - 1
+        } else if self.repeticion == ModoRepeticion::Todas {
+            self.orden.len() - 1
+        } else {
+            0
+        };
+        self.dejar_actual();
+        self.sonar(previa)
+    }
+
+    fn sonar(&mut self, posicion: usize) -> Option<&Cancion> {
+        self.posicion = Some(posicion);
+        self.actual = Some(self.playlist.canciones()[self.orden[posicion]].clone());
+        self.desde_cola = false;
+        self.actual.as_ref()
+    }
+
+    fn dejar_actual(&mut self) {
+        if let Some(cancion) = self.actual.take() {
+            self.agregar_al_historial(cancion);
+        }
+    }
+
+    fn agregar_al_historial(&mut self, cancion: Cancion) {
+        self.historial.push_front(cancion);
+        self.historial.truncate(LARGO_HISTORIAL);
+    }
+}
+
+
+#[cfg(test)]
+fn titulo(cancion: Option<&Cancion>) -> Option<&str> {
+    cancion.map(|cancion| cancion.titulo())
+}
+
+#[cfg(test)]
+fn escuchar_todo(reproductor: &mut Reproductor) -> Vec<String> {
+    let mut titulos = Vec::new();
+    while let Some(cancion) = reproductor.siguiente() {
+        titulos.push(cancion.titulo().to_string());
+    }
+    titulos
+}
+
+#[test]
+fn test_reproduccion_en_orden() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=3 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut reproductor = Reproductor::new(playlist);
+    assert_eq!(reproductor.actual(), None);
+    assert_eq!(titulo(reproductor.reproducir()), Some("Tema1"));
+    assert_eq!(titulo(reproductor.reproducir()), Some("Tema1"));
+    assert_eq!(titulo(reproductor.siguiente()), Some("Tema2"));
+    assert_eq!(titulo(reproductor.anterior()), Some("Tema1"));
+    // al principio de la playlist anterior vuelve a empezar la misma
+    assert_eq!(titulo(reproductor.anterior()), Some("Tema1"));
+    assert_eq!(escuchar_todo(&mut reproductor), vec!["Tema2", "Tema3"]);
+    assert_eq!(reproductor.actual(), None);
+
+    let historial: Vec<&str> = reproductor.historial().iter().map(|cancion| cancion.titulo()).collect();
+    assert_eq!(historial, vec!["Tema3", "Tema2", "Tema1", "Tema1", "Tema2", "Tema1"]);
+    // terminada la playlist no vuelve a empezar, y anterior retoma la ultima
+    assert_eq!(reproductor.siguiente(), None);
+    assert_eq!(titulo(reproductor.anterior()), Some("Tema3"));
+    assert_eq!(reproductor.siguiente(), None);
+
+    let mut vacio = Reproductor::new(Playlist::new("Vacia".to_string()));
+    assert_eq!(vacio.reproducir(), None);
+    assert_eq!(vacio.anterior(), None);
+}
+
+#[test]
+fn test_repeticion() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=3 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut reproductor = Reproductor::new(playlist);
+    reproductor.cambiar_repeticion(ModoRepeticion::Todas);
+    reproductor.reproducir();
+    assert_eq!(titulo(reproductor.anterior()), Some("Tema3"));
+    assert_eq!(titulo(reproductor.siguiente()), Some("Tema1"));
+
+    reproductor.cambiar_repeticion(ModoRepeticion::Una);
+    assert_eq!(titulo(reproductor.terminar()), Some("Tema1"));
+    assert_eq!(titulo(reproductor.terminar()), Some("Tema1"));
+    // pasar de cancion a mano no respeta la repeticion de una
+    assert_eq!(titulo(reproductor.siguiente()), Some("Tema2"));
+    assert_eq!(reproductor.historial().len(), 5);
+
+    reproductor.cambiar_repeticion(ModoRepeticion::Ninguna);
+    assert_eq!(titulo(reproductor.terminar()), Some("Tema3"));
+    assert_eq!(reproductor.terminar(), None);
+    assert_eq!(reproductor.siguiente(), None);
+    // con repeticion de todas sigue desde el principio
+    reproductor.cambiar_repeticion(ModoRepeticion::Todas);
+    assert_eq!(titulo(reproductor.siguiente()), Some("Tema1"));
+}
+
+#[test]
+fn test_cola_de_reproduccion() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=3 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut reproductor = Reproductor::new(playlist);
+    reproductor.reproducir();
+    reproductor.encolar(Cancion::new("Extra1".to_string(), "Otro".to_string(), Genero::Jazz));
+    reproductor.encolar(Cancion::new("Extra2".to_string(), "Otro".to_string(), Genero::Jazz));
+    assert_eq!(reproductor.cola().len(), 2);
+    assert_eq!(titulo(reproductor.siguiente()), Some("Extra1"));
+    // desde la cola, anterior vuelve a la ultima de la playlist
+    assert_eq!(titulo(reproductor.anterior()), Some("Tema1"));
+    assert_eq!(escuchar_todo(&mut reproductor), vec!["Extra2", "Tema2", "Tema3"]);
+    assert!(reproductor.cola().is_empty());
+}
+
+#[test]
+fn test_mezclar_con_semilla() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=8 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut primero = Reproductor::new(playlist.clone());
+    primero.mezclar(42);
+    let orden = escuchar_todo(&mut primero);
+
+    let mut segundo = Reproductor::new(playlist.clone());
+    segundo.mezclar(42);
+    assert_eq!(escuchar_todo(&mut segundo), orden);
+
+    let mut otra_semilla = Reproductor::new(playlist.clone());
+    otra_semilla.mezclar(7);
+    assert_ne!(escuchar_todo(&mut otra_semilla), orden);
+
+    // cada tema suena una sola vez
+    let mut ordenado = orden.clone();
+    ordenado.sort();
+    assert_eq!(ordenado, escuchar_todo(&mut Reproductor::new(playlist)));
+}
+
+#[test]
+fn test_mezclar_sin_cortar_la_actual() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=5 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut reproductor = Reproductor::new(playlist.clone());
+    reproductor.reproducir();
+    reproductor.siguiente();
+    reproductor.siguiente();
+    reproductor.mezclar(3);
+    assert!(reproductor.esta_mezclado());
+    assert_eq!(titulo(reproductor.actual()), Some("Tema3"));
+    let resto = escuchar_todo(&mut reproductor);
+    assert_eq!(resto.len(), 4);
+    assert!(!resto.contains(&"Tema3".to_string()));
+
+    let mut reproductor = Reproductor::new(playlist);
+    reproductor.mezclar(3);
+    reproductor.reproducir();
+    let actual = reproductor.actual().unwrap().titulo().to_string();
+    reproductor.desmezclar();
+    let numero: usize = actual.trim_start_matches("Tema").parse().unwrap();
+    assert_eq!(escuchar_todo(&mut reproductor).len(), 5 - numero);
+    // mezclar una playlist terminada la vuelve a escuchar entera
+    reproductor.mezclar(9);
+    assert_eq!(escuchar_todo(&mut reproductor).len(), 5);
+}
+
+#[test]
+fn test_historial_acotado() {
+    let mut playlist = Playlist::new("Prueba".to_string());
+    for i in 1..=30 {
+        playlist.agregar_cancion(Cancion::new(format!("Tema{}", i), "Artista".to_string(), Genero::Rock));
+    }
+    let mut reproductor = Reproductor::new(playlist);
+    escuchar_todo(&mut reproductor);
+    let historial = reproductor.historial();
+    assert_eq!(historial.len(), LARGO_HISTORIAL);
+    assert_eq!(historial[0].titulo(), "Tema30");
+}
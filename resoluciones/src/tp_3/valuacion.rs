@@ -0,0 +1,250 @@
+// Valor de mercado de un auto usado: el precio bruto se deprecia por la edad
+// segun una curva y el resultado se ajusta por kilometraje y por estado.
+
+use std::fmt;
+
+use super::ej_7::{Auto, EstadoAuto};
+use super::motor_precios::escribir_renglones;
+use crate::dinero::{Dinero, ErrorDinero, Porcentaje, Redondeo};
+#[cfg(test)]
+use super::ej_7::Color;
+#[cfg(test)]
+use crate::dinero::Moneda;
+
+const REDONDEO: Redondeo = Redondeo::MitadHaciaArriba;
+const CIEN_POR_CIENTO: i64 = 10_000; // en centesimas
+// el ajuste por kilometraje no puede mover el valor mas que esto
+const TOPE_PENALIZACION_KM: i64 = 3_000;
+const TOPE_BONIFICACION_KM: i64 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurvaDepreciacion {
+    // pierde la misma parte del precio cada anio hasta llegar al residual al final de la vida util
+    Lineal { vida_util: u32, residual: Porcentaje },
+    // pierde cada anio la tasa sobre lo que valia el anio anterior, sin bajar del residual
+    SaldoDecreciente { tasa_anual: Porcentaje, residual: Porcentaje },
+}
+
+impl CurvaDepreciacion {
+    fn validar(&self) -> Result<(), ErrorValuacion> {
+        let (residual, tasa) = match self {
+            CurvaDepreciacion::Lineal { vida_util: 0, .. } => return Err(ErrorValuacion::VidaUtilNula),
+            CurvaDepreciacion::Lineal { residual, .. } => (residual, Porcentaje::new(100)),
+            CurvaDepreciacion::SaldoDecreciente { tasa_anual, residual } => (residual, *tasa_anual),
+        };
+        if !(0..=CIEN_POR_CIENTO).contains(&residual.centesimas()) || !(1..=CIEN_POR_CIENTO).contains(&tasa.centesimas()) {
+            return Err(ErrorValuacion::PorcentajeFueraDeRango);
+        }
+        Ok(())
+    }
+
+    fn depreciar(&self, precio: Dinero, edad: u32) -> Result<Dinero, ErrorDinero> {
+        match *self {
+            CurvaDepreciacion::Lineal { vida_util, residual } => {
+                let perdida = (CIEN_POR_CIENTO - residual.centesimas()) * edad.min(vida_util) as i64 / vida_util as i64;
+                precio.aplicar_descuento(Porcentaje::con_decimales(perdida), REDONDEO)
+            }
+            CurvaDepreciacion::SaldoDecreciente { tasa_anual, residual } => {
+                // anio por anio para que cada paso redondee al centavo igual que una tasacion a mano.
+                // Cuando el descuento de un anio ya redondea a cero el valor no cambia mas
+                let piso = precio.porcentaje(residual, REDONDEO)?;
+                let mut valor = precio;
+                for _ in 0..edad {
+                    let siguiente = valor.aplicar_descuento(tasa_anual, REDONDEO)?;
+                    if siguiente.centavos() <= piso.centavos() {
+                        return Ok(piso);
+                    }
+                    if siguiente == valor {
+                        break;
+                    }
+                    valor = siguiente;
+                }
+                Ok(valor)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorValuacion {
+    VidaUtilNula,
+    PorcentajeFueraDeRango,
+    KilometrajeNulo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeloValuacion {
+    curva: CurvaDepreciacion,
+    km_anuales: u32, // lo que se espera que recorra un auto por anio
+    ajuste_cada_10000: Porcentaje, // por cada 10.000 km de diferencia con lo esperado
+}
+
+impl ModeloValuacion {
+    pub fn new(curva: CurvaDepreciacion) -> Result<ModeloValuacion, ErrorValuacion> {
+        curva.validar()?;
+        Ok(ModeloValuacion { curva, km_anuales: 15_000, ajuste_cada_10000: Porcentaje::new(2) })
+    }
+
+    // el ajuste va de 0 a 100 % por cada 10.000 km; los topes limitan el total
+    pub fn con_kilometraje_esperado(mut self, km_anuales: u32, ajuste_cada_10000: Porcentaje) -> Result<ModeloValuacion, ErrorValuacion> {
+        if km_anuales == 0 {
+            return Err(ErrorValuacion::KilometrajeNulo);
+        }
+        if !(0..=CIEN_POR_CIENTO).contains(&ajuste_cada_10000.centesimas()) {
+            return Err(ErrorValuacion::PorcentajeFueraDeRango);
+        }
+        self.km_anuales = km_anuales;
+        self.ajuste_cada_10000 = ajuste_cada_10000;
+        Ok(self)
+    }
+
+    // los ajustes por kilometraje y estado se calculan sobre el valor ya depreciado
+    pub fn valuar(&self, auto: &Auto, anio_actual: u32) -> Result<Valuacion, ErrorDinero> {
+        let edad = anio_actual.saturating_sub(auto.anio());
+        let depreciado = self.curva.depreciar(auto.precio_bruto(), edad)?;
+        let ajuste_kilometraje = depreciado.porcentaje(self.ajuste_por_kilometraje(auto.kilometraje(), edad), REDONDEO)?;
+        let ajuste_estado = depreciado.porcentaje(ajuste_por_estado(auto.estado()), REDONDEO)?;
+        let valor_de_mercado = depreciado.sumar(&ajuste_kilometraje)?.sumar(&ajuste_estado)?;
+        Ok(Valuacion {
+            edad,
+            precio_bruto: auto.precio_bruto(),
+            depreciacion: depreciado.restar(&auto.precio_bruto())?,
+            ajuste_kilometraje,
+            ajuste_estado,
+            valor_de_mercado,
+        })
+    }
+
+    // en su primer anio un auto puede tener hasta un anio de uso sin penalizacion
+    fn ajuste_por_kilometraje(&self, kilometraje: u32, edad: u32) -> Porcentaje {
+        let esperado = self.km_anuales as i64 * edad.max(1) as i64;
+        let diferencia = kilometraje as i64 - esperado;
+        let ajuste = -diferencia * self.ajuste_cada_10000.centesimas() / 10_000;
+        Porcentaje::con_decimales(ajuste.clamp(-TOPE_PENALIZACION_KM, TOPE_BONIFICACION_KM))
+    }
+}
+
+fn ajuste_por_estado(estado: EstadoAuto) -> Porcentaje {
+    match estado {
+        EstadoAuto::Excelente => Porcentaje::new(5),
+        EstadoAuto::Bueno => Porcentaje::new(0),
+        EstadoAuto::Regular => Porcentaje::new(-10),
+        EstadoAuto::Malo => Porcentaje::new(-25),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Valuacion {
+    edad: u32,
+    precio_bruto: Dinero,
+    depreciacion: Dinero, // negativa: lo que se perdio por la edad
+    ajuste_kilometraje: Dinero,
+    ajuste_estado: Dinero,
+    valor_de_mercado: Dinero,
+}
+
+impl Valuacion {
+    pub fn depreciacion(&self) -> Dinero {
+        self.depreciacion
+    }
+
+    pub fn ajuste_kilometraje(&self) -> Dinero {
+        self.ajuste_kilometraje
+    }
+
+    pub fn ajuste_estado(&self) -> Dinero {
+        self.ajuste_estado
+    }
+
+    pub fn valor_de_mercado(&self) -> Dinero {
+        self.valor_de_mercado
+    }
+}
+
+impl fmt::Display for Valuacion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let depreciacion = format!("Depreciacion ({} anios)", self.edad);
+        escribir_renglones(f, [
+            ("Precio bruto", self.precio_bruto),
+            (depreciacion.as_str(), self.depreciacion),
+            ("Kilometraje", self.ajuste_kilometraje),
+            ("Estado", self.ajuste_estado),
+            ("Valor de mercado", self.valor_de_mercado),
+        ])
+    }
+}
+
+
+#[test]
+fn test_depreciacion_lineal() {
+    let modelo = ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 10, residual: Porcentaje::new(20) }).unwrap();
+    // 5 anios con el kilometraje esperado: pierde la mitad del 80 %
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2019, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(75_000, EstadoAuto::Bueno);
+    assert_eq!(modelo.valuar(&auto, 2024).unwrap().valor_de_mercado(), Dinero::desde_unidades(6000, Moneda::Ars));
+    // pasada la vida util queda el residual
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2004, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(300_000, EstadoAuto::Bueno);
+    assert_eq!(modelo.valuar(&auto, 2024).unwrap().depreciacion(), Dinero::desde_unidades(-8000, Moneda::Ars));
+    // un auto del anio en curso no se deprecia
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2024, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(15_000, EstadoAuto::Bueno);
+    assert_eq!(modelo.valuar(&auto, 2024).unwrap().valor_de_mercado(), Dinero::desde_unidades(10000, Moneda::Ars));
+}
+
+#[test]
+fn test_depreciacion_saldo_decreciente() {
+    let curva = CurvaDepreciacion::SaldoDecreciente { tasa_anual: Porcentaje::new(20), residual: Porcentaje::new(10) };
+    let modelo = ModeloValuacion::new(curva).unwrap();
+    // 10000 * 0,8^3
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2021, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(45_000, EstadoAuto::Bueno);
+    assert_eq!(modelo.valuar(&auto, 2024).unwrap().valor_de_mercado(), Dinero::desde_unidades(5120, Moneda::Ars));
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2004, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(300_000, EstadoAuto::Bueno);
+    assert_eq!(modelo.valuar(&auto, 2024).unwrap().valor_de_mercado(), Dinero::desde_unidades(1000, Moneda::Ars));
+
+    // sin residual el valor se estanca en unos centavos y no hace falta recorrer cada anio
+    let sin_residual = CurvaDepreciacion::SaldoDecreciente { tasa_anual: Porcentaje::new(20), residual: Porcentaje::new(0) };
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 0, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro);
+    let valuacion = ModeloValuacion::new(sin_residual).unwrap().valuar(&auto, u32::MAX).unwrap();
+    assert_eq!(valuacion.depreciacion(), Dinero::new(-999_998, Moneda::Ars));
+}
+
+#[test]
+fn test_ajustes_por_kilometraje_y_estado() {
+    let modelo = ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 10, residual: Porcentaje::new(20) }).unwrap();
+    // 2 anios: vale 8400 y se esperaban 30.000 km
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2022, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(55_000, EstadoAuto::Regular);
+    let valuacion = modelo.valuar(&auto, 2024).unwrap();
+    assert_eq!(valuacion.ajuste_kilometraje(), Dinero::desde_unidades(-420, Moneda::Ars));
+    assert_eq!(valuacion.ajuste_estado(), Dinero::desde_unidades(-840, Moneda::Ars));
+    assert_eq!(valuacion.valor_de_mercado(), Dinero::desde_unidades(7140, Moneda::Ars));
+
+    // poco uso suma, mucho uso resta, siempre dentro de los topes
+    let poco_uso = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2022, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(0, EstadoAuto::Excelente);
+    let valuacion = modelo.valuar(&poco_uso, 2024).unwrap();
+    assert_eq!(valuacion.ajuste_kilometraje(), Dinero::desde_unidades(504, Moneda::Ars));
+    assert_eq!(valuacion.ajuste_estado(), Dinero::desde_unidades(420, Moneda::Ars));
+    let mucho_uso = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2022, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(400_000, EstadoAuto::Malo);
+    assert_eq!(modelo.valuar(&mucho_uso, 2024).unwrap().ajuste_kilometraje(), Dinero::desde_unidades(-2520, Moneda::Ars));
+
+    let exigente = modelo.con_kilometraje_esperado(10_000, Porcentaje::new(5)).unwrap();
+    assert_eq!(exigente.valuar(&auto, 2024).unwrap().ajuste_kilometraje(), Dinero::desde_unidades(-1470, Moneda::Ars));
+    assert_eq!(modelo.con_kilometraje_esperado(0, Porcentaje::new(5)), Err(ErrorValuacion::KilometrajeNulo));
+    assert_eq!(modelo.con_kilometraje_esperado(10_000, Porcentaje::new(-1)), Err(ErrorValuacion::PorcentajeFueraDeRango));
+    assert_eq!(modelo.con_kilometraje_esperado(10_000, Porcentaje::new(101)), Err(ErrorValuacion::PorcentajeFueraDeRango));
+}
+
+#[test]
+fn test_valuacion_explicada() {
+    let auto = Auto::new("AA100AA".to_string(), "Ford".to_string(), "Focus".to_string(), 2022, Dinero::desde_unidades(10000, Moneda::Ars), Color::Negro).con_uso(55_000, EstadoAuto::Regular);
+    let texto = ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 10, residual: Porcentaje::new(20) }).unwrap().valuar(&auto, 2024).unwrap().to_string();
+    let lineas: Vec<&str> = texto.lines().collect();
+    assert_eq!(lineas[0], "Precio bruto                          ARS 10000.00");
+    assert_eq!(lineas[1], "Depreciacion (2 anios)                ARS -1600.00");
+    assert_eq!(lineas[4], "Valor de mercado                       ARS 7140.00");
+}
+
+#[test]
+fn test_curvas_invalidas() {
+    assert_eq!(ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 0, residual: Porcentaje::new(10) }), Err(ErrorValuacion::VidaUtilNula));
+    assert_eq!(ModeloValuacion::new(CurvaDepreciacion::Lineal { vida_util: 5, residual: Porcentaje::new(110) }), Err(ErrorValuacion::PorcentajeFueraDeRango));
+    let sin_tasa = CurvaDepreciacion::SaldoDecreciente { tasa_anual: Porcentaje::new(0), residual: Porcentaje::new(10) };
+    assert_eq!(ModeloValuacion::new(sin_tasa), Err(ErrorValuacion::PorcentajeFueraDeRango));
+}
@@ -1,13 +1,44 @@
 use std::{clone, collections::HashMap};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+use crate::dinero::{Dinero, ErrorDinero};
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::dinero::Moneda;
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 enum TipoSuscripcion {
     Basic,
     Clasic,
     Super,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+impl ConNombre for TipoSuscripcion {
+    const VARIANTES: &'static [(TipoSuscripcion, &'static str)] = &[
+        (TipoSuscripcion::Basic, "basic"),
+        (TipoSuscripcion::Clasic, "clasic"),
+        (TipoSuscripcion::Super, "super"),
+    ];
+}
+
+impl fmt::Display for TipoSuscripcion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for TipoSuscripcion {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        TipoSuscripcion::desde_nombre(texto)
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 enum MedioDepago {
     Efectivo,
     MercadoPago,
@@ -15,16 +46,40 @@ enum MedioDepago {
     Transferencia,
     Cripto,
 }
+
+impl ConNombre for MedioDepago {
+    const VARIANTES: &'static [(MedioDepago, &'static str)] = &[
+        (MedioDepago::Efectivo, "efectivo"),
+        (MedioDepago::MercadoPago, "mercado pago"),
+        (MedioDepago::TarjetaDeCred, "tarjeta de credito"),
+        (MedioDepago::Transferencia, "transferencia"),
+        (MedioDepago::Cripto, "cripto"),
+    ];
+}
+
+impl fmt::Display for MedioDepago {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for MedioDepago {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        MedioDepago::desde_nombre(texto)
+    }
+}
 #[derive(Clone, PartialEq, Debug)]
 struct Suscripcion {
     tipo: TipoSuscripcion,
-    costo_mensual: f64,
+    costo_mensual: Dinero,
     duracion: u8,
     fecha_inicio: String,
 }
 
 impl Suscripcion {
-    fn new(tipo:TipoSuscripcion, costo_mensual:f64, duracion:u8, fecha_inicio:String) -> Suscripcion {
+    fn new(tipo:TipoSuscripcion, costo_mensual:Dinero, duracion:u8, fecha_inicio:String) -> Suscripcion {
         Suscripcion {
             tipo,
             costo_mensual,
@@ -32,6 +87,10 @@ impl Suscripcion {
             fecha_inicio,
         }
     }
+
+    fn costo_total(&self) -> Result<Dinero, ErrorDinero> {
+        self.costo_mensual.multiplicar(self.duracion as i64)
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -96,10 +155,10 @@ impl StreamingRust {
 
     fn agregar_usuario(&mut self, usuario: Usuario) {
         if let Some(suscripcion) = &usuario.suscripcion {
-            self.historial_suscripciones.entry(suscripcion.tipo.clone()).and_modify(|s| *s += 1).or_insert(1);
+            self.historial_suscripciones.entry(suscripcion.tipo).and_modify(|s| *s += 1).or_insert(1);
         }
         if let Some(medio_pago) = &usuario.medio_pago {
-            self.historial_medio_pago.entry(medio_pago.clone()).and_modify(|m| *m += 1).or_insert(1);
+            self.historial_medio_pago.entry(*medio_pago).and_modify(|m| *m += 1).or_insert(1);
         }
         self.usuarios.insert(usuario.id, usuario);
     }
@@ -108,7 +167,7 @@ impl StreamingRust {
         let mut contador: HashMap<MedioDepago, u32> = HashMap::new();
         for usuario in self.usuarios.values() {
             if let Some(medio) = &usuario.medio_pago {
-                    contador.entry(medio.clone()).and_modify(|m| *m += 1).or_insert(1);
+                    contador.entry(*medio).and_modify(|m| *m += 1).or_insert(1);
                 }
             }
         contador.into_iter().max_by_key(|&(_, cant)| cant).map(|(m, _)| m)
@@ -118,7 +177,7 @@ impl StreamingRust {
         let mut contador: HashMap<TipoSuscripcion, u32> = HashMap::new();
         for usuario in self.usuarios.values() {
             if let Some(suscripcion) = &usuario.suscripcion {
-                contador.entry(suscripcion.tipo.clone()).and_modify(|s| *s += 1).or_insert(1);
+                contador.entry(suscripcion.tipo).and_modify(|s| *s += 1).or_insert(1);
             }
         }
         contador.into_iter().max_by_key(|&(_, cant)| cant).map(|(s, _)| s)
@@ -135,23 +194,32 @@ impl StreamingRust {
 }
 
 
+#[test]
+fn test_enums_texto_ida_y_vuelta() {
+    comprobar_variantes::<TipoSuscripcion>();
+    comprobar_variantes::<MedioDepago>();
+    assert_eq!("Mercado Pago".parse::<MedioDepago>(), Ok(MedioDepago::MercadoPago));
+    assert_eq!("premium".parse::<TipoSuscripcion>(), Err(NombreDesconocido("premium".to_string())));
+    assert_eq!("cheque".parse::<MedioDepago>(), Err(NombreDesconocido("cheque".to_string())));
+}
+
 #[test]
 fn test_usuario_upgrade_suscripcion() {
-    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Basic, 10.0, 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
+    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Basic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
     usuario.upgrade_suscripcion();
     assert_eq!(usuario.suscripcion.unwrap().tipo, TipoSuscripcion::Clasic);
 }
 
 #[test]
 fn test_usuario_downgrade_suscripcion() {
-    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Clasic, 10.0, 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
+    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Clasic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
     usuario.downgrade_suscripcion();
     assert_eq!(usuario.suscripcion.unwrap().tipo, TipoSuscripcion::Basic);
 }
 
 #[test]
 fn test_usuario_cancelar_suscripcion() {
-    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Clasic, 10.0, 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
+    let mut usuario = Usuario::new(1, Suscripcion::new(TipoSuscripcion::Clasic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string()), MedioDepago::TarjetaDeCred);
     usuario.cancelar_suscripcion();
     assert!(usuario.suscripcion.is_none());
     assert!(usuario.medio_pago.is_none());
@@ -160,7 +228,7 @@ fn test_usuario_cancelar_suscripcion() {
 #[test]
 fn test_streaming_rust_agregar_usuario() {
     let mut streaming_rust = StreamingRust::new();
-    let suscripcion = Suscripcion::new(TipoSuscripcion::Clasic, 10.0, 1, "2024-06-01".to_string());
+    let suscripcion = Suscripcion::new(TipoSuscripcion::Clasic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string());
     let usuario = Usuario::new(1, suscripcion.clone(), MedioDepago::TarjetaDeCred);
     streaming_rust.agregar_usuario(usuario.clone());
 
@@ -172,7 +240,7 @@ fn test_streaming_rust_agregar_usuario() {
 #[test]
 fn test_streaming_rust_pago_mas_utilizado_activos() { 
     let mut streaming_rust = StreamingRust::new();
-    let suscripcion = Suscripcion::new(TipoSuscripcion::Clasic, 10.0, 1, "2024-06-01".to_string());
+    let suscripcion = Suscripcion::new(TipoSuscripcion::Clasic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string());
     let usuario1 = Usuario::new(1, suscripcion.clone(), MedioDepago::TarjetaDeCred);
     let usuario2 = Usuario::new(2, suscripcion.clone(), MedioDepago::MercadoPago);
     let usuario3 = Usuario::new(3, suscripcion.clone(), MedioDepago::TarjetaDeCred);
@@ -187,8 +255,8 @@ fn test_streaming_rust_pago_mas_utilizado_activos() {
 #[test]
 fn test_streaming_rust_suscripcion_mas_contratada_activos() { // FALLA
     let mut streaming_rust = StreamingRust::new();
-    let suscripcion1 = Suscripcion::new(TipoSuscripcion::Clasic, 10.0, 1, "2024-06-01".to_string());
-    let suscripcion2 = Suscripcion::new(TipoSuscripcion::Super, 15.0, 1, "2024-06-01".to_string());
+    let suscripcion1 = Suscripcion::new(TipoSuscripcion::Clasic, Dinero::desde_unidades(10, Moneda::Ars), 1, "2024-06-01".to_string());
+    let suscripcion2 = Suscripcion::new(TipoSuscripcion::Super, Dinero::desde_unidades(15, Moneda::Ars), 1, "2024-06-01".to_string());
     let usuario1 = Usuario::new(1, suscripcion1.clone(), MedioDepago::TarjetaDeCred);
     let usuario2 = Usuario::new(2, suscripcion2.clone(), MedioDepago::MercadoPago);
     let usuario3 = Usuario::new(3, suscripcion1.clone(), MedioDepago::Cripto);
@@ -218,4 +286,10 @@ fn test_streaming_rust_suscripcion_mas_contratada_historica() {
     streaming_rust.historial_suscripciones.insert(TipoSuscripcion::Super, 25);
 
     assert_eq!(streaming_rust.suscripcion_mas_contratada_historica(), Some(TipoSuscripcion::Super));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_suscripcion_costo_total() {
+    let suscripcion = Suscripcion::new(TipoSuscripcion::Super, Dinero::new(1499, Moneda::Ars), 12, "2024-06-01".to_string());
+    assert_eq!(suscripcion.costo_total(), Ok(Dinero::new(17988, Moneda::Ars)));
+}
@@ -1,16 +1,25 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::dinero::{Dinero, ErrorDinero, Moneda, Porcentaje, Redondeo};
+use crate::variantes::{ConNombre, NombreDesconocido};
+#[cfg(test)]
+use crate::dinero::pesos;
+#[cfg(test)]
+use crate::variantes::comprobar_variantes;
 
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct Producto<'a> {
     nombre: &'a str,
     categoria: &'a str,
-    precio_base: u32, // para evitar tener que implementar manualmente hash o eq para el f64
+    precio_base: Dinero,
     descuento: Option<u32>,
 }
 
 impl<'a> Producto<'a> {
-    fn new(nombre:&'a str, categoria:&'a str, precio_base:u32, descuento:Option<u32>) -> Producto<'a> {
+    fn new(nombre:&'a str, categoria:&'a str, precio_base:Dinero, descuento:Option<u32>) -> Producto<'a> {
         Producto{nombre, categoria, precio_base, descuento}
     }
 }
@@ -44,6 +53,7 @@ impl<'a> Cliente<'a>{
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum MedioDePago {
     Credito,
     Debito,
@@ -51,6 +61,29 @@ enum MedioDePago {
     Efectivo,
 }
 
+impl ConNombre for MedioDePago {
+    const VARIANTES: &'static [(MedioDePago, &'static str)] = &[
+        (MedioDePago::Credito, "credito"),
+        (MedioDePago::Debito, "debito"),
+        (MedioDePago::Transferencia, "transferencia"),
+        (MedioDePago::Efectivo, "efectivo"),
+    ];
+}
+
+impl fmt::Display for MedioDePago {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.nombre())
+    }
+}
+
+impl FromStr for MedioDePago {
+    type Err = NombreDesconocido;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        MedioDePago::desde_nombre(texto)
+    }
+}
+
 struct Venta<'a>{
     fecha: &'a str,
     cliente: Cliente<'a>,
@@ -63,31 +96,40 @@ impl <'a> Venta<'a>{
         Venta {fecha, cliente, vendedor, medio_pago, productos,}
     }
 
-    fn calcular_precio_final(&self, sistema_ventas:&SistemaVentas<'a>, descuento_newsletter:u8) -> f64 {
-        let mut total = 0.0;
+    // una venta sin productos se informa como cero pesos
+    fn calcular_precio_final(&self, sistema_ventas:&SistemaVentas<'a>, descuento_newsletter:u8) -> Result<Dinero, ErrorDinero> {
+        let mut total: Option<Dinero> = None;
         for (producto, cantidad) in &self.productos {
-            let mut precio = producto.precio_base as f64 * *cantidad as f64;
+            let mut precio = producto.precio_base.multiplicar(*cantidad as i64)?;
             if let Some(descuento) = sistema_ventas.descuentos_categorias.get(producto.categoria) {
-                let descuento_decimal = *descuento as f64 / 100.0;
-                precio *= 1.0 - descuento_decimal;
+                precio = precio.aplicar_descuento(Porcentaje::new(*descuento as i64), sistema_ventas.redondeo)?;
             }
-            total += precio;
+            total = Some(match total {
+                Some(acumulado) => acumulado.sumar(&precio)?,
+                None => precio,
+            });
         }
+        let mut total = total.unwrap_or(Dinero::cero(Moneda::Ars));
         if self.cliente.mail.is_some() {
-            let descuento_newsletter_decimal = descuento_newsletter as f64 / 100.0;
-            total -= total * descuento_newsletter_decimal;
+            total = total.aplicar_descuento(Porcentaje::new(descuento_newsletter as i64), sistema_ventas.redondeo)?;
         }
-        total
+        Ok(total)
     }
 }
 
 struct SistemaVentas<'a> {
     historial_ventas: Vec<Venta<'a>>,
     descuentos_categorias: HashMap<&'a str, u8>,
+    redondeo: Redondeo, // para los centavos que dejan los descuentos
 }
 impl<'a> SistemaVentas<'a>{
     fn new(descuentos_categorias:HashMap<&'a str, u8>) -> SistemaVentas<'a>{
-        SistemaVentas{historial_ventas: Vec::new(), descuentos_categorias}
+        SistemaVentas{historial_ventas: Vec::new(), descuentos_categorias, redondeo: Redondeo::MitadHaciaArriba}
+    }
+
+    fn con_redondeo(mut self, redondeo: Redondeo) -> SistemaVentas<'a> {
+        self.redondeo = redondeo;
+        self
     }
 
     fn agregar_venta_al_historial(&mut self, venta:Venta<'a>) {
@@ -113,13 +155,19 @@ impl<'a> SistemaVentas<'a>{
     }
 }
 
+#[test]
+fn test_medio_de_pago_texto_ida_y_vuelta() {
+    comprobar_variantes::<MedioDePago>();
+    assert_eq!(" Debito".parse::<MedioDePago>(), Ok(MedioDePago::Debito));
+    assert_eq!("cheque".parse::<MedioDePago>(), Err(NombreDesconocido("cheque".to_string())));
+}
 
 #[test]
 fn  test_cargar_venta() {
     let mut sistema_ventas = SistemaVentas::new(HashMap::new());
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: 100, descuento: None };
+    let producto = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: pesos(100), descuento: None };
         
     let mut productos = HashMap::new();
     productos.insert(producto, 2);
@@ -136,8 +184,8 @@ fn test_reporte_ventas_por_categoria() {
     let mut sistema_ventas = SistemaVentas::new(HashMap::new());
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto1 = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: 100, descuento: None };
-    let producto2 = Producto { nombre: "ProdB", categoria: "Cat 2", precio_base: 150, descuento: None };
+    let producto1 = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: pesos(100), descuento: None };
+    let producto2 = Producto { nombre: "ProdB", categoria: "Cat 2", precio_base: pesos(150), descuento: None };
         
     let mut productos = HashMap::new();
     productos.insert(producto1, 2);
@@ -157,7 +205,7 @@ fn test_reporte_ventas_por_categoria() {
         let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
         let vendedor1 = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
         let vendedor2 = Vendedor::new("maria", "gomez", "otrolado", 98765432, 5678, 3, 250000.00);
-        let producto = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: 100, descuento: None };
+        let producto = Producto { nombre: "ProdA", categoria: "Cat 1", precio_base: pesos(100), descuento: None };
             
         let mut productos = HashMap::new();
         productos.insert(producto, 2);
@@ -178,21 +226,21 @@ fn test_reporte_ventas_por_categoria() {
 fn test_calcular_precio_final_sin_descuentos() {
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto = Producto::new("ProdA", "Cat 1", 100, None);
+    let producto = Producto::new("ProdA", "Cat 1", pesos(100), None);
 
     let mut productos = HashMap::new();
     productos.insert(producto.clone(), 2);
     
     let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
-    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(HashMap::new()), 0), 200.0);
+    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(HashMap::new()), 0), Ok(pesos(200)));
 }
 
 #[test]
 fn test_calcular_precio_final_con_descuentos() {
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto1 = Producto::new("ProdA", "Cat 1", 100, Some(10));
-    let producto2 = Producto::new("ProdB", "Cat 2", 150, Some(20));
+    let producto1 = Producto::new("ProdA", "Cat 1", pesos(100), Some(10));
+    let producto2 = Producto::new("ProdB", "Cat 2", pesos(150), Some(20));
 
     let mut descuentos_categorias = HashMap::new();
     descuentos_categorias.insert("Cat 1", 10);
@@ -203,28 +251,28 @@ fn test_calcular_precio_final_con_descuentos() {
     productos.insert(producto2.clone(), 1);
 
     let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
-    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(descuentos_categorias), 0), 300.0);
+    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(descuentos_categorias), 0), Ok(pesos(300)));
 }
 
 #[test]
 fn test_calcular_precio_final_con_descuento_newsletter() {
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, Some("fulano@example.com"));
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto = Producto::new("ProdA", "Cat 1", 100, None);
+    let producto = Producto::new("ProdA", "Cat 1", pesos(100), None);
 
     let mut productos = HashMap::new();
     productos.insert(producto.clone(), 2);
 
     let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
-    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(HashMap::new()), 10), 180.0);
+    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(HashMap::new()), 10), Ok(pesos(180)));
 }
 
 #[test]
 fn test_calcular_precio_final_con_descuentos_y_newsletter() {
     let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, Some("fulano@example.com"));
     let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
-    let producto1 = Producto::new("ProdA", "Cat 1", 100, Some(10));
-    let producto2 = Producto::new("ProdB", "Cat 2", 150, Some(20));
+    let producto1 = Producto::new("ProdA", "Cat 1", pesos(100), Some(10));
+    let producto2 = Producto::new("ProdB", "Cat 2", pesos(150), Some(20));
 
     let mut descuentos_categorias = HashMap::new();
     descuentos_categorias.insert("Cat 1", 10);
@@ -235,5 +283,38 @@ fn test_calcular_precio_final_con_descuentos_y_newsletter() {
     productos.insert(producto2.clone(), 1);
 
     let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
-    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(descuentos_categorias), 10), 270.0);
+    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(descuentos_categorias), 10), Ok(pesos(270)));
+}
+
+#[test]
+fn test_calcular_precio_final_con_monedas_distintas() {
+    let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
+    let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
+    let producto1 = Producto::new("ProdA", "Cat 1", pesos(100), None);
+    let producto2 = Producto::new("ProdB", "Cat 2", Dinero::desde_unidades(5, Moneda::Usd), None);
+
+    let mut productos = HashMap::new();
+    productos.insert(producto1, 1);
+    productos.insert(producto2, 1);
+
+    let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
+    assert_eq!(venta.calcular_precio_final(&SistemaVentas::new(HashMap::new()), 0), Err(ErrorDinero::MonedasDistintas));
+}
+
+#[test]
+fn test_calcular_precio_final_con_redondeo() {
+    let cliente = Cliente::new("fulano", "detal", "callefalsa", 16485678, None);
+    let vendedor = Vendedor::new("juan", "perez", "algunlado", 87654321, 1234, 5, 300000.00);
+    // el 10 % de 25 centavos son 2,5 centavos
+    let producto = Producto::new("Caramelo", "Kiosco", Dinero::new(25, Moneda::Ars), None);
+
+    let mut productos = HashMap::new();
+    productos.insert(producto, 1);
+    let venta = Venta::new("2023-06-05", cliente, vendedor, MedioDePago::Efectivo, productos);
+
+    let descuentos_categorias = HashMap::from([("Kiosco", 10)]);
+    let sistema = SistemaVentas::new(descuentos_categorias.clone());
+    assert_eq!(venta.calcular_precio_final(&sistema, 0), Ok(Dinero::new(22, Moneda::Ars)));
+    let sistema = SistemaVentas::new(descuentos_categorias).con_redondeo(Redondeo::Bancario);
+    assert_eq!(venta.calcular_precio_final(&sistema, 0), Ok(Dinero::new(23, Moneda::Ars)));
 }
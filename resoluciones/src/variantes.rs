@@ -0,0 +1,51 @@
+// Enums sin datos que se muestran y se leen por su nombre. Cada enum declara
+// una sola tabla con sus variantes y de ahi salen Display y FromStr, asi que
+// agregar una variante es agregar una fila a la tabla.
+
+#[cfg(test)]
+use std::fmt;
+#[cfg(test)]
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NombreDesconocido(pub String);
+
+pub trait ConNombre: Sized + Copy + PartialEq + 'static {
+    const VARIANTES: &'static [(Self, &'static str)];
+
+    // una variante que falta en la tabla es un error de programacion
+    fn nombre(&self) -> &'static str {
+        Self::VARIANTES.iter()
+            .find(|(variante, _)| variante == self)
+            .map(|(_, nombre)| *nombre)
+            .expect("variante sin fila en VARIANTES")
+    }
+
+    // sin distinguir mayusculas y sin los espacios de los bordes
+    fn desde_nombre(texto: &str) -> Result<Self, NombreDesconocido> {
+        let buscado = texto.trim().to_lowercase();
+        Self::VARIANTES.iter()
+            .find(|(_, nombre)| nombre.to_lowercase() == buscado)
+            .map(|(variante, _)| *variante)
+            .ok_or_else(|| NombreDesconocido(texto.to_string()))
+    }
+}
+
+// cada fila de la tabla se muestra con su nombre y se vuelve a leer igual, y
+// no hay variantes ni nombres repetidos
+#[cfg(test)]
+pub fn comprobar_variantes<T>()
+where
+    T: ConNombre + fmt::Debug + fmt::Display + FromStr<Err = NombreDesconocido>,
+{
+    for (i, (variante, nombre)) in T::VARIANTES.iter().enumerate() {
+        assert_eq!(variante.to_string(), *nombre);
+        assert_eq!(nombre.parse::<T>(), Ok(*variante));
+        assert_eq!(nombre.to_uppercase().parse::<T>(), Ok(*variante));
+        for (otra, otro_nombre) in &T::VARIANTES[i + 1..] {
+            assert_ne!(variante, otra);
+            assert_ne!(nombre.to_lowercase(), otro_nombre.to_lowercase());
+        }
+    }
+    assert_eq!("".parse::<T>(), Err(NombreDesconocido(String::new())));
+}